@@ -0,0 +1,45 @@
+use pretty_console::{Console, FuzzySelect, MultiSelect, Select};
+
+fn main() -> std::io::Result<()> {
+    let regions = ["us-east-1", "us-west-2", "eu-west-1", "ap-southeast-1"];
+
+    let Some(index) = Select::new("Pick a region").items(&regions).interact()? else {
+        Console::new("Cancelled").yellow().println();
+        return Ok(());
+    };
+    Console::new(format!("Deploying to {}", regions[index]))
+        .green()
+        .println();
+
+    let features = ["tls", "gzip", "http2", "metrics", "tracing", "cors"];
+    if let Some(chosen) = MultiSelect::new("Enable features")
+        .items(&features)
+        .defaults(&[true])
+        .max_length(4)
+        .interact()?
+    {
+        let names: Vec<&str> = chosen.iter().map(|&i| features[i]).collect();
+        Console::new(format!("Enabled: {}", names.join(", ")))
+            .green()
+            .println();
+    }
+
+    let branches = [
+        "main",
+        "develop",
+        "feature/login",
+        "feature/logout",
+        "fix/session-timeout",
+        "release/1.2",
+    ];
+    if let Some(index) = FuzzySelect::new("Checkout branch")
+        .items(&branches)
+        .interact()?
+    {
+        Console::new(format!("Switched to {}", branches[index]))
+            .green()
+            .println();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input};
+
+// `#[derive(PrettyDisplay)]` prints the struct name followed by one aligned
+// `field: value` line per field. Values are styled with
+// `#[style(fg = "cyan", bg = "#202020", bold, ...)]`, and fields can be
+// renamed or left out with `#[pretty(label = "...")]` and `#[pretty(skip)]`.
+#[proc_macro_derive(PrettyDisplay, attributes(style, pretty))]
+pub fn derive_pretty_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    label: String,
+    access: TokenStream2,
+    style: TokenStream2,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let title = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PrettyDisplay can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut fields = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let (label, access) = match (&field.ident, &data.fields) {
+            (Some(ident), Fields::Named(_)) => (ident.to_string(), quote!(#ident)),
+            _ => {
+                let index = syn::Index::from(index);
+                (index.index.to_string(), quote!(#index))
+            }
+        };
+        let mut field_def = Field {
+            label,
+            access,
+            style: quote!(::pretty_console::Style::new()),
+        };
+        let mut skip = false;
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("pretty") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("skip") {
+                        skip = true;
+                    } else if meta.path.is_ident("label") {
+                        field_def.label = meta.value()?.parse::<LitStr>()?.value();
+                    } else {
+                        return Err(meta.error("expected `skip` or `label = \"...\"`"));
+                    }
+                    Ok(())
+                })?;
+            } else if attr.path().is_ident("style") {
+                let mut style = field_def.style;
+                attr.parse_nested_meta(|meta| {
+                    let ident = meta
+                        .path
+                        .get_ident()
+                        .map(|ident| ident.to_string())
+                        .unwrap_or_default();
+                    style = match ident.as_str() {
+                        "fg" | "bg" => {
+                            let lit = meta.value()?.parse::<LitStr>()?;
+                            let color = parse_color(&lit)?;
+                            let method = syn::Ident::new(&ident, lit.span());
+                            quote!(#style.#method(#color))
+                        }
+                        "bold" | "dim" | "italic" | "underline" | "blink" | "reverse"
+                        | "hidden" | "strikethrough" | "overline" => {
+                            let method =
+                                syn::Ident::new(&ident, meta.path.get_ident().unwrap().span());
+                            quote!(#style.#method())
+                        }
+                        _ => return Err(meta.error("unknown style option")),
+                    };
+                    Ok(())
+                })?;
+                field_def.style = style;
+            }
+        }
+
+        if !skip {
+            fields.push(field_def);
+        }
+    }
+
+    let width = fields
+        .iter()
+        .map(|f| f.label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines = fields.iter().map(|field| {
+        let Field {
+            label,
+            access,
+            style,
+        } = field;
+        quote! {
+            ::pretty_console::write_pretty_field(f, #label, #width, &self.#access, #style)?;
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                ::pretty_console::write_pretty_title(f, #title)?;
+                #(#lines)*
+                Ok(())
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn pretty(&self) -> ::std::string::String {
+                ::std::string::ToString::to_string(self)
+            }
+        }
+    })
+}
+
+// Color names match the `Console` shortcuts, e.g. "bright_cyan", or a hex
+// value such as "#ff8800"
+fn parse_color(lit: &LitStr) -> syn::Result<TokenStream2> {
+    let value = lit.value();
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(quote!(::pretty_console::Color::RGB(#r, #g, #b))),
+            _ => Err(syn::Error::new_spanned(
+                lit,
+                "expected a color like \"#ff8800\"",
+            )),
+        };
+    }
+
+    let names = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    let (bright, base) = match value.strip_prefix("bright_") {
+        Some(base) => (8u8, base),
+        None => (0u8, value.as_str()),
+    };
+    match names.iter().position(|&name| name == base) {
+        Some(index) => {
+            let n = index as u8 + bright;
+            Ok(quote!(::pretty_console::Color::Named(#n)))
+        }
+        None => Err(syn::Error::new_spanned(lit, "unknown color name")),
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{Attribute, Style};
+
+// Accessible mode replaces colors with text that survives screen readers and
+// colorblindness: a style's label becomes a `[LABEL]` prefix and emphasis
+// becomes Markdown-like markers. Off by default; enabled with
+// `PRETTY_CONSOLE_ACCESSIBLE=1` or `set_accessible_mode(true)`.
+const UNSET: u8 = 0;
+const OFF: u8 = 1;
+const ON: u8 = 2;
+
+static MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+pub fn set_accessible_mode(enabled: bool) {
+    MODE.store(if enabled { ON } else { OFF }, Ordering::Relaxed);
+}
+
+pub fn accessible_mode() -> bool {
+    match MODE.load(Ordering::Relaxed) {
+        UNSET => {
+            let enabled = is_truthy(std::env::var("PRETTY_CONSOLE_ACCESSIBLE").ok().as_deref());
+            // Keep a value set through the API in the meantime
+            let _ = MODE.compare_exchange(
+                UNSET,
+                if enabled { ON } else { OFF },
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+            MODE.load(Ordering::Relaxed) == ON
+        }
+        mode => mode == ON,
+    }
+}
+
+fn is_truthy(value: Option<&str>) -> bool {
+    matches!(
+        value.map(|v| v.trim().to_ascii_lowercase()).as_deref(),
+        Some("1" | "true" | "yes" | "on")
+    )
+}
+
+pub(crate) fn render(style: &Style, text: &str) -> String {
+    let has = |attr| style.attributes.contains(&attr);
+    let mut rendered = String::new();
+
+    if let Some(label) = &style.label {
+        rendered.push_str(&format!("[{}] ", label));
+    }
+    if has(Attribute::Hidden) {
+        return rendered;
+    }
+
+    let markers: Vec<&str> = [
+        (Attribute::Bold, "**"),
+        (Attribute::Italic, "*"),
+        (Attribute::Underline, "_"),
+        (Attribute::Strikethrough, "~~"),
+    ]
+    .into_iter()
+    .filter(|&(attr, _)| has(attr))
+    .map(|(_, marker)| marker)
+    .collect();
+
+    // Markers hug the words, leaving surrounding whitespace outside
+    let trimmed = text.trim();
+    if markers.is_empty() || trimmed.is_empty() {
+        rendered.push_str(text);
+        return rendered;
+    }
+    let start = text.len() - text.trim_start().len();
+    rendered.push_str(&text[..start]);
+    for marker in &markers {
+        rendered.push_str(marker);
+    }
+    rendered.push_str(trimmed);
+    for marker in markers.iter().rev() {
+        rendered.push_str(marker);
+    }
+    rendered.push_str(&text[start + trimmed.len()..]);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn test_is_truthy() {
+        assert!(is_truthy(Some("1")));
+        assert!(is_truthy(Some(" TRUE ")));
+        assert!(!is_truthy(Some("0")));
+        assert!(!is_truthy(Some("")));
+        assert!(!is_truthy(None));
+    }
+
+    #[test]
+    fn test_render_markers() {
+        let style = Style::new().fg(Color::RED).bold().underline();
+        assert_eq!(render(&style, "  failed\n"), "  **_failed_**\n");
+        assert_eq!(render(&Style::new().italic(), "   "), "   ");
+        assert_eq!(render(&Style::new().strikethrough(), "old"), "~~old~~");
+        assert_eq!(render(&Style::new().fg(Color::BLUE), "plain"), "plain");
+    }
+
+    #[test]
+    fn test_render_label() {
+        let style = Style::new().fg(Color::RED).bold().label("ERROR");
+        assert_eq!(render(&style, "disk full"), "[ERROR] **disk full**");
+        assert_eq!(
+            render(&Style::new().hidden().label("SECRET"), "x"),
+            "[SECRET] "
+        );
+    }
+
+    #[test]
+    fn test_tone_presets_are_labeled() {
+        // Rendered directly, since switching the mode on would change every
+        // other test's output
+        let error = crate::Console::error("disk full");
+        assert_eq!(render(error.style(), error.text()), "[ERROR] ✗ disk full");
+        let warning = crate::Console::warning("slow disk");
+        assert!(render(warning.style(), warning.text()).starts_with("[WARNING] "));
+    }
+}
@@ -0,0 +1,96 @@
+use std::io::{self, Write};
+
+use crate::Block;
+use crate::cleanup::{CleanupGuard, register_sequence};
+use crate::grid::Grid;
+use crate::terminal::terminal_size;
+
+// The terminal's alternate screen, for full-screen output such as a
+// dashboard laid out with `Layout`. Each `draw` replaces the whole screen,
+// writing only the cells that changed since the last one, so redraws don't
+// flicker even over slow connections. Dropping it returns to the normal
+// screen, with the scrollback as it was.
+pub struct AltScreen<W: Write> {
+    out: W,
+    columns: usize,
+    rows: usize,
+    // What's on screen
+    frame: Option<Grid>,
+    // Leaves the alternate screen if the process is interrupted first
+    _cleanup: CleanupGuard,
+}
+
+impl<W: Write> AltScreen<W> {
+    pub fn enter(out: W) -> io::Result<Self> {
+        let (columns, rows) = terminal_size().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the alternate screen needs a terminal of known size",
+            )
+        })?;
+        Self::with_size(out, columns, rows)
+    }
+
+    fn with_size(mut out: W, columns: usize, rows: usize) -> io::Result<Self> {
+        write!(out, "\x1b[?1049h\x1b[?25l")?;
+        out.flush()?;
+        Ok(AltScreen {
+            out,
+            columns,
+            rows,
+            frame: None,
+            _cleanup: register_sequence("\x1b[?25h\x1b[?1049l"),
+        })
+    }
+
+    // Columns and rows to render for
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    // Records the terminal's new size, e.g. from a `ResizeWatcher`; render
+    // and draw again afterwards, which repaints the whole screen
+    pub fn resize(&mut self, columns: usize, rows: usize) {
+        self.columns = columns;
+        self.rows = rows;
+        self.frame = None;
+    }
+
+    // Draws `block` from the top-left corner, cut to the screen, with
+    // blanks everywhere around it
+    pub fn draw(&mut self, block: &Block) -> io::Result<()> {
+        let frame = Grid::from_block(block, self.columns, self.rows);
+        write!(self.out, "{}", frame.diff(self.frame.as_ref()))?;
+        self.frame = Some(frame);
+        self.out.flush()
+    }
+}
+
+impl<W: Write> Drop for AltScreen<W> {
+    fn drop(&mut self) {
+        let _ = write!(self.out, "\x1b[?25h\x1b[?1049l");
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    #[test]
+    fn test_draw_writes_changes() {
+        let out = CaptureWriter::new();
+        let mut screen = AltScreen::with_size(out.clone(), 4, 2).unwrap();
+        assert_eq!(screen.size(), (4, 2));
+        screen
+            .draw(&Block::from("first line\nsecond\nthird"))
+            .unwrap();
+        screen.draw(&Block::from("fist\nsecond")).unwrap();
+        drop(screen);
+        assert_eq!(
+            out.contents(),
+            "\x1b[?1049h\x1b[?25l\x1b[0m\x1b[2J\x1b[1;1Hfirs\x1b[2;1Hseco\x1b[1;3Hst\x1b[?25h\x1b[?1049l"
+        );
+    }
+}
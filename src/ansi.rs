@@ -0,0 +1,452 @@
+use crate::{Attribute, Color, Console, Style, StyledText, UnderlineStyle};
+
+// Interprets SGR escape sequences into styled segments. Other escape
+// sequences (cursor movement, OSC titles and links, ...) are dropped.
+pub fn parse_ansi(input: &str) -> StyledText {
+    let mut parser = Parser {
+        text: StyledText::new(),
+        style: Style::new(),
+        pending: String::new(),
+    };
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((_, ch)) = chars.next() {
+        if ch != '\x1b' {
+            parser.pending.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameters, then a final byte in 0x40..=0x7e
+            Some((start, '[')) => {
+                let start = start + 1;
+                let mut end = None;
+                for (i, c) in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        end = Some((i, c));
+                        break;
+                    }
+                }
+                if let Some((end, 'm')) = end {
+                    parser.apply_sgr(&input[start..end]);
+                }
+            }
+            // OSC: terminated by BEL or ESC \
+            Some((_, ']')) => {
+                while let Some((_, c)) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek().is_some_and(|&(_, c)| c == '\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    parser.flush();
+    parser.text
+}
+
+struct Parser {
+    text: StyledText,
+    style: Style,
+    pending: String,
+}
+
+impl Parser {
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let text = std::mem::take(&mut self.pending);
+        self.text
+            .push(Console::new_with_style(text, self.style.clone()));
+    }
+
+    fn apply_sgr(&mut self, params: &str) {
+        let next = apply_sgr(&self.style, params);
+        if !same_style(&next, &self.style) {
+            self.flush();
+            self.style = next;
+        }
+    }
+}
+
+// `style` with the SGR parameters `params` (what's between `ESC [` and `m`)
+// applied
+pub(crate) fn apply_sgr(style: &Style, params: &str) -> Style {
+    let mut next = style.clone();
+    let groups: Vec<&str> = params.split(';').collect();
+
+    let mut i = 0;
+    while i < groups.len() {
+        let group = groups[i];
+        i += 1;
+
+        // Colon-separated sub-parameters are self-contained, e.g. `38:2::255:0:0`
+        if group.contains(':') {
+            let sub: Vec<Option<u8>> = group.split(':').map(param).collect();
+            match sub[0] {
+                Some(code @ (38 | 48 | 58)) => {
+                    let color = match sub[1..] {
+                        [Some(5), Some(n)] => Some(Color::Named(n)),
+                        [Some(2), Some(r), Some(g), Some(b)]
+                        | [Some(2), _, Some(r), Some(g), Some(b), ..] => Some(Color::RGB(r, g, b)),
+                        _ => None,
+                    };
+                    set_color(&mut next, code, color);
+                }
+                Some(4) => match sub.get(1).copied().flatten() {
+                    Some(0) => apply_code(&mut next, 24),
+                    Some(1) => next = next.underline_style(UnderlineStyle::Single),
+                    Some(2) => next = next.underline_style(UnderlineStyle::Double),
+                    Some(3) => next = next.underline_style(UnderlineStyle::Curly),
+                    Some(4) => next = next.underline_style(UnderlineStyle::Dotted),
+                    Some(5) => next = next.underline_style(UnderlineStyle::Dashed),
+                    _ => apply_code(&mut next, 4),
+                },
+                Some(code) => apply_code(&mut next, code),
+                None => {}
+            }
+            continue;
+        }
+
+        match param(group) {
+            Some(code @ (38 | 48 | 58)) => {
+                let arg = |n: usize| groups.get(i + n).copied().and_then(param);
+                match arg(0) {
+                    Some(5) => {
+                        set_color(&mut next, code, arg(1).map(Color::Named));
+                        i += 2;
+                    }
+                    Some(2) => {
+                        let color = match (arg(1), arg(2), arg(3)) {
+                            (Some(r), Some(g), Some(b)) => Some(Color::RGB(r, g, b)),
+                            _ => None,
+                        };
+                        set_color(&mut next, code, color);
+                        i += 4;
+                    }
+                    _ => i += 1,
+                }
+            }
+            Some(code) => apply_code(&mut next, code),
+            None => {}
+        }
+    }
+    next
+}
+
+// Missing parameters default to 0; out-of-range values are ignored
+fn param(text: &str) -> Option<u8> {
+    if text.is_empty() {
+        Some(0)
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn apply_code(style: &mut Style, code: u8) {
+    match code {
+        0 => *style = Style::new(),
+        1 => add(style, Attribute::Bold),
+        2 => add(style, Attribute::Dim),
+        3 => add(style, Attribute::Italic),
+        4 => {
+            add(style, Attribute::Underline);
+            style.underline_style = None;
+        }
+        5 | 6 => add(style, Attribute::Blink),
+        7 => add(style, Attribute::Reverse),
+        8 => add(style, Attribute::Hidden),
+        9 => add(style, Attribute::Strikethrough),
+        22 => remove(style, &[Attribute::Bold, Attribute::Dim]),
+        23 => remove(style, &[Attribute::Italic]),
+        24 => {
+            remove(style, &[Attribute::Underline]);
+            style.underline_style = None;
+        }
+        25 => remove(style, &[Attribute::Blink]),
+        27 => remove(style, &[Attribute::Reverse]),
+        28 => remove(style, &[Attribute::Hidden]),
+        29 => remove(style, &[Attribute::Strikethrough]),
+        51 => add(style, Attribute::Framed),
+        52 => add(style, Attribute::Encircled),
+        53 => add(style, Attribute::Overline),
+        54 => remove(style, &[Attribute::Framed, Attribute::Encircled]),
+        55 => remove(style, &[Attribute::Overline]),
+        73 => {
+            remove(style, &[Attribute::Subscript]);
+            add(style, Attribute::Superscript);
+        }
+        74 => {
+            remove(style, &[Attribute::Superscript]);
+            add(style, Attribute::Subscript);
+        }
+        75 => remove(style, &[Attribute::Superscript, Attribute::Subscript]),
+        30..=37 => style.foreground = Some(Color::Named(code - 30)),
+        39 => style.foreground = None,
+        40..=47 => style.background = Some(Color::Named(code - 40)),
+        49 => style.background = None,
+        59 => style.underline_color = None,
+        90..=97 => style.foreground = Some(Color::Named(code - 90 + 8)),
+        100..=107 => style.background = Some(Color::Named(code - 100 + 8)),
+        _ => {}
+    }
+}
+
+fn set_color(style: &mut Style, code: u8, color: Option<Color>) {
+    if let Some(color) = color {
+        match code {
+            38 => style.foreground = Some(color),
+            48 => style.background = Some(color),
+            _ => style.underline_color = Some(color),
+        }
+    }
+}
+
+// Removes escape sequences from a byte stream. Sequences may be split across
+// calls, so state is carried over between them.
+#[derive(Debug, Default)]
+pub(crate) struct AnsiStripper {
+    state: StripState,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum StripState {
+    #[default]
+    Text,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+impl AnsiStripper {
+    pub(crate) fn strip(&mut self, bytes: &[u8], out: &mut Vec<u8>) {
+        for &byte in bytes {
+            self.state = match (self.state, byte) {
+                (StripState::Text, 0x1b) => StripState::Escape,
+                (StripState::Text, _) => {
+                    out.push(byte);
+                    StripState::Text
+                }
+                (StripState::Escape, b'[') => StripState::Csi,
+                (StripState::Escape, b']') => StripState::Osc,
+                // Two-byte sequences such as `ESC 7`
+                (StripState::Escape, _) => StripState::Text,
+                (StripState::Csi, 0x40..=0x7e) => StripState::Text,
+                (StripState::Csi, _) => StripState::Csi,
+                (StripState::Osc, 0x07) => StripState::Text,
+                (StripState::Osc, 0x1b) => StripState::OscEscape,
+                (StripState::Osc, _) => StripState::Osc,
+                (StripState::OscEscape, b'\\') => StripState::Text,
+                (StripState::OscEscape, _) => StripState::Osc,
+            };
+        }
+    }
+}
+
+fn add(style: &mut Style, attr: Attribute) {
+    if !style.attributes.contains(&attr) {
+        style.attributes.push(attr);
+    }
+}
+
+fn remove(style: &mut Style, attrs: &[Attribute]) {
+    style.attributes.retain(|a| !attrs.contains(a));
+}
+
+pub(crate) fn same_style(a: &Style, b: &Style) -> bool {
+    a.foreground == b.foreground
+        && a.background == b.background
+        && crate::attribute_set(&a.attributes) == crate::attribute_set(&b.attributes)
+        && a.underline_style == b.underline_style
+        && a.underline_color == b.underline_color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Segment = (String, Option<Color>, Option<Color>, Vec<Attribute>);
+
+    fn segments(input: &str) -> Vec<Segment> {
+        parse_ansi(input)
+            .segments()
+            .iter()
+            .map(|s| {
+                (
+                    s.text.clone(),
+                    s.style.foreground,
+                    s.style.background,
+                    s.style.attributes.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            segments("hello"),
+            vec![("hello".into(), None, None, vec![])]
+        );
+        assert!(parse_ansi("").segments().is_empty());
+    }
+
+    #[test]
+    fn test_basic_colors_and_reset() {
+        assert_eq!(
+            segments("\x1b[31merror\x1b[0m: oops"),
+            vec![
+                ("error".into(), Some(Color::RED), None, vec![]),
+                (": oops".into(), None, None, vec![]),
+            ]
+        );
+        assert_eq!(
+            segments("\x1b[1;94;42mhi\x1b[m"),
+            vec![(
+                "hi".into(),
+                Some(Color::BRIGHT_BLUE),
+                Some(Color::GREEN),
+                vec![Attribute::Bold]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_extended_colors() {
+        assert_eq!(
+            segments("\x1b[38;5;208;48;2;1;2;3mx"),
+            vec![(
+                "x".into(),
+                Some(Color::Named(208)),
+                Some(Color::RGB(1, 2, 3)),
+                vec![]
+            )]
+        );
+        assert_eq!(
+            segments("\x1b[38:2::255:0:0my"),
+            vec![("y".into(), Some(Color::RGB(255, 0, 0)), None, vec![])]
+        );
+        assert_eq!(
+            segments("\x1b[38:2:10:20:30mz"),
+            vec![("z".into(), Some(Color::RGB(10, 20, 30)), None, vec![])]
+        );
+    }
+
+    #[test]
+    fn test_attribute_off_codes() {
+        assert_eq!(
+            segments("\x1b[1;3;4mA\x1b[22;24mB"),
+            vec![
+                (
+                    "A".into(),
+                    None,
+                    None,
+                    vec![Attribute::Bold, Attribute::Italic, Attribute::Underline]
+                ),
+                ("B".into(), None, None, vec![Attribute::Italic]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_underline_style_and_color() {
+        let text = parse_ansi("\x1b[4:3;58;5;1mcurly\x1b[4m plain\x1b[59;24m none");
+        let styles: Vec<_> = text
+            .segments()
+            .iter()
+            .map(|s| (s.style.underline_style, s.style.underline_color))
+            .collect();
+        assert_eq!(
+            styles,
+            vec![
+                (Some(UnderlineStyle::Curly), Some(Color::RED)),
+                (None, Some(Color::RED)),
+                (None, None),
+            ]
+        );
+        assert!(
+            !text.segments()[2]
+                .style
+                .attributes
+                .contains(&Attribute::Underline)
+        );
+    }
+
+    #[test]
+    fn test_extra_attributes() {
+        assert_eq!(
+            segments("\x1b[53;51mA\x1b[54;73mB\x1b[74;55mC\x1b[75mD"),
+            vec![
+                (
+                    "A".into(),
+                    None,
+                    None,
+                    vec![Attribute::Overline, Attribute::Framed]
+                ),
+                (
+                    "B".into(),
+                    None,
+                    None,
+                    vec![Attribute::Overline, Attribute::Superscript]
+                ),
+                ("C".into(), None, None, vec![Attribute::Subscript]),
+                ("D".into(), None, None, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redundant_codes_do_not_split_segments() {
+        assert_eq!(
+            segments("\x1b[31mab\x1b[31mcd"),
+            vec![("abcd".into(), Some(Color::RED), None, vec![])]
+        );
+    }
+
+    #[test]
+    fn test_non_sgr_sequences_are_dropped() {
+        let input = "\x1b]0;title\x07\x1b[2Kclear\x1b[1Aup\x1b]8;;https://x\x1b\\link";
+        assert_eq!(parse_ansi(input).plain(), "clearuplink");
+    }
+
+    #[test]
+    fn test_stripper_handles_split_sequences() {
+        let mut stripper = AnsiStripper::default();
+        let mut out = Vec::new();
+        stripper.strip(b"a\x1b[3", &mut out);
+        stripper.strip(b"1mb\x1b]0;ti", &mut out);
+        stripper.strip(b"tle\x1b", &mut out);
+        stripper.strip(b"\\c\x1b[0m", &mut out);
+        assert_eq!(out, b"abc");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let console = Console::new("styled")
+            .fg_rgb(1, 2, 3)
+            .on_blue()
+            .bold()
+            .italic();
+        let parsed = parse_ansi(&console.to_string());
+
+        #[cfg(not(feature = "no-color"))]
+        {
+            let segment = &parsed.segments()[0];
+            assert_eq!(segment.style.foreground, Some(Color::RGB(1, 2, 3)));
+            assert_eq!(segment.style.background, Some(Color::BLUE));
+            assert_eq!(
+                segment.style.attributes,
+                vec![Attribute::Bold, Attribute::Italic]
+            );
+        }
+        assert_eq!(parsed.plain(), "styled");
+    }
+}
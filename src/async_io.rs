@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::task::JoinHandle;
+use tokio::time::{MissedTickBehavior, interval};
+
+use crate::{Console, ProgressBar, Spinner, StyledText};
+
+impl Console {
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        writer.write_all(self.to_string().as_bytes()).await
+    }
+}
+
+impl StyledText {
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        writer.write_all(self.to_string().as_bytes()).await
+    }
+}
+
+impl ProgressBar {
+    // Like `enable_steady_tick`, but ticks from a task on the current tokio
+    // runtime instead of a thread. The task ends once the bar is finished.
+    // Must be called from within a runtime.
+    pub fn enable_steady_tick_async(&self, every: Duration) -> JoinHandle<()> {
+        let bar = self.clone();
+        tokio::spawn(async move {
+            let mut ticks = interval(every);
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                if bar.is_finished() {
+                    return;
+                }
+                bar.tick();
+            }
+        })
+    }
+}
+
+impl Spinner {
+    // Keeps the spinner moving from a task on the current tokio runtime
+    // until it finishes; for spinners made with `with_writer`, which don't
+    // tick by themselves
+    pub fn enable_steady_tick_async(&self, every: Duration) -> JoinHandle<()> {
+        self.bar.enable_steady_tick_async(every)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    // Writing into a Vec never waits, so a single poll completes the future
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        match pin!(future).poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future did not complete"),
+        }
+    }
+
+    #[test]
+    fn test_console_write_to_async() {
+        let mut buffer = Vec::new();
+        block_on(Console::new("async").green().write_to_async(&mut buffer)).unwrap();
+
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(buffer, b"\x1b[38;5;2masync\x1b[0m");
+        #[cfg(feature = "no-color")]
+        assert_eq!(buffer, b"async");
+    }
+
+    #[test]
+    fn test_steady_tick_async_animates_until_finished() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let out = CaptureWriter::new();
+        let spinner = Spinner::with_writer(out.clone(), "Waiting");
+        let drawn = out.contents();
+        runtime.block_on(async {
+            let task = spinner.enable_steady_tick_async(Duration::from_millis(60));
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            assert_ne!(out.contents(), drawn);
+            spinner.finish_ok("Done");
+            task.await.unwrap();
+        });
+        assert!(strip_ansi(&out.contents()).ends_with("✓ Done\n"));
+    }
+
+    #[test]
+    fn test_styled_text_write_to_async() {
+        let text = StyledText::new()
+            .then(Console::new("a").bold())
+            .then(Console::new("b"));
+        let mut buffer = Vec::new();
+        block_on(text.write_to_async(&mut buffer)).unwrap();
+        assert_eq!(buffer, text.to_string().as_bytes());
+    }
+}
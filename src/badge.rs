@@ -0,0 +1,168 @@
+use std::fmt::{self, Display};
+
+use crate::theme::{Tone, theme};
+use crate::{Color, Console, Style};
+
+// A padded label on a colored background, e.g. ` PASS ` on green. The text
+// color is picked for contrast against the background unless set explicitly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Badge {
+    text: String,
+    background: Color,
+    foreground: Option<Color>,
+    bold: bool,
+    padding: usize,
+}
+
+macro_rules! background_methods {
+    ($($name:ident => $color:ident;)*) => {
+        $(
+            pub fn $name(self) -> Self {
+                self.bg(Color::$color)
+            }
+        )*
+    };
+}
+
+impl Badge {
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        Badge {
+            text: text.into(),
+            background: Color::BRIGHT_BLACK,
+            foreground: None,
+            bold: false,
+            padding: 1,
+        }
+    }
+
+    // Uses the theme's color for `tone` as the background
+    pub fn tone<T: Into<String>>(tone: Tone, text: T) -> Self {
+        let badge = Badge::new(text).bold();
+        match theme().style(tone).foreground {
+            Some(color) => badge.bg(color),
+            None => badge,
+        }
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.bg(Color::RGB(r, g, b))
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    // Spaces on each side of the text, defaults to 1
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    background_methods! {
+        black => BLACK;
+        red => RED;
+        green => GREEN;
+        yellow => YELLOW;
+        blue => BLUE;
+        magenta => MAGENTA;
+        cyan => CYAN;
+        white => WHITE;
+        gray => BRIGHT_BLACK;
+    }
+
+    pub fn to_console(&self) -> Console {
+        let pad = " ".repeat(self.padding);
+        let foreground = self
+            .foreground
+            .unwrap_or_else(|| contrasting_foreground(self.background));
+        let mut style = Style::new().fg(foreground).bg(self.background);
+        if self.bold {
+            style = style.bold();
+        }
+        Console::new_with_style(format!("{}{}{}", pad, self.text, pad), style)
+    }
+}
+
+impl Display for Badge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.to_console().fmt(f)
+    }
+}
+
+impl From<Badge> for Console {
+    fn from(badge: Badge) -> Console {
+        badge.to_console()
+    }
+}
+
+// Black or white, whichever has the higher WCAG contrast ratio
+pub(crate) fn contrasting_foreground(background: Color) -> Color {
+    let luminance = relative_luminance(background);
+    let against_black = (luminance + 0.05) / 0.05;
+    let against_white = 1.05 / (luminance + 0.05);
+    if against_black >= against_white {
+        Color::BLACK
+    } else {
+        Color::BRIGHT_WHITE
+    }
+}
+
+fn relative_luminance(color: Color) -> f64 {
+    let (r, g, b) = color.to_rgb();
+    let channel = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrasting_foreground() {
+        assert_eq!(contrasting_foreground(Color::GREEN), Color::BLACK);
+        assert_eq!(contrasting_foreground(Color::YELLOW), Color::BLACK);
+        assert_eq!(contrasting_foreground(Color::BLUE), Color::BRIGHT_WHITE);
+        assert_eq!(contrasting_foreground(Color::RED), Color::BRIGHT_WHITE);
+        assert_eq!(
+            contrasting_foreground(Color::RGB(20, 20, 20)),
+            Color::BRIGHT_WHITE
+        );
+    }
+
+    #[test]
+    fn test_badge_console() {
+        let badge = Badge::new("PASS").green().bold().to_console();
+        assert_eq!(badge.text, " PASS ");
+        assert_eq!(badge.style.background, Some(Color::GREEN));
+        assert_eq!(badge.style.foreground, Some(Color::BLACK));
+
+        let badge = Badge::new("v1.2.3").padding(0).fg(Color::CYAN).to_console();
+        assert_eq!(badge.text, "v1.2.3");
+        assert_eq!(badge.style.foreground, Some(Color::CYAN));
+        assert_eq!(badge.style.background, Some(Color::BRIGHT_BLACK));
+    }
+
+    #[test]
+    fn test_tone_badge() {
+        let badge = Badge::tone(Tone::Error, "FAIL").to_console();
+        assert_eq!(badge.style.background, Some(Color::RED));
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::{self, Display};
+
+use crate::Console;
+use crate::symbols::symbols;
+use crate::terminal::terminal_width;
+use crate::theme::{Tone, theme};
+use crate::unicode;
+use crate::wrap::wrap_words;
+
+// A boxed startup header with the program name, version, a short
+// description and labeled links. The box shrinks to its content and never
+// grows past the terminal width; the description wraps and long links are
+// truncated to fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Banner {
+    name: String,
+    version: String,
+    description: Option<String>,
+    links: Vec<(String, String)>,
+    width: Option<usize>,
+}
+
+impl Banner {
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        Banner {
+            name: name.into(),
+            version: version.into(),
+            description: None,
+            links: Vec::new(),
+            width: None,
+        }
+    }
+
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn link<L: Into<String>, U: Into<String>>(mut self, label: L, url: U) -> Self {
+        self.links.push((label.into(), url.into()));
+        self
+    }
+
+    pub fn links<I, L, U>(mut self, links: I) -> Self
+    where
+        I: IntoIterator<Item = (L, U)>,
+        L: Into<String>,
+        U: Into<String>,
+    {
+        self.links
+            .extend(links.into_iter().map(|(l, u)| (l.into(), u.into())));
+        self
+    }
+
+    // Maximum total width including the border, defaults to the terminal width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn print(&self) {
+        crate::ignore_broken_pipe(crate::group::write_stdout(&self.to_string()));
+    }
+}
+
+impl Display for Banner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let border = theme.style(Tone::Muted).clone();
+        let label_width = self
+            .links
+            .iter()
+            .map(|(label, _)| unicode::width(label))
+            .max()
+            .unwrap_or(0);
+
+        let header_width = unicode::width(&self.name) + 1 + unicode::width(&self.version);
+        let natural = [
+            header_width,
+            self.description
+                .as_deref()
+                .map_or(0, |d| d.lines().map(unicode::width).max().unwrap_or(0)),
+        ]
+        .into_iter()
+        .chain(
+            self.links
+                .iter()
+                .map(|(_, url)| label_width + 2 + unicode::width(url)),
+        )
+        .max()
+        .unwrap_or(0);
+        let limit = self.width.unwrap_or_else(terminal_width);
+        let inner = natural.min(limit.saturating_sub(4)).max(1);
+
+        // Each row is (visible width, rendered text)
+        let mut rows: Vec<(usize, String)> = Vec::new();
+        let name = truncate(&self.name, inner);
+        let version = truncate(
+            &self.version,
+            inner.saturating_sub(unicode::width(&name) + 1),
+        );
+        rows.push((
+            unicode::width(&name) + usize::from(!version.is_empty()) + unicode::width(&version),
+            format!(
+                "{}{}{}",
+                Console::new_with_style(name, theme.style(Tone::Info).clone().bold()),
+                if version.is_empty() { "" } else { " " },
+                Console::new_with_style(version, border.clone()),
+            ),
+        ));
+        if let Some(description) = &self.description {
+            for line in wrap_words(description, inner) {
+                rows.push((unicode::width(&line), line));
+            }
+        }
+        if !self.links.is_empty() {
+            rows.push((0, String::new()));
+        }
+        for (label, url) in &self.links {
+            let label = truncate(
+                &format!(
+                    "{}{}",
+                    label,
+                    " ".repeat(label_width - unicode::width(label))
+                ),
+                inner,
+            );
+            let url = truncate(url, inner.saturating_sub(unicode::width(&label) + 2));
+            rows.push((
+                (unicode::width(&label) + 2 + unicode::width(&url)).min(inner),
+                format!(
+                    "{}  {}",
+                    Console::new_with_style(label, border.clone()),
+                    Console::new_with_style(url, theme.style(Tone::Info).clone().underline()),
+                ),
+            ));
+        }
+
+        let chars = symbols().border;
+        let edge = chars.horizontal.repeat(inner + 2);
+        writeln!(
+            f,
+            "{}",
+            Console::new_with_style(
+                format!("{}{}{}", chars.top_left, edge, chars.top_right),
+                border.clone()
+            )
+        )?;
+        let side = Console::new_with_style(chars.vertical, border.clone());
+        for (width, text) in rows {
+            let padding = " ".repeat(inner.saturating_sub(width));
+            writeln!(f, "{} {}{} {}", side, text, padding, side)?;
+        }
+        writeln!(
+            f,
+            "{}",
+            Console::new_with_style(
+                format!("{}{}{}", chars.bottom_left, edge, chars.bottom_right),
+                border
+            )
+        )
+    }
+}
+
+// Shortens text to at most `width` columns, ending in an ellipsis when cut
+fn truncate(text: &str, width: usize) -> String {
+    unicode::truncate(text, width, symbols().ellipsis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    fn banner() -> Banner {
+        Banner::new("mytool", "v1.2.3")
+            .description("Fast file syncing")
+            .link("Docs", "https://docs.rs/mytool")
+            .links([("Issues", "https://github.com/me/mytool")])
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("abcdef", 6), "abcdef");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("abcdef", 0), "");
+    }
+
+    #[test]
+    fn test_banner_fits_content() {
+        assert_eq!(
+            strip_ansi(&banner().width(80).to_string()),
+            "╭──────────────────────────────────────╮\n\
+             │ mytool v1.2.3                        │\n\
+             │ Fast file syncing                    │\n\
+             │                                      │\n\
+             │ Docs    https://docs.rs/mytool       │\n\
+             │ Issues  https://github.com/me/mytool │\n\
+             ╰──────────────────────────────────────╯\n"
+        );
+    }
+
+    #[test]
+    fn test_banner_shrinks_to_width() {
+        assert_eq!(
+            strip_ansi(&banner().width(24).to_string()),
+            "╭──────────────────────╮\n\
+             │ mytool v1.2.3        │\n\
+             │ Fast file syncing    │\n\
+             │                      │\n\
+             │ Docs    https://doc… │\n\
+             │ Issues  https://git… │\n\
+             ╰──────────────────────╯\n"
+        );
+    }
+
+    #[test]
+    fn test_banner_render() {
+        insta::assert_yaml_snapshot!(banner().width(80).to_string());
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::io;
+
+use crate::symbols::symbols;
+use crate::unicode;
+use crate::{ColorScale, Console, Style};
+
+// Renders short strings in large block-character glyphs, either with the
+// built-in 5-row font or a FIGlet font. Glyphs are placed side by side
+// without FIGlet's kerning or smushing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BigText {
+    text: String,
+    font: Option<FigletFont>,
+    fill: Fill,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Fill {
+    Solid(Style),
+    Gradient(ColorScale),
+}
+
+impl BigText {
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        BigText {
+            text: text.into(),
+            font: None,
+            fill: Fill::Solid(Style::new()),
+        }
+    }
+
+    pub fn font(mut self, font: FigletFont) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.fill = Fill::Solid(style);
+        self
+    }
+
+    // Colors the glyphs left to right along the scale
+    pub fn gradient(mut self, scale: ColorScale) -> Self {
+        self.fill = Fill::Gradient(scale);
+        self
+    }
+
+    // The unstyled rows of the rendered text
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (i, text_line) in self.text.lines().enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            lines.extend(match &self.font {
+                Some(font) => font.render_line(text_line),
+                None => builtin_line(text_line),
+            });
+        }
+        lines
+    }
+}
+
+impl Display for BigText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self.lines();
+        let width = lines.iter().map(|l| unicode::width(l)).max().unwrap_or(0);
+
+        for line in &lines {
+            match &self.fill {
+                Fill::Solid(style) => writeln!(
+                    f,
+                    "{}",
+                    Console::new_with_style(line.trim_end(), style.clone())
+                )?,
+                Fill::Gradient(scale) => {
+                    for (column, ch) in line.trim_end().chars().enumerate() {
+                        if ch == ' ' {
+                            f.write_str(" ")?;
+                        } else {
+                            let position = column as f64 / width.saturating_sub(1).max(1) as f64;
+                            let style = Style::new().fg(scale.at(position));
+                            write!(f, "{}", Console::new_with_style(ch.to_string(), style))?;
+                        }
+                    }
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// A font in the FIGlet `.flf` format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FigletFont {
+    height: usize,
+    glyphs: HashMap<char, Vec<String>>,
+}
+
+impl FigletFont {
+    pub fn parse(source: &str) -> io::Result<Self> {
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut lines = source.lines();
+        let header = lines.next().ok_or_else(|| invalid("empty font file"))?;
+        let rest = header
+            .strip_prefix("flf2a")
+            .ok_or_else(|| invalid("missing flf2a signature"))?;
+        let hardblank = rest
+            .chars()
+            .next()
+            .ok_or_else(|| invalid("missing hardblank"))?;
+        let fields: Vec<usize> = rest[hardblank.len_utf8()..]
+            .split_whitespace()
+            .map_while(|field| field.parse().ok())
+            .collect();
+        let (height, comments) = match fields.as_slice() {
+            [height, _, _, _, comments, ..] if *height > 0 => (*height, *comments),
+            _ => return Err(invalid("invalid font header")),
+        };
+
+        let mut lines = lines.skip(comments);
+        let mut glyphs = HashMap::new();
+        // Required characters are ASCII 32..=126 in order; a truncated file
+        // simply has fewer glyphs
+        'chars: for code in 32u8..=126 {
+            let mut rows = Vec::with_capacity(height);
+            for _ in 0..height {
+                let Some(line) = lines.next() else {
+                    break 'chars;
+                };
+                let endmark = line.chars().last().unwrap_or('@');
+                let row = line.trim_end_matches(endmark).replace(hardblank, " ");
+                rows.push(row);
+            }
+            glyphs.insert(code as char, rows);
+        }
+
+        Ok(FigletFont { height, glyphs })
+    }
+
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn render_line(&self, text: &str) -> Vec<String> {
+        let mut rows = vec![String::new(); self.height];
+        for ch in text.chars() {
+            let Some(glyph) = self.glyphs.get(&ch).or_else(|| self.glyphs.get(&'?')) else {
+                continue;
+            };
+            let width = glyph.iter().map(|r| unicode::width(r)).max().unwrap_or(0);
+            for (i, row) in rows.iter_mut().enumerate() {
+                let part = glyph.get(i).map(String::as_str).unwrap_or("");
+                row.push_str(part);
+                row.push_str(&" ".repeat(width - unicode::width(part)));
+            }
+        }
+        rows
+    }
+}
+
+fn builtin_line(text: &str) -> Vec<String> {
+    let block = symbols().block;
+    let mut rows = vec![String::new(); 5];
+    for (i, ch) in text.chars().enumerate() {
+        let glyph =
+            builtin_glyph(ch.to_ascii_uppercase()).unwrap_or_else(|| builtin_glyph('?').unwrap());
+        for (row, pattern) in rows.iter_mut().zip(glyph) {
+            if i > 0 {
+                row.push(' ');
+            }
+            row.extend(pattern.chars().map(|c| if c == '#' { block } else { ' ' }));
+        }
+    }
+    rows
+}
+
+fn builtin_glyph(ch: char) -> Option<[&'static str; 5]> {
+    Some(match ch {
+        'A' => [".###.", "#...#", "#####", "#...#", "#...#"],
+        'B' => ["####.", "#...#", "####.", "#...#", "####."],
+        'C' => [".####", "#....", "#....", "#....", ".####"],
+        'D' => ["####.", "#...#", "#...#", "#...#", "####."],
+        'E' => ["#####", "#....", "####.", "#....", "#####"],
+        'F' => ["#####", "#....", "####.", "#....", "#...."],
+        'G' => [".####", "#....", "#..##", "#...#", ".###."],
+        'H' => ["#...#", "#...#", "#####", "#...#", "#...#"],
+        'I' => ["###", ".#.", ".#.", ".#.", "###"],
+        'J' => ["..###", "...#.", "...#.", "#..#.", ".##.."],
+        'K' => ["#...#", "#..#.", "###..", "#..#.", "#...#"],
+        'L' => ["#....", "#....", "#....", "#....", "#####"],
+        'M' => ["#...#", "##.##", "#.#.#", "#...#", "#...#"],
+        'N' => ["#...#", "##..#", "#.#.#", "#..##", "#...#"],
+        'O' => [".###.", "#...#", "#...#", "#...#", ".###."],
+        'P' => ["####.", "#...#", "####.", "#....", "#...."],
+        'Q' => [".###.", "#...#", "#.#.#", "#..#.", ".##.#"],
+        'R' => ["####.", "#...#", "####.", "#..#.", "#...#"],
+        'S' => [".####", "#....", ".###.", "....#", "####."],
+        'T' => ["#####", "..#..", "..#..", "..#..", "..#.."],
+        'U' => ["#...#", "#...#", "#...#", "#...#", ".###."],
+        'V' => ["#...#", "#...#", "#...#", ".#.#.", "..#.."],
+        'W' => ["#...#", "#...#", "#.#.#", "##.##", "#...#"],
+        'X' => ["#...#", ".#.#.", "..#..", ".#.#.", "#...#"],
+        'Y' => ["#...#", ".#.#.", "..#..", "..#..", "..#.."],
+        'Z' => ["#####", "...#.", "..#..", ".#...", "#####"],
+        '0' => [".###.", "#..##", "#.#.#", "##..#", ".###."],
+        '1' => [".#.", "##.", ".#.", ".#.", "###"],
+        '2' => ["####.", "....#", ".###.", "#....", "#####"],
+        '3' => ["####.", "....#", ".###.", "....#", "####."],
+        '4' => ["#...#", "#...#", "#####", "....#", "....#"],
+        '5' => ["#####", "#....", "####.", "....#", "####."],
+        '6' => [".###.", "#....", "####.", "#...#", ".###."],
+        '7' => ["#####", "....#", "...#.", "..#..", "..#.."],
+        '8' => [".###.", "#...#", ".###.", "#...#", ".###."],
+        '9' => [".###.", "#...#", ".####", "....#", ".###."],
+        ' ' => ["...", "...", "...", "...", "..."],
+        '!' => ["#", "#", "#", ".", "#"],
+        '?' => ["###.", "...#", ".##.", "....", ".#.."],
+        '.' => [".", ".", ".", ".", "#"],
+        ',' => ["..", "..", "..", ".#", "#."],
+        ':' => [".", "#", ".", "#", "."],
+        '\'' => ["#", "#", ".", ".", "."],
+        '-' => ["....", "....", "####", "....", "...."],
+        '+' => [".....", "..#..", "#####", "..#..", "....."],
+        '_' => ["....", "....", "....", "....", "####"],
+        '/' => ["....#", "...#.", "..#..", ".#...", "#...."],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    const MINI_FONT: &str = "flf2a$ 2 2 4 0 1\n\
+                             a tiny test font\n\
+                             $$@\n\
+                             $$@@\n\
+                             |@\n\
+                             o@@\n";
+
+    #[test]
+    fn test_builtin_font() {
+        assert_eq!(
+            BigText::new("hi").lines(),
+            vec![
+                "█   █ ███",
+                "█   █  █ ",
+                "█████  █ ",
+                "█   █  █ ",
+                "█   █ ███",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_characters_and_line_breaks() {
+        assert_eq!(BigText::new("~").lines(), BigText::new("?").lines());
+
+        let lines = BigText::new("1\n1").lines();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[5], "");
+    }
+
+    #[test]
+    fn test_figlet_font() {
+        let font = FigletFont::parse(MINI_FONT).unwrap();
+        assert_eq!(font.height, 2);
+        assert_eq!(BigText::new("! !").font(font).lines(), vec!["|  |", "o  o"]);
+    }
+
+    #[test]
+    fn test_invalid_figlet_font() {
+        assert!(FigletFont::parse("").is_err());
+        assert!(FigletFont::parse("tlf2a$ 2 2 4 0 0").is_err());
+        assert!(FigletFont::parse("flf2a$ x").is_err());
+    }
+
+    #[test]
+    fn test_display_trims_trailing_space() {
+        let text = BigText::new("T").to_string();
+        assert_eq!(strip_ansi(&text).lines().nth(1), Some("  █"));
+    }
+
+    #[test]
+    fn test_gradient_render() {
+        let text = BigText::new("OK").gradient(ColorScale::heat());
+        insta::assert_yaml_snapshot!(text.to_string());
+    }
+}
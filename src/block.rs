@@ -0,0 +1,193 @@
+use std::fmt;
+
+use crate::{Console, StyledText};
+
+// A rectangle-ish group of styled lines, the building block for panels and
+// columns. `beside` and `above` compose blocks, padding the narrower or
+// shorter side with spaces so lines stay aligned.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Block {
+    lines: Vec<StyledText>,
+}
+
+impl Block {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // An empty area, e.g. a gap between columns
+    pub fn spacer(width: usize, height: usize) -> Self {
+        Block {
+            lines: vec![StyledText::from(Console::repeat(' ', width)); height],
+        }
+    }
+
+    pub fn push_line<T: Into<StyledText>>(&mut self, line: T) {
+        self.lines.push(line.into());
+    }
+
+    pub fn line<T: Into<StyledText>>(mut self, line: T) -> Self {
+        self.push_line(line);
+        self
+    }
+
+    pub fn lines(&self) -> &[StyledText] {
+        &self.lines
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn max_width(&self) -> usize {
+        self.lines.iter().map(StyledText::width).max().unwrap_or(0)
+    }
+
+    // Makes every line exactly `width` columns, truncating longer ones, and
+    // the block exactly `height` lines
+    pub fn pad_to_rect(mut self, width: usize, height: usize) -> Self {
+        self.lines.resize_with(height, StyledText::new);
+        for line in &mut self.lines {
+            let mut fitted = line.take_visible(width);
+            let padding = width - fitted.width();
+            if padding > 0 {
+                fitted.push(Console::repeat(' ', padding));
+            }
+            *line = fitted;
+        }
+        self
+    }
+
+    // Places `other` to the right, aligning both to the top
+    pub fn beside(self, other: Block) -> Self {
+        let height = self.height().max(other.height());
+        let left_width = self.max_width();
+        let left = self.pad_to_rect(left_width, height);
+        let right_width = other.max_width();
+        let right = other.pad_to_rect(right_width, height);
+        Block {
+            lines: left
+                .lines
+                .into_iter()
+                .zip(right.lines)
+                .map(|(mut line, right)| {
+                    line.extend(right.segments().iter().cloned());
+                    line
+                })
+                .collect(),
+        }
+    }
+
+    // Places `other` below
+    pub fn above(mut self, other: Block) -> Self {
+        self.lines.extend(other.lines);
+        self
+    }
+}
+
+// Splits at newlines, keeping each piece's style
+impl From<StyledText> for Block {
+    fn from(text: StyledText) -> Self {
+        let mut lines = vec![StyledText::new()];
+        for segment in text.segments() {
+            for (i, piece) in segment.text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(StyledText::new());
+                }
+                if !piece.is_empty() {
+                    lines
+                        .last_mut()
+                        .unwrap()
+                        .push(Console::new_with_style(piece, segment.style.clone()));
+                }
+            }
+        }
+        Block { lines }
+    }
+}
+
+impl From<Console> for Block {
+    fn from(console: Console) -> Self {
+        Block::from(StyledText::from(console))
+    }
+}
+
+impl From<&str> for Block {
+    fn from(text: &str) -> Self {
+        Block::from(StyledText::from(text))
+    }
+}
+
+impl FromIterator<StyledText> for Block {
+    fn from_iter<I: IntoIterator<Item = StyledText>>(iter: I) -> Self {
+        Block {
+            lines: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    fn plain(block: &Block) -> Vec<String> {
+        block.lines().iter().map(StyledText::plain).collect()
+    }
+
+    #[test]
+    fn test_from_text_splits_lines() {
+        let block = Block::from(
+            StyledText::new()
+                .then(Console::new("one\ntw").red())
+                .then(Console::new("o\n")),
+        );
+        assert_eq!(plain(&block), ["one", "two", ""]);
+        assert_eq!(block.lines()[1].segments().len(), 2);
+        assert_eq!(block.max_width(), 3);
+        assert_eq!(block.height(), 3);
+    }
+
+    #[test]
+    fn test_pad_to_rect() {
+        let block = Block::from("abc\nd").pad_to_rect(2, 3);
+        assert_eq!(plain(&block), ["ab", "d ", "  "]);
+
+        let mut block = Block::new().line("wide line");
+        block.push_line(Console::new("x").red());
+        assert_eq!(plain(&block.pad_to_rect(4, 1)), ["wide"]);
+    }
+
+    #[test]
+    fn test_beside_and_above() {
+        let left = Block::from("a\nbbb");
+        let right = Block::from("1\n2\n3");
+        let combined = left
+            .beside(Block::spacer(1, 1))
+            .beside(right)
+            .above(Block::from("--"));
+        assert_eq!(strip_ansi(&combined.to_string()), "a   1\nbbb 2\n    3\n--");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_styles_stay_inside_lines() {
+        let block = Block::from(Console::new("x\ny").green()).beside(Block::from("|"));
+        assert_eq!(
+            block.to_string(),
+            "\x1b[38;5;2mx\x1b[0m|\n\x1b[38;5;2my\x1b[0m "
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::StyledText;
+use crate::html::inline_css;
+
+// The arguments for a browser `console.log` call that shows styled text the
+// way a terminal would: a format string with a `%c` in front of every
+// segment, and the CSS for each of them in order
+pub fn console_log_args(text: &StyledText) -> (String, Vec<String>) {
+    let mut format = String::new();
+    let mut styles = Vec::new();
+    for segment in text.segments() {
+        if segment.text.is_empty() {
+            continue;
+        }
+        // `%c` also resets the previous segment's CSS, so unstyled text
+        // gets one too
+        format.push_str("%c");
+        format.push_str(&segment.text.replace('%', "%%"));
+        styles.push(inline_css(&segment.style).join("; "));
+    }
+    (format, styles)
+}
+
+// Where `print` and `println` go in a browser: stdout isn't connected to
+// anything there
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+pub(crate) fn console_log(text: &StyledText) {
+    let (format, styles) = console_log_args(text);
+    let args = js_sys::Array::new();
+    args.push(&format.into());
+    for css in styles {
+        args.push(&css.into());
+    }
+    web_sys::console::log(&args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Console;
+
+    #[test]
+    fn test_console_log_args() {
+        let text = StyledText::new()
+            .then(Console::new("error").red().bold())
+            .then(Console::new(": 100% "))
+            .then(Console::new(""))
+            .then(Console::new("broken").underline());
+        let (format, styles) = console_log_args(&text);
+        assert_eq!(format, "%cerror%c: 100%% %cbroken");
+        assert_eq!(
+            styles,
+            [
+                "color: #cd0000; font-weight: bold",
+                "",
+                "text-decoration: underline"
+            ]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::badge::contrasting_foreground;
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::{ColorScale, Console, Style};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+// A month grid, or a GitHub-style contribution map of a whole year, where
+// days with a value get a background from the color scale
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calendar {
+    year: i32,
+    month: Option<u32>,
+    values: HashMap<(u32, u32), f64>,
+    scale: ColorScale,
+}
+
+impl Calendar {
+    pub fn month(year: i32, month: u32) -> Self {
+        assert!((1..=12).contains(&month), "month must be in 1..=12");
+        Calendar {
+            year,
+            month: Some(month),
+            values: HashMap::new(),
+            scale: ColorScale::greens(),
+        }
+    }
+
+    pub fn year(year: i32) -> Self {
+        Calendar {
+            year,
+            month: None,
+            values: HashMap::new(),
+            scale: ColorScale::greens(),
+        }
+    }
+
+    pub fn value(mut self, month: u32, day: u32, value: f64) -> Self {
+        self.values.insert((month, day), value);
+        self
+    }
+
+    // Sets a day of the month shown by a `Calendar::month`
+    pub fn day(self, day: u32, value: f64) -> Self {
+        let month = self.month.unwrap_or(1);
+        self.value(month, day, value)
+    }
+
+    pub fn values<I: IntoIterator<Item = (u32, u32, f64)>>(mut self, values: I) -> Self {
+        for (month, day, value) in values {
+            self.values.insert((month, day), value);
+        }
+        self
+    }
+
+    pub fn scale(mut self, scale: ColorScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    fn cell_style(&self, month: u32, day: u32) -> Option<Style> {
+        let value = *self.values.get(&(month, day))?;
+        let max = self.values.values().copied().fold(0.0, f64::max);
+        let background = self.scale.at(if max > 0.0 { value / max } else { 0.0 });
+        Some(
+            Style::new()
+                .bg(background)
+                .fg(contrasting_foreground(background)),
+        )
+    }
+
+    fn fmt_month(&self, f: &mut fmt::Formatter, month: u32) -> fmt::Result {
+        let muted = theme().style(Tone::Muted).clone();
+        let title = format!("{} {}", MONTHS[month as usize - 1], self.year);
+        writeln!(f, "{}", Console::new(format!("{:^20}", title)).bold())?;
+        writeln!(
+            f,
+            "{}",
+            Console::new_with_style("Mo Tu We Th Fr Sa Su", muted)
+        )?;
+
+        let offset = weekday(self.year, month, 1);
+        let mut line = "   ".repeat(offset);
+        for day in 1..=days_in_month(self.year, month) {
+            let column = (offset + day as usize - 1) % 7;
+            let number = format!("{:>2}", day);
+            match self.cell_style(month, day) {
+                Some(style) => line.push_str(&Console::new_with_style(number, style).to_string()),
+                None => line.push_str(&number),
+            }
+            if column == 6 {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            } else {
+                line.push(' ');
+            }
+        }
+        if !line.is_empty() {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+
+    fn fmt_year(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let muted = theme().style(Tone::Muted).clone();
+        let first_offset = weekday(self.year, 1, 1);
+        let days: Vec<(u32, u32)> = (1..=12)
+            .flat_map(|month| (1..=days_in_month(self.year, month)).map(move |day| (month, day)))
+            .collect();
+        let weeks = (first_offset + days.len()).div_ceil(7);
+
+        // Month names above the week in which each month starts
+        let mut header = String::new();
+        for (index, &(month, day)) in days.iter().enumerate() {
+            let column = (first_offset + index) / 7;
+            if day == 1 && header.chars().count() <= column * 2 {
+                let pad = column * 2 - header.chars().count();
+                header.push_str(&" ".repeat(pad));
+                header.push_str(&MONTHS[month as usize - 1][..3]);
+            }
+        }
+        writeln!(f, "    {}", Console::new_with_style(header, muted.clone()))?;
+
+        for row in 0..7 {
+            let label = match row {
+                0 => "Mon",
+                2 => "Wed",
+                4 => "Fri",
+                _ => "",
+            };
+            write!(
+                f,
+                "{}",
+                Console::new_with_style(format!("{:<4}", label), muted.clone())
+            )?;
+            for week in 0..weeks {
+                let index = (week * 7 + row).checked_sub(first_offset);
+                match index.and_then(|i| days.get(i)) {
+                    Some(&(month, day)) => match self.cell_style(month, day) {
+                        Some(style) => write!(f, "{}", Console::new_with_style("  ", style))?,
+                        None => write!(
+                            f,
+                            "{}",
+                            Console::new_with_style(
+                                format!("{} ", symbols().bullet),
+                                muted.clone()
+                            )
+                        )?,
+                    },
+                    None => f.write_str("  ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.month {
+            Some(month) => self.fmt_month(f, month),
+            None => self.fmt_year(f),
+        }
+    }
+}
+
+fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Day of the week with Monday as 0, using Sakamoto's method
+fn weekday(year: i32, month: u32, day: u32) -> usize {
+    const T: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let y = if month < 3 { year - 1 } else { year };
+    let sunday_based = (y + y.div_euclid(4) - y.div_euclid(100)
+        + y.div_euclid(400)
+        + T[month as usize - 1]
+        + day as i32)
+        .rem_euclid(7);
+    ((sunday_based + 6) % 7) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_date_math() {
+        assert_eq!(weekday(2024, 1, 1), 0); // Monday
+        assert_eq!(weekday(2024, 3, 1), 4); // Friday
+        assert_eq!(weekday(2000, 2, 29), 1); // Tuesday
+        assert_eq!(weekday(2023, 12, 31), 6); // Sunday
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2023, 4), 30);
+    }
+
+    #[test]
+    fn test_month_layout() {
+        let calendar = Calendar::month(2024, 3).day(5, 2.0);
+        assert_eq!(
+            strip_ansi(&calendar.to_string()),
+            "     March 2024     \n\
+             Mo Tu We Th Fr Sa Su\n             \
+             1  2  3\n 4  5  6  7  8  9 10\n\
+             11 12 13 14 15 16 17\n\
+             18 19 20 21 22 23 24\n\
+             25 26 27 28 29 30 31\n"
+        );
+    }
+
+    #[test]
+    fn test_cell_styles_follow_the_scale() {
+        let calendar = Calendar::month(2024, 3)
+            .day(1, 1.0)
+            .day(2, 4.0)
+            .scale(ColorScale::grayscale());
+        let low = calendar.cell_style(3, 1).unwrap();
+        let high = calendar.cell_style(3, 2).unwrap();
+        assert_eq!(low.background, Some(crate::Color::RGB(96, 96, 96)));
+        assert_eq!(high.background, Some(crate::Color::RGB(240, 240, 240)));
+        assert_eq!(high.foreground, Some(crate::Color::BLACK));
+        assert!(calendar.cell_style(3, 3).is_none());
+    }
+
+    #[test]
+    fn test_year_layout() {
+        let output = strip_ansi(&Calendar::year(2024).value(1, 1, 1.0).to_string());
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].starts_with("    Jan     Feb     Mar"));
+        // 2024 starts on a Monday and has 53 week columns
+        assert!(lines[1].starts_with("Mon   · "));
+        assert_eq!(lines[1].chars().count(), 4 + 53 * 2);
+    }
+}
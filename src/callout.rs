@@ -0,0 +1,119 @@
+use std::fmt::{self, Display};
+
+use crate::Console;
+use crate::symbols::symbols;
+use crate::terminal::terminal_width;
+use crate::theme::{Tone, theme};
+use crate::wrap::wrap_words;
+
+// A GitHub-admonition-like block: a colored bar down the left, an icon and
+// label, then the body wrapped to the terminal width
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callout {
+    tone: Tone,
+    title: Option<String>,
+    body: String,
+    width: Option<usize>,
+}
+
+impl Callout {
+    pub fn new<T: Into<String>>(tone: Tone, body: T) -> Self {
+        Callout {
+            tone,
+            title: None,
+            body: body.into(),
+            width: None,
+        }
+    }
+
+    pub fn note<T: Into<String>>(body: T) -> Self {
+        Self::new(Tone::Note, body)
+    }
+
+    pub fn tip<T: Into<String>>(body: T) -> Self {
+        Self::new(Tone::Tip, body)
+    }
+
+    pub fn warning<T: Into<String>>(body: T) -> Self {
+        Self::new(Tone::Warning, body)
+    }
+
+    pub fn error<T: Into<String>>(body: T) -> Self {
+        Self::new(Tone::Error, body)
+    }
+
+    // Replaces the tone's label, e.g. "Deprecated" instead of "Warning"
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    // Total width including the bar, defaults to the terminal width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn print(&self) {
+        crate::ignore_broken_pipe(crate::group::write_stdout(&self.to_string()));
+    }
+}
+
+impl Display for Callout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let style = theme.style(self.tone);
+        let bar = Console::new_with_style(format!("{} ", symbols().border.vertical), style.clone());
+        let title = self.title.as_deref().unwrap_or(self.tone.label());
+
+        writeln!(
+            f,
+            "{}{}",
+            bar,
+            Console::new_with_style(
+                format!("{} {}", theme.icon(self.tone), title),
+                theme.labeled_style(self.tone).bold()
+            )
+        )?;
+        let width = self.width.unwrap_or_else(terminal_width);
+        for line in wrap_words(&self.body, width.saturating_sub(2)) {
+            writeln!(f, "{}{}", bar, line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_callout_layout() {
+        let callout =
+            Callout::warning("This flag is deprecated and will be removed in 2.0.").width(24);
+        assert_eq!(
+            strip_ansi(&callout.to_string()),
+            "│ ⚠ Warning\n\
+             │ This flag is\n\
+             │ deprecated and will be\n\
+             │ removed in 2.0.\n"
+        );
+    }
+
+    #[test]
+    fn test_callout_title() {
+        let callout = Callout::tip("Use --fast.").title("Pro tip").width(40);
+        assert_eq!(
+            strip_ansi(&callout.to_string()),
+            "│ ★ Pro tip\n│ Use --fast.\n"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_callout_uses_theme_style() {
+        let output = Callout::note("x").width(20).to_string();
+        assert!(output.starts_with(&Console::new("│ ").blue().to_string()));
+    }
+}
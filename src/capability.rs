@@ -0,0 +1,158 @@
+use std::sync::OnceLock;
+
+#[cfg(not(feature = "no-color"))]
+use crate::Attribute;
+
+// Escape sequences beyond the basic SGR set that the terminal is known to
+// understand. Anything unsupported is left out of the output rather than
+// risking a misinterpretation, e.g. `4:3` being read as underline + italic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Capabilities {
+    // Curly/dotted/dashed/double underlines (`4:n`) and underline color (58)
+    pub(crate) styled_underline: bool,
+    // Overline (53)
+    pub(crate) overline: bool,
+    // Framed, encircled, superscript and subscript (51, 52, 73, 74)
+    pub(crate) rare_attributes: bool,
+    // Box drawing, braille spinners and other non-ASCII symbols
+    pub(crate) unicode: bool,
+    // 24-bit colors (38;2), rather than only the 256-color palette
+    pub(crate) truecolor: bool,
+}
+
+impl Capabilities {
+    pub(crate) fn get() -> Capabilities {
+        static DETECTED: OnceLock<Capabilities> = OnceLock::new();
+        *DETECTED.get_or_init(|| Capabilities::from_env(|name| std::env::var(name).ok()))
+    }
+
+    fn from_env<F: Fn(&str) -> Option<String>>(var: F) -> Capabilities {
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        // GNOME Terminal and other VTE-based terminals since 0.51.2
+        let vte = var("VTE_VERSION")
+            .and_then(|v| v.parse::<u32>().ok())
+            .is_some_and(|v| v >= 5102);
+
+        let styled_underline =
+            vte || matches!(
+                term.as_str(),
+                "xterm-kitty" | "xterm-ghostty" | "wezterm" | "foot" | "foot-extra" | "contour"
+            ) || matches!(
+                program.as_str(),
+                "WezTerm" | "ghostty" | "vscode" | "mintty"
+            );
+
+        let mintty = program == "mintty";
+        let overline = styled_underline || var("KONSOLE_VERSION").is_some();
+
+        let unicode = term != "dumb"
+            && (var("WT_SESSION").is_some()
+                || matches!(
+                    program.as_str(),
+                    "WezTerm" | "ghostty" | "vscode" | "mintty"
+                )
+                || utf8_locale(&var));
+
+        let truecolor = var("COLORTERM").is_some_and(|c| c == "truecolor" || c == "24bit")
+            || styled_underline
+            || matches!(program.as_str(), "iTerm.app" | "Hyper");
+
+        Capabilities {
+            styled_underline,
+            overline,
+            rare_attributes: mintty,
+            unicode,
+            truecolor,
+        }
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    pub(crate) fn supports(&self, attribute: Attribute) -> bool {
+        match attribute {
+            Attribute::Overline => self.overline,
+            Attribute::Framed
+            | Attribute::Encircled
+            | Attribute::Superscript
+            | Attribute::Subscript => self.rare_attributes,
+            _ => true,
+        }
+    }
+
+    #[cfg(all(test, not(feature = "no-color")))]
+    pub(crate) fn all() -> Capabilities {
+        Capabilities {
+            styled_underline: true,
+            overline: true,
+            rare_attributes: true,
+            unicode: true,
+            truecolor: true,
+        }
+    }
+}
+
+// The first non-empty of LC_ALL, LC_CTYPE and LANG names a UTF-8 encoding
+pub(crate) fn utf8_locale<F: Fn(&str) -> Option<String>>(var: F) -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> Capabilities {
+        Capabilities::from_env(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_detect_styled_underline() {
+        assert!(detect(&[("TERM", "xterm-kitty")]).styled_underline);
+        assert!(detect(&[("TERM_PROGRAM", "WezTerm")]).styled_underline);
+        assert!(detect(&[("VTE_VERSION", "7600")]).styled_underline);
+        assert!(!detect(&[("VTE_VERSION", "5000")]).styled_underline);
+        assert!(!detect(&[("TERM", "xterm-256color")]).styled_underline);
+        assert!(!detect(&[]).styled_underline);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_detect_extra_attributes() {
+        let konsole = detect(&[("KONSOLE_VERSION", "230805")]);
+        assert!(konsole.supports(Attribute::Overline));
+        assert!(!konsole.supports(Attribute::Framed));
+
+        let mintty = detect(&[("TERM_PROGRAM", "mintty")]);
+        assert!(mintty.supports(Attribute::Superscript));
+        assert!(detect(&[]).supports(Attribute::Bold));
+        assert!(!detect(&[]).supports(Attribute::Overline));
+    }
+
+    #[test]
+    fn test_detect_unicode() {
+        assert!(detect(&[("LANG", "en_US.UTF-8")]).unicode);
+        assert!(detect(&[("LC_ALL", "C.utf8"), ("LANG", "C")]).unicode);
+        assert!(detect(&[("WT_SESSION", "1")]).unicode);
+        assert!(!detect(&[("LANG", "C")]).unicode);
+        assert!(!detect(&[("TERM", "dumb"), ("LANG", "en_US.UTF-8")]).unicode);
+        assert!(!detect(&[]).unicode);
+    }
+
+    #[test]
+    fn test_detect_truecolor() {
+        assert!(detect(&[("COLORTERM", "truecolor")]).truecolor);
+        assert!(detect(&[("COLORTERM", "24bit")]).truecolor);
+        assert!(detect(&[("TERM", "xterm-kitty")]).truecolor);
+        assert!(detect(&[("TERM_PROGRAM", "iTerm.app")]).truecolor);
+        assert!(!detect(&[("TERM", "xterm-256color")]).truecolor);
+        assert!(!detect(&[]).truecolor);
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use crate::draw_target::{stderr_target, stdout_target};
+
+// Undoes terminal changes that are still in effect, newest first
+type Restore = Box<dyn FnMut() + Send>;
+
+struct Registry {
+    next_id: usize,
+    entries: Vec<(usize, Restore)>,
+}
+
+impl Registry {
+    const fn new() -> Self {
+        Registry {
+            next_id: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn register(&mut self, restore: Restore) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push((id, restore));
+        id
+    }
+
+    fn remove(&mut self, id: usize) -> Option<Restore> {
+        let index = self.entries.iter().position(|(entry, _)| *entry == id)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    // Entries run once; whatever registered them has been interrupted
+    fn take(&mut self) -> Vec<(usize, Restore)> {
+        std::mem::take(&mut self.entries)
+    }
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Keeps a restore action registered until the terminal has been put back
+// the normal way; dropping the guard unregisters it. A guard dropped while
+// a panic unwinds runs the action first, since whatever owned it won't.
+#[derive(Debug)]
+#[must_use = "the restore action is unregistered when the guard is dropped"]
+pub struct CleanupGuard {
+    id: usize,
+}
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        let restore = registry().remove(self.id);
+        if let Some(mut restore) = restore
+            && std::thread::panicking()
+        {
+            restore();
+        }
+    }
+}
+
+// Registers `restore` to run if the process is interrupted or panics before
+// the guard is dropped, e.g. to leave an alternate screen the caller entered
+pub fn register_cleanup<F: FnMut() + Send + 'static>(restore: F) -> CleanupGuard {
+    CleanupGuard {
+        id: registry().register(Box::new(restore)),
+    }
+}
+
+// Writes an escape sequence to stderr as a restore action
+pub(crate) fn register_sequence(sequence: &'static str) -> CleanupGuard {
+    register_cleanup(move || {
+        let _ = write_sequence(sequence);
+    })
+}
+
+// Writes an escape sequence to the stderr target, where the prompts draw
+pub(crate) fn write_sequence(sequence: &str) -> io::Result<()> {
+    stderr_target().write_locked(|out| {
+        out.write_all(sequence.as_bytes())?;
+        out.flush()
+    })
+}
+
+// Puts the terminal back: runs every registered restore action, newest
+// first, and resets colors on the streams that are terminals. The handlers
+// call this; call it yourself before exiting from a signal handler of your
+// own.
+pub fn run_cleanup() {
+    let entries = registry().take();
+    for (_, mut restore) in entries.into_iter().rev() {
+        restore();
+    }
+    for target in [stdout_target(), stderr_target()] {
+        if target.is_terminal() {
+            reset_colors(target);
+        }
+    }
+}
+
+fn reset_colors(mut out: impl Write) {
+    let _ = out.write_all(b"\x1b[0m");
+    let _ = out.flush();
+}
+
+// Runs `run_cleanup` on Ctrl-C, then exits with status 130 as the default
+// handler would, and before the message of a panic that aborts the process.
+// A panic that unwinds may be caught, so it's left to the guards, which
+// restore the terminal as they're dropped on the way up. Returns an error
+// when another Ctrl-C handler is already installed.
+#[cfg(feature = "cleanup")]
+pub fn install_cleanup_handlers() -> io::Result<()> {
+    static INSTALLED: Mutex<bool> = Mutex::new(false);
+    let mut installed = INSTALLED.lock().unwrap_or_else(|e| e.into_inner());
+    if *installed {
+        return Ok(());
+    }
+    ctrlc::set_handler(|| {
+        run_cleanup();
+        std::process::exit(130);
+    })
+    .map_err(io::Error::other)?;
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if cfg!(panic = "abort") {
+            run_cleanup();
+        }
+        previous(info);
+    }));
+    *installed = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_registry_runs_newest_first_once() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = Registry::new();
+        for name in ["raw mode", "cursor", "alt screen"] {
+            let log = Arc::clone(&log);
+            registry.register(Box::new(move || log.lock().unwrap().push(name)));
+        }
+        assert!(registry.remove(1).is_some());
+        assert!(registry.remove(1).is_none());
+
+        for (_, mut restore) in registry.take().into_iter().rev() {
+            restore();
+        }
+        assert_eq!(*log.lock().unwrap(), ["alt screen", "raw mode"]);
+        assert!(registry.take().is_empty());
+    }
+
+    #[test]
+    fn test_guards_restore_while_unwinding() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let record = |name: &'static str| {
+            let log = Arc::clone(&log);
+            register_cleanup(move || log.lock().unwrap().push(name))
+        };
+        drop(record("dropped"));
+        let result = std::panic::catch_unwind(|| {
+            let _guard = record("unwound");
+            panic!("caught");
+        });
+        assert!(result.is_err());
+        assert_eq!(*log.lock().unwrap(), ["unwound"]);
+    }
+}
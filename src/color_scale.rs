@@ -0,0 +1,86 @@
+use crate::Color;
+
+// Maps values in 0.0..=1.0 onto a gradient through evenly spaced color stops
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScale {
+    stops: Vec<(u8, u8, u8)>,
+}
+
+impl ColorScale {
+    pub fn new<I: IntoIterator<Item = Color>>(stops: I) -> Self {
+        let stops: Vec<_> = stops.into_iter().map(Color::to_rgb).collect();
+        assert!(!stops.is_empty(), "a color scale needs at least one color");
+        ColorScale { stops }
+    }
+
+    // Blue through green and yellow to red
+    pub fn heat() -> Self {
+        Self::new([
+            Color::RGB(49, 54, 149),
+            Color::RGB(69, 171, 93),
+            Color::RGB(254, 224, 79),
+            Color::RGB(215, 48, 39),
+        ])
+    }
+
+    // GitHub-style contribution greens
+    pub fn greens() -> Self {
+        Self::new([
+            Color::RGB(14, 68, 41),
+            Color::RGB(0, 109, 50),
+            Color::RGB(38, 166, 65),
+            Color::RGB(57, 211, 83),
+        ])
+    }
+
+    pub fn grayscale() -> Self {
+        Self::new([Color::RGB(48, 48, 48), Color::RGB(240, 240, 240)])
+    }
+
+    // Values outside 0.0..=1.0 are clamped
+    pub fn at(&self, value: f64) -> Color {
+        let value = if value.is_nan() {
+            0.0
+        } else {
+            value.clamp(0.0, 1.0)
+        };
+        if self.stops.len() == 1 {
+            let (r, g, b) = self.stops[0];
+            return Color::RGB(r, g, b);
+        }
+
+        let position = value * (self.stops.len() - 1) as f64;
+        let index = (position.floor() as usize).min(self.stops.len() - 2);
+        let t = position - index as f64;
+        let (from, to) = (self.stops[index], self.stops[index + 1]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::RGB(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_scale_interpolates() {
+        let scale = ColorScale::new([Color::RGB(0, 0, 0), Color::RGB(200, 100, 50)]);
+        assert_eq!(scale.at(0.0), Color::RGB(0, 0, 0));
+        assert_eq!(scale.at(0.5), Color::RGB(100, 50, 25));
+        assert_eq!(scale.at(1.0), Color::RGB(200, 100, 50));
+        assert_eq!(scale.at(7.0), Color::RGB(200, 100, 50));
+        assert_eq!(scale.at(f64::NAN), Color::RGB(0, 0, 0));
+    }
+
+    #[test]
+    fn test_color_scale_multiple_stops() {
+        let scale = ColorScale::new([
+            Color::RGB(0, 0, 0),
+            Color::RGB(100, 0, 0),
+            Color::RGB(100, 100, 0),
+        ]);
+        assert_eq!(scale.at(0.5), Color::RGB(100, 0, 0));
+        assert_eq!(scale.at(0.75), Color::RGB(100, 50, 0));
+        assert_eq!(ColorScale::new([Color::RED]).at(0.3), Color::RGB(205, 0, 0));
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::{Console, Style, StyledText};
+
+// Colors lines of text by an ordered list of regex rules, like `grcat`.
+// A rule styles each match, or only its capture groups when it has any, or
+// the whole line for `line_rule`s. By default every matching rule applies,
+// later rules layering on earlier ones; `first_match` stops at the first
+// rule that matches a line.
+#[derive(Debug, Clone, Default)]
+pub struct Colorizer {
+    rules: Vec<Rule>,
+    first_match: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    style: Style,
+    whole_line: bool,
+}
+
+impl Colorizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rule(mut self, regex: Regex, style: Style) -> Self {
+        self.rules.push(Rule {
+            regex,
+            style,
+            whole_line: false,
+        });
+        self
+    }
+
+    // Styles the whole line when the regex matches anywhere in it
+    pub fn line_rule(mut self, regex: Regex, style: Style) -> Self {
+        self.rules.push(Rule {
+            regex,
+            style,
+            whole_line: true,
+        });
+        self
+    }
+
+    pub fn first_match(mut self, first_match: bool) -> Self {
+        self.first_match = first_match;
+        self
+    }
+
+    pub fn colorize_line(&self, line: &str) -> StyledText {
+        let mut spans: Vec<(Range<usize>, &Style)> = Vec::new();
+        for rule in &self.rules {
+            let before = spans.len();
+            rule.spans(line, &mut spans);
+            if self.first_match && spans.len() > before {
+                break;
+            }
+        }
+        layer(line, &spans)
+    }
+
+    // Copies `input` to `out` line by line, colorizing each one. Line
+    // endings are kept as they were.
+    pub fn colorize<R: BufRead, W: Write>(&self, mut input: R, mut out: W) -> io::Result<()> {
+        let mut line = String::new();
+        while input.read_line(&mut line)? > 0 {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let ending = &line[content.len()..];
+            write!(out, "{}{}", self.colorize_line(content), ending)?;
+            line.clear();
+        }
+        out.flush()
+    }
+}
+
+impl Rule {
+    fn spans<'a>(&'a self, line: &str, spans: &mut Vec<(Range<usize>, &'a Style)>) {
+        if self.whole_line {
+            if self.regex.is_match(line) {
+                spans.push((0..line.len(), &self.style));
+            }
+            return;
+        }
+        for captures in self.regex.captures_iter(line) {
+            let groups: Vec<_> = captures.iter().skip(1).flatten().collect();
+            if groups.is_empty() {
+                spans.extend(captures.get(0).map(|m| (m.range(), &self.style)));
+            } else {
+                spans.extend(groups.into_iter().map(|m| (m.range(), &self.style)));
+            }
+        }
+        spans.retain(|(range, _)| !range.is_empty());
+    }
+}
+
+// Splits the line at every span boundary and merges the styles of the spans
+// covering each piece, in rule order
+fn layer(line: &str, spans: &[(Range<usize>, &Style)]) -> StyledText {
+    let mut bounds: Vec<usize> = spans
+        .iter()
+        .flat_map(|(range, _)| [range.start, range.end])
+        .chain([0, line.len()])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut text = StyledText::new();
+    for piece in bounds.windows(2) {
+        let (start, end) = (piece[0], piece[1]);
+        let style = spans
+            .iter()
+            .filter(|(range, _)| range.start <= start && end <= range.end)
+            .fold(Style::new(), |style, (_, layer)| style.merge(layer));
+        text.push(Console::new_with_style(&line[start..end], style));
+    }
+    if text.segments().is_empty() {
+        text.push(Console::new_with_style("", Style::new()));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use crate::testing::strip_ansi;
+
+    fn colorizer() -> Colorizer {
+        Colorizer::new()
+            .line_rule(Regex::new("ERROR").unwrap(), Style::new().fg(Color::RED))
+            .rule(Regex::new(r"\d+ms").unwrap(), Style::new().bold())
+            .rule(
+                Regex::new(r"user=(\w+)").unwrap(),
+                Style::new().fg(Color::CYAN),
+            )
+    }
+
+    fn parts(text: &StyledText) -> Vec<(&str, Option<Color>)> {
+        text.segments()
+            .iter()
+            .map(|s| (s.text.as_str(), s.style.foreground))
+            .collect()
+    }
+
+    #[test]
+    fn test_all_rules_layer() {
+        let text = colorizer().colorize_line("ERROR 12ms user=ann");
+        assert_eq!(
+            parts(&text),
+            [
+                ("ERROR ", Some(Color::RED)),
+                ("12ms", Some(Color::RED)),
+                (" user=", Some(Color::RED)),
+                ("ann", Some(Color::CYAN)),
+            ]
+        );
+        assert!(
+            text.segments()[1]
+                .style
+                .attributes
+                .contains(&crate::Attribute::Bold)
+        );
+    }
+
+    #[test]
+    fn test_first_match() {
+        let text = colorizer()
+            .first_match(true)
+            .colorize_line("INFO 5ms user=bob");
+        assert_eq!(
+            parts(&text),
+            [("INFO ", None), ("5ms", None), (" user=bob", None)]
+        );
+        assert!(
+            text.segments()[1]
+                .style
+                .attributes
+                .contains(&crate::Attribute::Bold)
+        );
+    }
+
+    #[test]
+    fn test_unmatched_and_empty_lines() {
+        assert_eq!(
+            parts(&colorizer().colorize_line("quiet")),
+            [("quiet", None)]
+        );
+        assert_eq!(parts(&colorizer().colorize_line("")), [("", None)]);
+    }
+
+    #[test]
+    fn test_colorize_stream() {
+        let mut out = Vec::new();
+        colorizer()
+            .colorize("a 1ms\r\nb\nc".as_bytes(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(strip_ansi(&out), "a 1ms\r\nb\nc");
+    }
+}
@@ -0,0 +1,136 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+use crate::theme::{Tone, theme};
+use crate::{Console, Style, group};
+
+// Runs a child process with its output passed through as it arrives, a line
+// at a time, stdout in one style and stderr in another (red by default) so
+// errors stand out. Lines the child already colored itself are left as
+// they are.
+pub struct StyledCommand<'a> {
+    command: &'a mut Command,
+    stdout: Style,
+    stderr: Style,
+}
+
+impl<'a> StyledCommand<'a> {
+    pub fn new(command: &'a mut Command) -> Self {
+        StyledCommand {
+            command,
+            stdout: Style::new(),
+            stderr: theme().style(Tone::Error).clone(),
+        }
+    }
+
+    pub fn stdout_style(mut self, style: Style) -> Self {
+        self.stdout = style;
+        self
+    }
+
+    pub fn stderr_style(mut self, style: Style) -> Self {
+        self.stderr = style;
+        self
+    }
+
+    // Waits for the child to exit, returning its status once all of its
+    // output has been written
+    pub fn run(self) -> io::Result<ExitStatus> {
+        let mut child = self
+            .command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        // The copiers indent like the thread running the command
+        let depth = group::depth();
+        let stdout = child.stdout.take().map(|out| {
+            let style = self.stdout;
+            thread::spawn(move || {
+                group::set_depth(depth);
+                copy_lines(out, &style, group::write_stdout)
+            })
+        });
+        let stderr = child.stderr.take().map(|err| {
+            let style = self.stderr;
+            thread::spawn(move || {
+                group::set_depth(depth);
+                copy_lines(err, &style, group::write_stderr)
+            })
+        });
+        let status = child.wait()?;
+        for copier in [stdout, stderr].into_iter().flatten() {
+            copier
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("output thread panicked")))?;
+        }
+        Ok(status)
+    }
+}
+
+// Passes each line of `reader` to `write` whole, styled unless it carries
+// escape sequences of its own
+fn copy_lines<R, F>(reader: R, style: &Style, mut write: F) -> io::Result<()>
+where
+    R: Read,
+    F: FnMut(&str) -> io::Result<()>,
+{
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end_matches(['\n', '\r']);
+        let styled = if text.contains('\x1b') {
+            format!("{}\x1b[0m\n", text)
+        } else {
+            format!("{}\n", Console::new_with_style(text, style.clone()))
+        };
+        write(&styled)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copied(input: &[u8], style: Style) -> Vec<String> {
+        let mut lines = Vec::new();
+        copy_lines(input, &style, |line| {
+            lines.push(line.to_string());
+            Ok(())
+        })
+        .unwrap();
+        lines
+    }
+
+    #[test]
+    fn test_lines_are_styled() {
+        let red = Style::new().fg(crate::Color::RED);
+        assert_eq!(
+            copied(b"error: oops\r\nsecond", red.clone()),
+            [
+                format!("{}\n", Console::new_with_style("error: oops", red.clone())),
+                format!("{}\n", Console::new_with_style("second", red)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_child_colors_are_kept() {
+        let lines = copied(b"\x1b[32mok\x1b[0m done\n", Style::new().dim());
+        assert_eq!(lines, ["\x1b[32mok\x1b[0m done\x1b[0m\n"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_returns_status() {
+        let status = StyledCommand::new(Command::new("sh").args(["-c", "exit 3"]))
+            .run()
+            .unwrap();
+        assert_eq!(status.code(), Some(3));
+    }
+}
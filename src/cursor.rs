@@ -0,0 +1,106 @@
+//! Cursor shape and visibility for interactive widgets, e.g. a bar cursor
+//! while the user types text. Each change returns a guard that puts the
+//! cursor back when dropped, and is undone by `run_cleanup` if the process
+//! is interrupted first.
+
+use std::io;
+use std::sync::Mutex;
+
+use crate::cleanup::{CleanupGuard, register_sequence, write_sequence};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorShape {
+    Block,
+    Underline,
+    Bar,
+}
+
+impl CursorShape {
+    // DECSCUSR, `CSI n SP q`
+    fn sequence(self, blinking: bool) -> String {
+        let n = match self {
+            CursorShape::Block => 1,
+            CursorShape::Underline => 3,
+            CursorShape::Bar => 5,
+        };
+        format!("\x1b[{} q", n + u8::from(!blinking))
+    }
+}
+
+// The terminal's own default shape, whatever the user configured
+const DEFAULT_SHAPE: &str = "\x1b[0 q";
+
+// The shape last set through `set_shape`, if any. Terminals can't be asked
+// for their current shape, so this is what guards put back.
+static CURRENT: Mutex<Option<(CursorShape, bool)>> = Mutex::new(None);
+
+fn current() -> std::sync::MutexGuard<'static, Option<(CursorShape, bool)>> {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Restores the previous cursor shape when dropped
+#[derive(Debug)]
+#[must_use = "the previous cursor shape comes back when the guard is dropped"]
+pub struct ShapeGuard {
+    previous: Option<(CursorShape, bool)>,
+    _cleanup: CleanupGuard,
+}
+
+impl Drop for ShapeGuard {
+    fn drop(&mut self) {
+        let sequence = match self.previous {
+            Some((shape, blinking)) => shape.sequence(blinking),
+            None => DEFAULT_SHAPE.to_string(),
+        };
+        *current() = self.previous;
+        let _ = write_sequence(&sequence);
+    }
+}
+
+// Changes the cursor shape on stderr, where the prompts draw, until the
+// guard is dropped
+pub fn set_shape(shape: CursorShape, blinking: bool) -> io::Result<ShapeGuard> {
+    let mut current = current();
+    write_sequence(&shape.sequence(blinking))?;
+    let previous = current.replace((shape, blinking));
+    Ok(ShapeGuard {
+        previous,
+        _cleanup: register_sequence(DEFAULT_SHAPE),
+    })
+}
+
+// Shows the cursor again when dropped
+#[derive(Debug)]
+#[must_use = "the cursor is shown again when the guard is dropped"]
+pub struct HiddenCursor {
+    _cleanup: CleanupGuard,
+}
+
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        let _ = write_sequence("\x1b[?25h");
+    }
+}
+
+// Hides the cursor on stderr until the guard is dropped
+pub fn hide() -> io::Result<HiddenCursor> {
+    write_sequence("\x1b[?25l")?;
+    Ok(HiddenCursor {
+        _cleanup: register_sequence("\x1b[?25h"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_sequences() {
+        assert_eq!(CursorShape::Block.sequence(true), "\x1b[1 q");
+        assert_eq!(CursorShape::Block.sequence(false), "\x1b[2 q");
+        assert_eq!(CursorShape::Underline.sequence(true), "\x1b[3 q");
+        assert_eq!(CursorShape::Underline.sequence(false), "\x1b[4 q");
+        assert_eq!(CursorShape::Bar.sequence(true), "\x1b[5 q");
+        assert_eq!(CursorShape::Bar.sequence(false), "\x1b[6 q");
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt::Debug;
+
+use crate::{Color, Console, Style, StyledText};
+
+// Re-formats `{:#?}` output with colors for type names, field names, strings,
+// numbers and keyword-like values such as `true` or `None`
+pub fn pretty_debug<T: Debug + ?Sized>(value: &T) -> String {
+    highlight_debug(&format!("{:#?}", value)).to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    TypeName,
+    Field,
+    Str,
+    Number,
+    Keyword,
+    Plain,
+}
+
+impl Token {
+    fn style(self) -> Style {
+        match self {
+            Token::TypeName => Style::new().fg(Color::CYAN).bold(),
+            Token::Field => Style::new().fg(Color::BLUE),
+            Token::Str => Style::new().fg(Color::GREEN),
+            Token::Number => Style::new().fg(Color::YELLOW),
+            Token::Keyword => Style::new().fg(Color::MAGENTA),
+            Token::Plain => Style::new(),
+        }
+    }
+}
+
+fn highlight_debug(text: &str) -> StyledText {
+    tokenize(text)
+        .into_iter()
+        .map(|(token, part)| Console::new_with_style(part, token.style()))
+        .collect()
+}
+
+fn tokenize(text: &str) -> Vec<(Token, &str)> {
+    let bytes = text.as_bytes();
+    let mut tokens: Vec<(Token, std::ops::Range<usize>)> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        let token = match c {
+            b'"' | b'\'' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != c {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(bytes.len());
+                Token::Str
+            }
+            b'0'..=b'9' => {
+                i += 1;
+                skip_number(bytes, &mut i);
+                Token::Number
+            }
+            b'-' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                i += 1;
+                skip_number(bytes, &mut i);
+                Token::Number
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let word = &text[start..i];
+                let is_field = text[i..].starts_with(": ")
+                    || (text[i..].starts_with(':') && !text[i..].starts_with("::"));
+                if is_field {
+                    Token::Field
+                } else if matches!(word, "true" | "false" | "None" | "NaN" | "inf") {
+                    Token::Keyword
+                } else if c.is_ascii_uppercase() {
+                    Token::TypeName
+                } else {
+                    Token::Plain
+                }
+            }
+            _ => {
+                // Step over a whole (possibly multi-byte) character
+                i += text[i..].chars().next().map_or(1, char::len_utf8);
+                Token::Plain
+            }
+        };
+        // Merge neighbouring plain text so punctuation doesn't fragment output
+        match tokens.last_mut() {
+            Some((Token::Plain, range)) if token == Token::Plain => range.end = i,
+            _ => tokens.push((token, start..i)),
+        }
+    }
+    tokens
+        .into_iter()
+        .map(|(token, range)| (token, &text[range]))
+        .collect()
+}
+
+fn skip_number(bytes: &[u8], i: &mut usize) {
+    while *i < bytes.len() {
+        match bytes[*i] {
+            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'.' => *i += 1,
+            b'-' | b'+' if matches!(bytes[*i - 1], b'e' | b'E') => *i += 1,
+            _ => break,
+        }
+    }
+}
+
+// Like `std::dbg!`, but the value is printed with `pretty_debug`
+#[macro_export]
+macro_rules! dbg {
+    () => {
+        $crate::dbg_location(file!(), line!(), column!(), None)
+    };
+    ($value:expr $(,)?) => {
+        match $value {
+            value => {
+                $crate::dbg_location(
+                    file!(),
+                    line!(),
+                    column!(),
+                    Some((stringify!($value), &value)),
+                );
+                value
+            }
+        }
+    };
+    ($($value:expr),+ $(,)?) => {
+        ($($crate::dbg!($value)),+,)
+    };
+}
+
+#[doc(hidden)]
+pub fn dbg_location(file: &str, line: u32, column: u32, value: Option<(&str, &dyn Debug)>) {
+    let location = Console::new(format!("[{}:{}:{}]", file, line, column)).dim();
+    let line = match value {
+        Some((expr, value)) => format!("{} {} = {}\n", location, expr, pretty_debug(value)),
+        None => format!("{}\n", location),
+    };
+    crate::ignore_broken_pipe(crate::group::write_stderr(&line));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Request {
+        method: &'static str,
+        retries: i32,
+        timeout: Option<f64>,
+        verbose: bool,
+        tags: Vec<char>,
+    }
+
+    fn request() -> Request {
+        Request {
+            method: "GET \"x\"",
+            retries: -3,
+            timeout: Some(1.5e-3),
+            verbose: true,
+            tags: vec!['a', '\''],
+        }
+    }
+
+    #[test]
+    fn test_text_is_unchanged() {
+        assert_eq!(
+            strip_ansi(&pretty_debug(&request())),
+            format!("{:#?}", request())
+        );
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let text = format!("{:?}", request());
+        let tokens: Vec<_> = tokenize(&text)
+            .into_iter()
+            .filter(|(token, _)| *token != Token::Plain)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::TypeName, "Request"),
+                (Token::Field, "method"),
+                (Token::Str, "\"GET \\\"x\\\"\""),
+                (Token::Field, "retries"),
+                (Token::Number, "-3"),
+                (Token::Field, "timeout"),
+                (Token::TypeName, "Some"),
+                (Token::Number, "0.0015"),
+                (Token::Field, "verbose"),
+                (Token::Keyword, "true"),
+                (Token::Field, "tags"),
+                (Token::Str, "'a'"),
+                (Token::Str, "'\\''"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dbg_returns_value() {
+        let value = crate::dbg!(1 + 1);
+        assert_eq!(value, 2);
+        let (a, b) = crate::dbg!("a", 3);
+        assert_eq!((a, b), ("a", 3));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{Attribute, Style, UnderlineStyle};
+
+// The changes needed to turn one style into another, produced by
+// `Style::diff`. Displays as the minimal escape sequence for the transition.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyleDelta {
+    changes: Style,
+}
+
+impl StyleDelta {
+    pub fn is_empty(&self) -> bool {
+        let changes = &self.changes;
+        changes.foreground.is_none()
+            && changes.background.is_none()
+            && changes.attributes.is_empty()
+            && changes.removed.is_empty()
+            && !changes.clear_fg
+            && !changes.clear_bg
+            && changes.underline_style.is_none()
+            && changes.underline_color.is_none()
+            && !changes.clear_underline_color
+    }
+
+    // The delta as a style layer, e.g. for `Console::new_with_style`
+    pub fn to_style(&self) -> Style {
+        self.changes.clone()
+    }
+}
+
+impl std::fmt::Display for StyleDelta {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.changes.to_ansi_start())
+    }
+}
+
+impl Style {
+    // Layers `other` on top: colors and attributes it sets win, and its
+    // negations (`no_bold`, `clear_fg`, ...) remove them from this style
+    pub fn merge(&self, other: &Style) -> Style {
+        let mut merged = self.clone();
+        for &attr in &other.removed {
+            merged = merged.no_attr(attr);
+        }
+        for &attr in &other.attributes {
+            if !merged.attributes.contains(&attr) {
+                merged = merged.attr(attr);
+            }
+        }
+        if let Some(fg) = other.foreground {
+            merged = merged.fg(fg);
+        } else if other.clear_fg {
+            merged = merged.clear_fg();
+        }
+        if let Some(bg) = other.background {
+            merged = merged.bg(bg);
+        } else if other.clear_bg {
+            merged = merged.clear_bg();
+        }
+        if let Some(label) = &other.label {
+            merged = merged.label(label.clone());
+        }
+        if let Some(underline) = other.underline_style {
+            merged = merged.underline_style(underline);
+        }
+        if let Some(color) = other.underline_color {
+            merged = merged.underline_color(color);
+        } else if other.clear_underline_color {
+            merged = merged.clear_underline_color();
+        }
+        merged
+    }
+
+    pub fn patch(&self, delta: &StyleDelta) -> Style {
+        self.merge(&delta.changes)
+    }
+
+    // What has to change to get from this style to `other`, so that
+    // `a.patch(&a.diff(&b))` looks the same as `b`
+    pub fn diff(&self, other: &Style) -> StyleDelta {
+        let mut changes = Style::new();
+
+        for &attr in &self.attributes {
+            if !other.attributes.contains(&attr) {
+                changes = changes.no_attr(attr);
+            }
+        }
+        for &attr in &other.attributes {
+            // Bold and dim are both turned off by SGR 22
+            let shares_off_code = attr.to_off_code() == "22"
+                && changes.removed.iter().any(|a| a.to_off_code() == "22");
+            if !self.attributes.contains(&attr) || shares_off_code {
+                changes = changes.attr(attr);
+            }
+        }
+
+        if other.foreground != self.foreground {
+            changes = match other.foreground {
+                Some(fg) => changes.fg(fg),
+                None => changes.clear_fg(),
+            };
+        }
+        if other.background != self.background {
+            changes = match other.background {
+                Some(bg) => changes.bg(bg),
+                None => changes.clear_bg(),
+            };
+        }
+
+        // `4:n` replaces the current underline style, and plain `4` resets it
+        if other.underline_style != self.underline_style
+            && other.attributes.contains(&Attribute::Underline)
+        {
+            changes =
+                changes.underline_style(other.underline_style.unwrap_or(UnderlineStyle::Single));
+        }
+        if other.underline_color != self.underline_color {
+            changes = match other.underline_color {
+                Some(color) => changes.underline_color(color),
+                None => changes.clear_underline_color(),
+            };
+        }
+
+        StyleDelta { changes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    fn effective(style: &Style) -> (Option<Color>, Option<Color>, Vec<Attribute>) {
+        let mut attributes = style.attributes.clone();
+        attributes.sort_by_key(|a| *a as u8);
+        (style.foreground, style.background, attributes)
+    }
+
+    #[test]
+    fn test_merge_layers() {
+        let base = Style::new().fg(Color::WHITE).bg(Color::BLACK).bold();
+        let severity = Style::new().fg(Color::RED).no_bold();
+        let emphasis = Style::new().underline().clear_bg();
+
+        let merged = base.merge(&severity).merge(&emphasis);
+        assert_eq!(
+            effective(&merged),
+            (Some(Color::RED), None, vec![Attribute::Underline])
+        );
+    }
+
+    #[test]
+    fn test_merge_does_not_duplicate_attributes() {
+        let merged = Style::new().bold().merge(&Style::new().bold().italic());
+        assert_eq!(merged.attributes, vec![Attribute::Bold, Attribute::Italic]);
+    }
+
+    #[test]
+    fn test_diff_round_trips_through_patch() {
+        let from = Style::new().fg(Color::GREEN).bold().dim().italic();
+        let to = Style::new().bg(Color::BLUE).dim().underline();
+
+        let delta = from.diff(&to);
+        assert_eq!(effective(&from.patch(&delta)), effective(&to));
+        assert!(from.diff(&from).is_empty());
+    }
+
+    #[test]
+    fn test_underline_layers() {
+        let base = Style::new().underline();
+        let diagnostic = Style::new()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::RED);
+        let merged = base.merge(&diagnostic);
+        assert_eq!(merged.underline_style, Some(UnderlineStyle::Curly));
+        assert_eq!(merged.underline_color, Some(Color::RED));
+
+        let delta = merged.diff(&base);
+        let restored = merged.patch(&delta);
+        assert_eq!(restored.underline_style, Some(UnderlineStyle::Single));
+        assert_eq!(restored.underline_color, None);
+        assert!(restored.attributes.contains(&Attribute::Underline));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_delta_escape_sequence() {
+        let from = Style::new().fg(Color::GREEN).bold().dim();
+        let to = Style::new().dim().underline();
+        // 22 turns off dim too, so it is set again
+        assert_eq!(from.diff(&to).to_string(), "\x1b[22;2;4;39m");
+        assert_eq!(from.diff(&from).to_string(), "");
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Arc, Mutex, RwLock};
+
+static STDOUT_TARGET: RwLock<Option<DrawTarget>> = RwLock::new(None);
+static STDERR_TARGET: RwLock<Option<DrawTarget>> = RwLock::new(None);
+
+// Where output goes: one of the standard streams, a buffer in memory, any
+// writer, or nowhere. Clones write to the same place.
+//
+// Everything the crate prints to stdout or stderr goes to the targets set
+// with `set_stdout_target` and `set_stderr_target`: messages, reporters,
+// notices, prompts, watch screens and the escape sequences that restore the
+// terminal. A test or a library can capture or silence it in one place.
+// Live components such as `ProgressBar` draw on the stderr target, and take
+// a target of their own with `with_target`.
+#[derive(Clone)]
+pub struct DrawTarget {
+    kind: Kind,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Stdout,
+    Stderr,
+    Buffer(SharedBuffer),
+    Writer(Arc<Mutex<Box<dyn Write + Send>>>),
+    Hidden,
+}
+
+impl DrawTarget {
+    pub fn stdout() -> Self {
+        DrawTarget { kind: Kind::Stdout }
+    }
+
+    pub fn stderr() -> Self {
+        DrawTarget { kind: Kind::Stderr }
+    }
+
+    // Keeps everything written in memory, for `contents` to read back
+    pub fn buffer() -> Self {
+        DrawTarget {
+            kind: Kind::Buffer(SharedBuffer::default()),
+        }
+    }
+
+    // Writes to `out`, e.g. a log file or a test's `CaptureWriter`
+    pub fn writer<W: Write + Send + 'static>(out: W) -> Self {
+        DrawTarget {
+            kind: Kind::Writer(Arc::new(Mutex::new(Box::new(out)))),
+        }
+    }
+
+    // Throws everything away
+    pub fn hidden() -> Self {
+        DrawTarget { kind: Kind::Hidden }
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        matches!(self.kind, Kind::Hidden)
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        match self.kind {
+            Kind::Stdout => io::stdout().is_terminal(),
+            Kind::Stderr => io::stderr().is_terminal(),
+            _ => false,
+        }
+    }
+
+    // Whether live components draw here. They skip standard streams that
+    // aren't terminals, so piped output and CI logs don't fill up with
+    // redraws, but always draw into buffers and writers.
+    pub fn is_live(&self) -> bool {
+        match self.kind {
+            Kind::Stdout | Kind::Stderr => self.is_terminal(),
+            Kind::Buffer(_) | Kind::Writer(_) => true,
+            Kind::Hidden => false,
+        }
+    }
+
+    // What's been written to a buffer; empty for other targets
+    pub fn contents(&self) -> String {
+        match &self.kind {
+            Kind::Buffer(buffer) => buffer.contents(),
+            _ => String::new(),
+        }
+    }
+
+    // Runs `write` with the target locked, so what it writes can't be
+    // interleaved with other threads' output
+    pub(crate) fn write_locked<F>(&self, write: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        match &self.kind {
+            Kind::Stdout => write(&mut io::stdout().lock()),
+            Kind::Stderr => write(&mut io::stderr().lock()),
+            Kind::Buffer(buffer) => write(&mut buffer.clone()),
+            Kind::Writer(out) => write(&mut *out.lock().unwrap_or_else(|e| e.into_inner())),
+            Kind::Hidden => write(&mut io::sink()),
+        }
+    }
+}
+
+// Bytes in memory, shared between clones
+#[derive(Clone, Default)]
+struct SharedBuffer {
+    bytes: Arc<Mutex<Vec<u8>>>,
+}
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        let bytes = self.bytes.lock().unwrap_or_else(|e| e.into_inner());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut bytes = self.bytes.lock().unwrap_or_else(|e| e.into_inner());
+        bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for DrawTarget {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_locked(|out| out.write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_locked(|out| out.flush())
+    }
+}
+
+impl fmt::Debug for DrawTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self.kind {
+            Kind::Stdout => "Stdout",
+            Kind::Stderr => "Stderr",
+            Kind::Buffer(_) => "Buffer",
+            Kind::Writer(_) => "Writer",
+            Kind::Hidden => "Hidden",
+        };
+        write!(f, "DrawTarget::{}", name)
+    }
+}
+
+// Sends what the crate prints to stdout to `target` instead
+pub fn set_stdout_target(target: DrawTarget) {
+    *STDOUT_TARGET.write().unwrap_or_else(|e| e.into_inner()) = Some(target);
+}
+
+// Sends what the crate prints to stderr, including live components created
+// with `new`, to `target` instead
+pub fn set_stderr_target(target: DrawTarget) {
+    *STDERR_TARGET.write().unwrap_or_else(|e| e.into_inner()) = Some(target);
+}
+
+pub fn stdout_target() -> DrawTarget {
+    let target = STDOUT_TARGET.read().unwrap_or_else(|e| e.into_inner());
+    target.clone().unwrap_or_else(DrawTarget::stdout)
+}
+
+pub fn stderr_target() -> DrawTarget {
+    let target = STDERR_TARGET.read().unwrap_or_else(|e| e.into_inner());
+    target.clone().unwrap_or_else(DrawTarget::stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    #[test]
+    fn test_buffer_and_writer() {
+        let mut buffer = DrawTarget::buffer();
+        write!(buffer.clone(), "a").unwrap();
+        write!(buffer, "b").unwrap();
+        assert_eq!(buffer.contents(), "ab");
+        assert!(buffer.is_live());
+        assert!(!buffer.is_terminal());
+
+        let out = CaptureWriter::new();
+        let mut writer = DrawTarget::writer(out.clone());
+        write!(writer, "c").unwrap();
+        assert_eq!(out.contents(), "c");
+        assert_eq!(writer.contents(), "");
+    }
+
+    #[test]
+    fn test_hidden() {
+        let mut hidden = DrawTarget::hidden();
+        assert_eq!(hidden.write(b"gone").unwrap(), 4);
+        assert!(hidden.is_hidden());
+        assert!(!hidden.is_live());
+        assert_eq!(format!("{:?}", hidden), "DrawTarget::Hidden");
+    }
+
+    #[test]
+    fn test_redirect_stdout() {
+        let _lock = crate::group::test_lock();
+        let buffer = DrawTarget::buffer();
+        set_stdout_target(buffer.clone());
+        crate::Console::new("redirected").println();
+        crate::Console::new("locked").println_locked();
+        set_stdout_target(DrawTarget::stdout());
+        // Other tests may print while the buffer is in place
+        let contents = buffer.contents();
+        assert!(contents.contains("redirected\n"));
+        assert!(contents.contains("locked\n"));
+    }
+
+    #[test]
+    fn test_live_components_follow_target() {
+        let buffer = DrawTarget::buffer();
+        let bar = crate::ProgressBar::with_target(buffer.clone(), 2).width(2);
+        bar.finish();
+        assert!(crate::testing::strip_ansi(&buffer.contents()).contains("██"));
+
+        let bar = crate::ProgressBar::with_target(DrawTarget::hidden(), 2);
+        bar.inc(1);
+        assert_eq!(bar.position(), 1);
+    }
+}
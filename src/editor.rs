@@ -0,0 +1,129 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Console;
+use crate::group;
+use crate::theme::{Tone, theme};
+
+const INSTRUCTIONS: &str = "\n\
+# Write your text above this line. Lines starting with '#' are ignored,\n\
+# and saving an empty file leaves the text empty.\n";
+
+// Opens `$VISUAL` or `$EDITOR` on a temporary file holding `initial_text`
+// and returns what was saved, with `#` comment lines and trailing blank
+// lines removed — the `git commit` message flow
+pub fn edit_in_editor(initial_text: &str) -> io::Result<String> {
+    let path = temp_path();
+    std::fs::write(
+        &path,
+        format!("{}\n{}", initial_text.trim_end(), INSTRUCTIONS),
+    )?;
+    let result = run_editor(&path).and_then(|_| std::fs::read_to_string(&path));
+    let _ = std::fs::remove_file(&path);
+    Ok(strip_comments(&result?))
+}
+
+fn run_editor(path: &Path) -> io::Result<()> {
+    let command = editor_command(|name| std::env::var(name).ok());
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+
+    let hint = Console::new_with_style(
+        format!("hint: Waiting for {} to close the file...", program),
+        theme().style(Tone::Hint).clone(),
+    );
+    crate::ignore_broken_pipe(group::write_stderr(&hint.to_string()));
+    let status = Command::new(program).args(words).arg(path).status();
+    // Take the hint back down, like git does
+    crate::ignore_broken_pipe(group::write_stderr("\r\x1b[2K"));
+
+    let status = status.map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("could not start editor `{}`: {}", program, e),
+        )
+    })?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "editor `{}` exited with {}",
+            program, status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+// `$VISUAL` wins over `$EDITOR`; either may include arguments, e.g.
+// `code --wait`
+fn editor_command<F: Fn(&str) -> Option<String>>(var: F) -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(var)
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+fn temp_path() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "pretty-console-edit-{}-{}.txt",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+fn strip_comments(text: &str) -> String {
+    let kept: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+    kept.join("\n").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments() {
+        assert_eq!(
+            strip_comments(&format!(
+                "Fix the parser\n\nDetails here.\n{}",
+                INSTRUCTIONS
+            )),
+            "Fix the parser\n\nDetails here."
+        );
+        assert_eq!(strip_comments("# only comments\n\n"), "");
+        // Only lines that start with `#` are comments
+        assert_eq!(strip_comments("issue #12\n  # kept"), "issue #12\n  # kept");
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(editor_command(env(&[("EDITOR", "nano")])), "nano");
+        assert_eq!(
+            editor_command(env(&[("VISUAL", "code --wait"), ("EDITOR", "nano")])),
+            "code --wait"
+        );
+        assert_eq!(
+            editor_command(env(&[("VISUAL", " "), ("EDITOR", "nano")])),
+            "nano"
+        );
+        assert_eq!(editor_command(env(&[])), DEFAULT_EDITOR);
+    }
+
+    #[test]
+    fn test_temp_paths_are_unique() {
+        assert_ne!(temp_path(), temp_path());
+    }
+}
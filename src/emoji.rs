@@ -0,0 +1,247 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Console;
+use crate::capability::utf8_locale;
+
+// `:shortcode:` resolution for `Console::new`. Only known names are
+// replaced, so text like `12:30:45` or `error: x` is left alone. Terminals
+// that likely can't draw emoji get an ASCII stand-in instead; override the
+// detection with `set_emoji_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmojiMode {
+    Emoji,
+    Ascii,
+    // Leaves shortcodes as written
+    Off,
+}
+
+const UNSET: u8 = 0;
+const EMOJI: u8 = 1;
+const ASCII: u8 = 2;
+const OFF: u8 = 3;
+
+static MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+pub fn set_emoji_mode(mode: EmojiMode) {
+    let value = match mode {
+        EmojiMode::Emoji => EMOJI,
+        EmojiMode::Ascii => ASCII,
+        EmojiMode::Off => OFF,
+    };
+    MODE.store(value, Ordering::Relaxed);
+}
+
+pub fn emoji_mode() -> EmojiMode {
+    let value = match MODE.load(Ordering::Relaxed) {
+        UNSET => {
+            let detected = if emoji_likely(|name| std::env::var(name).ok()) {
+                EMOJI
+            } else {
+                ASCII
+            };
+            // Keep a value set through the API in the meantime
+            let _ = MODE.compare_exchange(UNSET, detected, Ordering::Relaxed, Ordering::Relaxed);
+            MODE.load(Ordering::Relaxed)
+        }
+        value => value,
+    };
+    match value {
+        EMOJI => EmojiMode::Emoji,
+        ASCII => EmojiMode::Ascii,
+        _ => EmojiMode::Off,
+    }
+}
+
+// Emoji need a UTF-8 locale and a terminal with color glyph fonts; the Linux
+// virtual console has neither
+fn emoji_likely(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var("WT_SESSION").is_some() {
+        return true;
+    }
+    var("TERM").as_deref() != Some("linux") && utf8_locale(var)
+}
+
+// Replaces known `:shortcodes:` according to the current emoji mode
+pub fn emojify(text: &str) -> String {
+    match emoji_mode() {
+        EmojiMode::Emoji => replace_shortcodes(text, false),
+        EmojiMode::Ascii => replace_shortcodes(text, true),
+        EmojiMode::Off => text.to_string(),
+    }
+}
+
+impl Console {
+    // Replaces known `:shortcodes:` in text that `Console::new` didn't
+    // build, e.g. from `new_with_style` or `with_text`
+    pub fn emojify(self) -> Self {
+        Console {
+            text: emojify(&self.text),
+            ..self
+        }
+    }
+}
+
+pub(crate) fn replace_shortcodes(text: &str, ascii: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(':') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')))
+            .unwrap_or(after.len());
+        let replacement = after[name_len..]
+            .starts_with(':')
+            .then(|| lookup(&after[..name_len]))
+            .flatten();
+        match replacement {
+            Some((emoji, fallback)) => {
+                result.push_str(if ascii { fallback } else { emoji });
+                rest = &after[name_len + 1..];
+            }
+            None => {
+                // The colon may still open a shortcode that starts here
+                result.push(':');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn lookup(name: &str) -> Option<(&'static str, &'static str)> {
+    SHORTCODES
+        .binary_search_by_key(&name, |&(name, _, _)| name)
+        .ok()
+        .map(|i| (SHORTCODES[i].1, SHORTCODES[i].2))
+}
+
+// Sorted by name for binary search
+const SHORTCODES: &[(&str, &str, &str)] = &[
+    ("+1", "👍", "+1"),
+    ("-1", "👎", "-1"),
+    ("arrow_down", "⬇️", "v"),
+    ("arrow_left", "⬅️", "<-"),
+    ("arrow_right", "➡️", "->"),
+    ("arrow_up", "⬆️", "^"),
+    ("art", "🎨", "*"),
+    ("book", "📖", "[doc]"),
+    ("boom", "💥", "!!"),
+    ("broom", "🧹", "[clean]"),
+    ("bug", "🐛", "[bug]"),
+    ("bulb", "💡", "(i)"),
+    ("check", "✔️", "v"),
+    ("clock", "🕒", "[time]"),
+    ("computer", "💻", "[pc]"),
+    ("construction", "🚧", "[wip]"),
+    ("exclamation", "❗", "!"),
+    ("eyes", "👀", "o.o"),
+    ("fire", "🔥", "*"),
+    ("floppy_disk", "💾", "[save]"),
+    ("gear", "⚙️", "[cfg]"),
+    ("globe", "🌐", "[www]"),
+    ("hammer", "🔨", "[build]"),
+    ("heart", "❤️", "<3"),
+    ("heavy_check_mark", "✔️", "v"),
+    ("hourglass", "⏳", "..."),
+    ("information_source", "ℹ️", "(i)"),
+    ("key", "🔑", "[key]"),
+    ("link", "🔗", "[link]"),
+    ("lock", "🔒", "[lock]"),
+    ("mag", "🔍", "[find]"),
+    ("memo", "📝", "[note]"),
+    ("no_entry", "⛔", "[x]"),
+    ("package", "📦", "[pkg]"),
+    ("question", "❓", "?"),
+    ("recycle", "♻️", "[re]"),
+    ("rocket", "🚀", ">>"),
+    ("skull", "💀", "x_x"),
+    ("smile", "😄", ":)"),
+    ("sparkles", "✨", "*"),
+    ("star", "⭐", "*"),
+    ("stop_sign", "🛑", "[stop]"),
+    ("tada", "🎉", "\\o/"),
+    ("thumbsdown", "👎", "-1"),
+    ("thumbsup", "👍", "+1"),
+    ("truck", "🚚", "[ship]"),
+    ("warning", "⚠️", "!"),
+    ("white_check_mark", "✅", "[ok]"),
+    ("wink", "😉", ";)"),
+    ("wrench", "🔧", "[fix]"),
+    ("x", "❌", "[x]"),
+    ("zap", "⚡", "!"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Style;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_table_is_sorted() {
+        assert!(SHORTCODES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_replace_shortcodes() {
+        assert_eq!(
+            replace_shortcodes(":rocket: launching", false),
+            "🚀 launching"
+        );
+        assert_eq!(
+            replace_shortcodes(":rocket: launching", true),
+            ">> launching"
+        );
+        assert_eq!(
+            replace_shortcodes("done :white_check_mark::tada:", false),
+            "done ✅🎉"
+        );
+    }
+
+    #[test]
+    fn test_unknown_shortcodes_are_kept() {
+        for text in [
+            "12:30:45",
+            "error: failed",
+            ":nope:",
+            "a::b",
+            ":",
+            "::rocket:",
+        ] {
+            let expected = text.replace(":rocket:", "🚀");
+            assert_eq!(replace_shortcodes(text, false), expected);
+        }
+    }
+
+    #[test]
+    fn test_console_expands_shortcodes() {
+        assert_eq!(Console::new(":rocket: go").text(), emojify(":rocket: go"));
+        // Untrusted text is left as written
+        assert_eq!(Console::new_sanitized(":rocket:").text(), ":rocket:");
+        let styled = Console::new_with_style(":rocket: go", Style::new().bold()).emojify();
+        assert_eq!(styled.text(), emojify(":rocket: go"));
+        assert_eq!(styled.style(), &Style::new().bold());
+    }
+
+    #[test]
+    fn test_emoji_detection() {
+        let env = |pairs: &[(&str, &str)]| {
+            let map: HashMap<String, String> = pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            move |name: &str| map.get(name).cloned()
+        };
+        assert!(emoji_likely(env(&[("LANG", "en_US.UTF-8")])));
+        assert!(emoji_likely(env(&[("LC_ALL", "C.utf8"), ("LANG", "C")])));
+        assert!(!emoji_likely(env(&[("LANG", "C")])));
+        assert!(!emoji_likely(env(&[
+            ("TERM", "linux"),
+            ("LANG", "en_US.UTF-8")
+        ])));
+        assert!(emoji_likely(env(&[("WT_SESSION", "1")])));
+        assert!(!emoji_likely(env(&[])));
+    }
+}
@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+// How a `ProgressBar` works out its rate and time left. The bar records its
+// position, with the time since it started, every time it draws, and asks
+// for `rate` and `eta` when rendering them. Without one the bar measures
+// its rate over the last few seconds and estimates the time left from the
+// average so far.
+pub trait Estimator: Send {
+    fn record(&mut self, pos: u64, elapsed: Duration);
+
+    // Units per second
+    fn rate(&self) -> f64;
+
+    // Time left to reach `len` from `pos`, or `None` when there's nothing
+    // to go by yet
+    fn eta(&self, pos: u64, len: u64) -> Option<Duration> {
+        if len == 0 {
+            return None;
+        }
+        time_left(len.saturating_sub(pos) as f64, self.rate())
+    }
+}
+
+// How long `left` units take at `rate` units per second, or `None` when
+// the rate is too small or not a number to go by
+fn time_left(left: f64, rate: f64) -> Option<Duration> {
+    if rate <= 0.0 || !rate.is_finite() {
+        return None;
+    }
+    Duration::try_from_secs_f64(left / rate).ok()
+}
+
+// Units per second over `elapsed`
+fn per_second(units: f64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 { 0.0 } else { units / seconds }
+}
+
+// The average rate since the start: steady, but slow to notice changes in
+// speed
+#[derive(Debug, Clone, Default)]
+pub struct AverageEstimator {
+    pos: u64,
+    elapsed: Duration,
+}
+
+impl AverageEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Estimator for AverageEstimator {
+    fn record(&mut self, pos: u64, elapsed: Duration) {
+        self.pos = pos;
+        self.elapsed = elapsed;
+    }
+
+    fn rate(&self) -> f64 {
+        per_second(self.pos as f64, self.elapsed)
+    }
+}
+
+// An exponentially smoothed rate: each interval's speed counts for half as
+// much after every `half_life`, so the estimate follows a change in speed
+// within a few half-lives without jumping on every update
+#[derive(Debug, Clone)]
+pub struct SmoothedEstimator {
+    half_life: Duration,
+    last: Option<(u64, Duration)>,
+    rate: Option<f64>,
+}
+
+impl SmoothedEstimator {
+    pub fn new(half_life: Duration) -> Self {
+        SmoothedEstimator {
+            half_life,
+            last: None,
+            rate: None,
+        }
+    }
+}
+
+impl Default for SmoothedEstimator {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(2))
+    }
+}
+
+impl Estimator for SmoothedEstimator {
+    fn record(&mut self, pos: u64, elapsed: Duration) {
+        let (last_pos, last_elapsed) = self.last.unwrap_or((0, Duration::ZERO));
+        if elapsed <= last_elapsed {
+            return;
+        }
+        let interval = elapsed - last_elapsed;
+        let speed = per_second(pos.saturating_sub(last_pos) as f64, interval);
+        // The share of the old rate to keep
+        let kept = if self.half_life.is_zero() {
+            0.0
+        } else {
+            0.5f64.powf(interval.as_secs_f64() / self.half_life.as_secs_f64())
+        };
+        self.rate = Some(match self.rate {
+            None => speed,
+            Some(rate) => rate * kept + speed * (1.0 - kept),
+        });
+        self.last = Some((pos, elapsed));
+    }
+
+    fn rate(&self) -> f64 {
+        self.rate.unwrap_or(0.0)
+    }
+}
+
+// For work whose steps don't all cost the same, e.g. a download that's
+// quick to start and slow to verify at the end. Each phase covers the
+// positions up to its end and gives each of them a weight, and the time
+// left is the weighted work left over the weighted work done per second.
+// Positions past the last phase weigh 1.
+//
+//   // The last 10% of 1000 steps take five times as long
+//   PhasedEstimator::new([(900, 1.0), (1000, 5.0)])
+#[derive(Debug, Clone)]
+pub struct PhasedEstimator {
+    // Ends, in increasing order, and the weight of each step before them
+    phases: Vec<(u64, f64)>,
+    pos: u64,
+    elapsed: Duration,
+}
+
+impl PhasedEstimator {
+    pub fn new<I: IntoIterator<Item = (u64, f64)>>(phases: I) -> Self {
+        let mut phases: Vec<(u64, f64)> = phases.into_iter().collect();
+        phases.sort_by_key(|&(end, _)| end);
+        PhasedEstimator {
+            phases,
+            pos: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    // The weighted work in the first `pos` steps
+    fn work(&self, pos: u64) -> f64 {
+        let mut work = 0.0;
+        let mut start = 0;
+        for &(end, weight) in &self.phases {
+            if pos <= start {
+                return work;
+            }
+            work += (pos.min(end) - start) as f64 * weight;
+            start = start.max(end);
+        }
+        work + pos.saturating_sub(start) as f64
+    }
+}
+
+impl Estimator for PhasedEstimator {
+    fn record(&mut self, pos: u64, elapsed: Duration) {
+        self.pos = pos;
+        self.elapsed = elapsed;
+    }
+
+    // Steps per second on average, so rates display in the bar's units
+    fn rate(&self) -> f64 {
+        per_second(self.pos as f64, self.elapsed)
+    }
+
+    fn eta(&self, pos: u64, len: u64) -> Option<Duration> {
+        if len == 0 {
+            return None;
+        }
+        let speed = per_second(self.work(self.pos), self.elapsed);
+        let left = (self.work(len) - self.work(pos)).max(0.0);
+        time_left(left, speed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_average() {
+        let mut estimator = AverageEstimator::new();
+        assert_eq!(estimator.eta(0, 100), None);
+        estimator.record(20, secs(2));
+        assert_eq!(estimator.rate(), 10.0);
+        assert_eq!(estimator.eta(20, 100), Some(secs(8)));
+    }
+
+    #[test]
+    fn test_smoothed_follows_speed() {
+        let mut estimator = SmoothedEstimator::new(secs(1));
+        estimator.record(10, secs(1));
+        assert_eq!(estimator.rate(), 10.0);
+        // Half of the old rate is kept after a half-life
+        estimator.record(40, secs(2));
+        assert_eq!(estimator.rate(), 20.0);
+        for second in 3..=12 {
+            estimator.record(40 + (second - 2) * 30, secs(second));
+        }
+        assert!((estimator.rate() - 30.0).abs() < 0.01);
+        // Repeated times are ignored
+        estimator.record(1000, secs(12));
+        assert!((estimator.rate() - 30.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_stalls_leave_the_eta_unknown() {
+        let mut estimator = SmoothedEstimator::new(secs(2));
+        estimator.record(10, secs(1));
+        // A stall of a few minutes decays the rate to almost nothing, and
+        // the time left past what a `Duration` holds
+        estimator.record(10, secs(401));
+        assert!(estimator.rate() > 0.0);
+        assert_eq!(estimator.eta(10, 100), None);
+
+        let mut estimator = PhasedEstimator::new([(10, -1.0), (20, f64::NAN)]);
+        estimator.record(5, secs(5));
+        assert_eq!(estimator.eta(5, 20), None);
+    }
+
+    #[test]
+    fn test_phases_weigh_work() {
+        let estimator = PhasedEstimator::new([(100, 4.0), (50, 1.0)]);
+        assert_eq!(estimator.work(30), 30.0);
+        assert_eq!(estimator.work(70), 50.0 + 80.0);
+        assert_eq!(estimator.work(120), 50.0 + 200.0 + 20.0);
+
+        let mut estimator = estimator;
+        // 50 light steps in 5 seconds: 10 units of work a second, with 200
+        // heavy ones left
+        estimator.record(50, secs(5));
+        assert_eq!(estimator.rate(), 10.0);
+        assert_eq!(estimator.eta(50, 100), Some(secs(20)));
+        assert_eq!(estimator.eta(100, 100), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,570 @@
+//! Minimal keyboard input for building interactive widgets: a raw-mode guard
+//! and a blocking `read_key`, plus opt-in bracketed paste, focus and mouse
+//! events read with `read_event`.
+
+use std::io;
+
+use crate::cleanup::{CleanupGuard, register_sequence, write_sequence};
+
+const ESC: u8 = 0x1b;
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+    Backspace,
+    Tab,
+    BackTab,
+    CtrlC,
+    // Any other control key, e.g. `Ctrl('d')` for ctrl-d
+    Ctrl(char),
+    Char(char),
+    Unknown,
+}
+
+// Everything `read_event` reports. Pastes, focus changes and the mouse
+// only arrive while `BracketedPaste`, `FocusEvents` and `MouseCapture` are
+// enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Key(Key),
+    // Pasted text in one piece, with line breaks as "\n", so a multi-line
+    // paste isn't taken as several presses of enter
+    Paste(String),
+    FocusGained,
+    FocusLost,
+    Mouse(MouseEvent),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Press(MouseButton),
+    Release(MouseButton),
+    ScrollUp,
+    ScrollDown,
+}
+
+// Where the mouse was, counted in cells from 0 at the top-left of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    pub column: usize,
+    pub row: usize,
+}
+
+// Has the terminal mark pasted text until dropped
+#[derive(Debug)]
+#[must_use = "bracketed paste is disabled when the guard is dropped"]
+pub struct BracketedPaste {
+    _cleanup: CleanupGuard,
+}
+
+impl BracketedPaste {
+    pub fn enable() -> io::Result<Self> {
+        write_sequence("\x1b[?2004h")?;
+        Ok(BracketedPaste {
+            _cleanup: register_sequence("\x1b[?2004l"),
+        })
+    }
+}
+
+impl Drop for BracketedPaste {
+    fn drop(&mut self) {
+        let _ = write_sequence("\x1b[?2004l");
+    }
+}
+
+// Has the terminal report when its window gains or loses focus until
+// dropped, e.g. to pause animations nobody is looking at
+#[derive(Debug)]
+#[must_use = "focus events are disabled when the guard is dropped"]
+pub struct FocusEvents {
+    _cleanup: CleanupGuard,
+}
+
+impl FocusEvents {
+    pub fn enable() -> io::Result<Self> {
+        write_sequence("\x1b[?1004h")?;
+        Ok(FocusEvents {
+            _cleanup: register_sequence("\x1b[?1004l"),
+        })
+    }
+}
+
+impl Drop for FocusEvents {
+    fn drop(&mut self) {
+        let _ = write_sequence("\x1b[?1004l");
+    }
+}
+
+// Has the terminal report mouse clicks and the scroll wheel until dropped.
+// While enabled, the terminal no longer selects text on click or scrolls
+// its own history with the wheel.
+#[derive(Debug)]
+#[must_use = "mouse capture is disabled when the guard is dropped"]
+pub struct MouseCapture {
+    _cleanup: CleanupGuard,
+}
+
+impl MouseCapture {
+    // Button presses and releases (1000), reported in SGR format (1006)
+    // rather than as bytes that overflow past column 223
+    pub fn enable() -> io::Result<Self> {
+        write_sequence("\x1b[?1000h\x1b[?1006h")?;
+        Ok(MouseCapture {
+            _cleanup: register_sequence("\x1b[?1006l\x1b[?1000l"),
+        })
+    }
+}
+
+impl Drop for MouseCapture {
+    fn drop(&mut self) {
+        let _ = write_sequence("\x1b[?1006l\x1b[?1000l");
+    }
+}
+
+// Puts the terminal attached to stdin into raw mode until dropped
+pub struct RawMode {
+    #[cfg(unix)]
+    original: libc::termios,
+    #[cfg(unix)]
+    cleanup: Option<crate::cleanup::CleanupGuard>,
+}
+
+impl RawMode {
+    #[cfg(unix)]
+    pub fn enable() -> io::Result<Self> {
+        let mut original = std::mem::MaybeUninit::<libc::termios>::uninit();
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, original.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = unsafe { original.assume_init() };
+
+        // Output processing is left on so that "\n" still returns the carriage
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Cooked mode comes back even if a panic or Ctrl-C skips the drop
+        let cleanup = crate::cleanup::register_cleanup(move || unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original);
+        });
+        Ok(RawMode {
+            original,
+            cleanup: Some(cleanup),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn enable() -> io::Result<Self> {
+        Err(unsupported())
+    }
+
+    // Restores the terminal, reporting failures that dropping the guard ignores
+    pub fn disable(mut self) -> io::Result<()> {
+        let result = self.restore();
+        #[cfg(unix)]
+        drop(self.cleanup.take());
+        std::mem::forget(self);
+        result
+    }
+
+    #[cfg(unix)]
+    fn restore(&self) -> io::Result<()> {
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn restore(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+#[cfg(unix)]
+pub fn read_key() -> io::Result<Key> {
+    Ok(parse_key(&read_sequence()?))
+}
+
+#[cfg(not(unix))]
+pub fn read_key() -> io::Result<Key> {
+    Err(unsupported())
+}
+
+// Like `read_key`, but also reports pastes and focus changes
+#[cfg(unix)]
+pub fn read_event() -> io::Result<Event> {
+    Ok(parse_event(&read_sequence()?))
+}
+
+#[cfg(not(unix))]
+pub fn read_event() -> io::Result<Event> {
+    Err(unsupported())
+}
+
+// Bytes read from stdin past the last sequence returned. Keys that arrive
+// together, like a held-down arrow or fast typing, are read at once and
+// handed out one at a time.
+#[cfg(unix)]
+static PENDING: std::sync::Mutex<Vec<u8>> = std::sync::Mutex::new(Vec::new());
+
+#[cfg(unix)]
+fn read_sequence() -> io::Result<Vec<u8>> {
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    next_sequence(&mut pending, read_stdin, stdin_ready)
+}
+
+// Takes the first complete key or event off `pending`, reading more with
+// `read` until there is one
+fn next_sequence<R, W>(pending: &mut Vec<u8>, mut read: R, mut ready: W) -> io::Result<Vec<u8>>
+where
+    R: FnMut(&mut [u8]) -> io::Result<usize>,
+    W: FnMut(i32) -> io::Result<bool>,
+{
+    let mut buf = [0u8; 1024];
+    loop {
+        if let Some(len) = sequence_len(pending) {
+            return Ok(pending.drain(..len).collect());
+        }
+        // A lone ESC is either the escape key or the start of a sequence
+        // whose remaining bytes have not arrived yet; anything else cut short
+        // is taken as it is after a moment. Pastes are waited for in full.
+        if !pending.is_empty() && !pending.starts_with(PASTE_START) && !ready(25)? {
+            return Ok(std::mem::take(pending));
+        }
+        let n = read(&mut buf)?;
+        pending.extend_from_slice(&buf[..n]);
+    }
+}
+
+// The length of the key or event `bytes` starts with, or `None` when it
+// hasn't been read in full
+fn sequence_len(bytes: &[u8]) -> Option<usize> {
+    match bytes {
+        [] | [ESC] => None,
+        [ESC, b'[', rest @ ..] => {
+            if bytes.starts_with(PASTE_START) {
+                return find(bytes, PASTE_END).map(|end| end + PASTE_END.len());
+            }
+            // Parameters and intermediates, then one final byte
+            let end = rest.iter().position(|b| !(0x20..=0x3f).contains(b))?;
+            Some(2 + end + 1)
+        }
+        [ESC, b'O'] => None,
+        [ESC, b'O', _, ..] => Some(3),
+        // Escape pressed twice, or with alt held
+        [ESC, ESC, ..] => Some(1),
+        [ESC, rest @ ..] => Some(1 + char_len(rest)?),
+        _ => char_len(bytes),
+    }
+}
+
+// The length of the UTF-8 character `bytes` starts with; a byte that can't
+// start one stands alone
+fn char_len(bytes: &[u8]) -> Option<usize> {
+    let len = match bytes[0] {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    };
+    (bytes.len() >= len).then_some(len)
+}
+
+#[cfg(unix)]
+pub(crate) fn read_stdin(buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+        if n > 0 {
+            return Ok(n as usize);
+        }
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn stdin_ready(timeout_ms: i32) -> io::Result<bool> {
+    let mut fd = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    match unsafe { libc::poll(&mut fd, 1, timeout_ms) } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(false),
+        _ => Ok(true),
+    }
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "raw keyboard input is only supported on unix terminals",
+    )
+}
+
+fn parse_event(bytes: &[u8]) -> Event {
+    if let Some(rest) = bytes.strip_prefix(PASTE_START) {
+        let end = find(rest, PASTE_END).unwrap_or(rest.len());
+        // Terminals send line breaks in pastes as carriage returns
+        let text = String::from_utf8_lossy(&rest[..end])
+            .replace("\r\n", "\n")
+            .replace('\r', "\n");
+        return Event::Paste(text);
+    }
+    if let Some(mouse) = bytes.strip_prefix(b"\x1b[<").and_then(parse_mouse) {
+        return Event::Mouse(mouse);
+    }
+    match bytes {
+        [ESC, b'[', b'I'] => Event::FocusGained,
+        [ESC, b'[', b'O'] => Event::FocusLost,
+        _ => Event::Key(parse_key(bytes)),
+    }
+}
+
+// SGR mouse reports are `CSI < button ; column ; row M`, ending in `m` for
+// a release, with 1-based coordinates
+fn parse_mouse(bytes: &[u8]) -> Option<MouseEvent> {
+    let (&last, params) = bytes.split_last()?;
+    let params = std::str::from_utf8(params).ok()?;
+    let mut numbers = params.split(';').map(|n| n.parse::<usize>().ok());
+    let (code, column, row) = (numbers.next()??, numbers.next()??, numbers.next()??);
+    // Bits 2-4 are the shift, alt and ctrl modifiers, 5 is motion
+    let button = match code & 0b11 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        _ => MouseButton::Right,
+    };
+    let kind = match (code & !0b11100, last) {
+        (64, b'M') => MouseKind::ScrollUp,
+        (65, b'M') => MouseKind::ScrollDown,
+        (0..=2, b'M') => MouseKind::Press(button),
+        (0..=2, b'm') => MouseKind::Release(button),
+        _ => return None,
+    };
+    Some(MouseEvent {
+        kind,
+        column: column.checked_sub(1)?,
+        row: row.checked_sub(1)?,
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn parse_key(bytes: &[u8]) -> Key {
+    match bytes {
+        [ESC] => Key::Escape,
+        [ESC, b'[' | b'O', b'A'] => Key::Up,
+        [ESC, b'[' | b'O', b'B'] => Key::Down,
+        [ESC, b'[' | b'O', b'C'] => Key::Right,
+        [ESC, b'[' | b'O', b'D'] => Key::Left,
+        [ESC, b'[' | b'O', b'H'] | [ESC, b'[', b'1' | b'7', b'~'] => Key::Home,
+        [ESC, b'[' | b'O', b'F'] | [ESC, b'[', b'4' | b'8', b'~'] => Key::End,
+        [ESC, b'[', b'3', b'~'] => Key::Delete,
+        [ESC, b'[', b'5', b'~'] => Key::PageUp,
+        [ESC, b'[', b'6', b'~'] => Key::PageDown,
+        [ESC, b'[', b'Z'] => Key::BackTab,
+        [b'\r'] | [b'\n'] => Key::Enter,
+        [0x7f] | [0x08] => Key::Backspace,
+        [b'\t'] => Key::Tab,
+        [0x03] => Key::CtrlC,
+        [byte @ 0x01..=0x1a] => Key::Ctrl((b'a' + byte - 1) as char),
+        _ => match std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.chars().next())
+        {
+            Some(c) if !c.is_control() => Key::Char(c),
+            _ => Key::Unknown,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arrow_keys() {
+        assert_eq!(parse_key(b"\x1b[A"), Key::Up);
+        assert_eq!(parse_key(b"\x1b[B"), Key::Down);
+        assert_eq!(parse_key(b"\x1b[C"), Key::Right);
+        assert_eq!(parse_key(b"\x1b[D"), Key::Left);
+        // Application cursor mode
+        assert_eq!(parse_key(b"\x1bOA"), Key::Up);
+    }
+
+    #[test]
+    fn test_parse_control_keys() {
+        assert_eq!(parse_key(b"\x1b"), Key::Escape);
+        assert_eq!(parse_key(b"\r"), Key::Enter);
+        assert_eq!(parse_key(b"\n"), Key::Enter);
+        assert_eq!(parse_key(b"\x7f"), Key::Backspace);
+        assert_eq!(parse_key(b"\t"), Key::Tab);
+        assert_eq!(parse_key(b"\x03"), Key::CtrlC);
+        assert_eq!(parse_key(b"\x01"), Key::Ctrl('a'));
+        assert_eq!(parse_key(b"\x04"), Key::Ctrl('d'));
+        assert_eq!(parse_key(b"\x1a"), Key::Ctrl('z'));
+        assert_eq!(parse_key(b"\x1c"), Key::Unknown);
+    }
+
+    #[test]
+    fn test_parse_navigation_keys() {
+        assert_eq!(parse_key(b"\x1b[H"), Key::Home);
+        assert_eq!(parse_key(b"\x1b[1~"), Key::Home);
+        assert_eq!(parse_key(b"\x1b[F"), Key::End);
+        assert_eq!(parse_key(b"\x1b[4~"), Key::End);
+        assert_eq!(parse_key(b"\x1b[3~"), Key::Delete);
+        assert_eq!(parse_key(b"\x1b[5~"), Key::PageUp);
+        assert_eq!(parse_key(b"\x1b[6~"), Key::PageDown);
+        assert_eq!(parse_key(b"\x1b[Z"), Key::BackTab);
+    }
+
+    #[test]
+    fn test_parse_chars() {
+        assert_eq!(parse_key(b"a"), Key::Char('a'));
+        assert_eq!(parse_key("é".as_bytes()), Key::Char('é'));
+        assert_eq!(parse_key(b"\x1b[99~"), Key::Unknown);
+    }
+
+    #[test]
+    fn test_coalesced_input_is_split() {
+        let chunks = std::cell::RefCell::new(
+            vec![
+                b"\x1b[A\x1b[Aab".to_vec(),
+                "é\x1b".as_bytes().to_vec(),
+                b"[B\x1b[200~x\x1b".to_vec(),
+                b"[201~\x1b\x1b".to_vec(),
+            ]
+            .into_iter(),
+        );
+        let mut pending = Vec::new();
+        let next = |pending: &mut Vec<u8>| {
+            let sequence = next_sequence(
+                pending,
+                |buf: &mut [u8]| {
+                    let chunk = chunks.borrow_mut().next().expect("read past the input");
+                    buf[..chunk.len()].copy_from_slice(&chunk);
+                    Ok(chunk.len())
+                },
+                |_| Ok(chunks.borrow().len() > 0),
+            )
+            .unwrap();
+            parse_event(&sequence)
+        };
+        let key = |key| Event::Key(key);
+        assert_eq!(next(&mut pending), key(Key::Up));
+        assert_eq!(next(&mut pending), key(Key::Up));
+        assert_eq!(next(&mut pending), key(Key::Char('a')));
+        assert_eq!(next(&mut pending), key(Key::Char('b')));
+        assert_eq!(next(&mut pending), key(Key::Char('é')));
+        // A sequence split across reads is put back together
+        assert_eq!(next(&mut pending), key(Key::Down));
+        assert_eq!(next(&mut pending), Event::Paste("x".to_string()));
+        assert_eq!(next(&mut pending), key(Key::Escape));
+        // With nothing more to come, a lone ESC is the escape key
+        assert_eq!(next(&mut pending), key(Key::Escape));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_sequence_len() {
+        assert_eq!(sequence_len(b"\x1b[5~rest"), Some(4));
+        assert_eq!(sequence_len(b"\x1b[<0;5;3Mx"), Some(9));
+        assert_eq!(sequence_len(b"\x1bOAx"), Some(3));
+        assert_eq!(sequence_len(b"\x1b[1;5"), None);
+        assert_eq!(sequence_len(b"\x1b[200~abc"), None);
+        assert_eq!(sequence_len(&"é".as_bytes()[..1]), None);
+        assert_eq!(sequence_len(b"\x1bx"), Some(2));
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(parse_event(b"\x1b[I"), Event::FocusGained);
+        assert_eq!(parse_event(b"\x1b[O"), Event::FocusLost);
+        assert_eq!(parse_event(b"\x1b[A"), Event::Key(Key::Up));
+        assert_eq!(parse_event(b"q"), Event::Key(Key::Char('q')));
+    }
+
+    #[test]
+    fn test_parse_mouse() {
+        let mouse = |kind, column, row| Event::Mouse(MouseEvent { kind, column, row });
+        assert_eq!(
+            parse_event(b"\x1b[<0;5;3M"),
+            mouse(MouseKind::Press(MouseButton::Left), 4, 2)
+        );
+        assert_eq!(
+            parse_event(b"\x1b[<2;1;1m"),
+            mouse(MouseKind::Release(MouseButton::Right), 0, 0)
+        );
+        assert_eq!(
+            parse_event(b"\x1b[<64;10;20M"),
+            mouse(MouseKind::ScrollUp, 9, 19)
+        );
+        assert_eq!(
+            parse_event(b"\x1b[<65;300;7M"),
+            mouse(MouseKind::ScrollDown, 299, 6)
+        );
+        // Ctrl-click
+        assert_eq!(
+            parse_event(b"\x1b[<16;2;2M"),
+            mouse(MouseKind::Press(MouseButton::Left), 1, 1)
+        );
+        // Motion isn't asked for
+        assert_eq!(parse_event(b"\x1b[<32;2;2M"), Event::Key(Key::Unknown));
+        assert_eq!(parse_event(b"\x1b[<0;2M"), Event::Key(Key::Unknown));
+    }
+
+    #[test]
+    fn test_parse_paste() {
+        assert_eq!(
+            parse_event(b"\x1b[200~cargo build\rcargo test\r\n\x1b[201~"),
+            Event::Paste("cargo build\ncargo test\n".to_string())
+        );
+        assert_eq!(
+            parse_event(b"\x1b[200~\x1b[201~"),
+            Event::Paste(String::new())
+        );
+    }
+}
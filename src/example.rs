@@ -0,0 +1,182 @@
+use std::fmt::{self, Display};
+
+use crate::terminal::terminal_width;
+use crate::theme::{Tone, theme};
+use crate::{Console, Style, unicode};
+
+// A shell command as it appears in help and error messages: a dimmed `$`
+// prompt, the program name in bold, flags in the info color and quoted
+// arguments in the success color. Commands too long for the width are
+// wrapped onto indented lines ending in `\`, so they can still be pasted.
+//
+//   # Build for release
+//   $ mytool build --release \
+//       --target x86_64-unknown-linux-gnu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    command: String,
+    description: Option<String>,
+    width: Option<usize>,
+}
+
+impl Example {
+    pub fn new<T: Into<String>>(command: T) -> Self {
+        Example {
+            command: command.into(),
+            description: None,
+            width: None,
+        }
+    }
+
+    // A dimmed `# comment` line above the command
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    // Width to wrap at, defaults to the terminal width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+}
+
+impl Display for Example {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let muted = theme.style(Tone::Muted).clone();
+        if let Some(description) = &self.description {
+            writeln!(
+                f,
+                "{}",
+                Console::new_with_style(format!("# {}", description), muted.clone())
+            )?;
+        }
+
+        let width = self.width.unwrap_or_else(terminal_width);
+        let words = split_words(&self.command);
+        // Room for the trailing ` \` on lines that continue
+        let limit = width.saturating_sub(2);
+        write!(f, "{} ", Console::new_with_style("$", muted.clone()))?;
+        let mut used = 2;
+        for (i, word) in words.iter().enumerate() {
+            let word_width = unicode::width(word);
+            if i > 0 {
+                if used + 1 + word_width > limit {
+                    writeln!(f, " {}", Console::new_with_style("\\", muted.clone()))?;
+                    write!(f, "    ")?;
+                    used = 4;
+                } else {
+                    write!(f, " ")?;
+                    used += 1;
+                }
+            }
+            write_word(f, word, i == 0)?;
+            used += word_width;
+        }
+        writeln!(f)
+    }
+}
+
+// Writes one word of the command in the style for its kind
+fn write_word(f: &mut fmt::Formatter, word: &str, program: bool) -> fmt::Result {
+    let theme = theme();
+    if program {
+        return write!(f, "{}", Console::new_with_style(word, Style::new().bold()));
+    }
+    if word.starts_with('-') {
+        // The value of `--flag=value` isn't part of the flag
+        let (flag, value) = match word.find('=') {
+            Some(i) => word.split_at(i),
+            None => (word, ""),
+        };
+        return write!(
+            f,
+            "{}{}",
+            Console::new_with_style(flag, theme.style(Tone::Info).clone()),
+            value
+        );
+    }
+    if word.starts_with(['"', '\'']) {
+        return write!(
+            f,
+            "{}",
+            Console::new_with_style(word, theme.style(Tone::Success).clone())
+        );
+    }
+    write!(f, "{}", word)
+}
+
+// Splits at spaces outside of quotes, keeping the quotes
+fn split_words(command: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    for (i, c) in command.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                start.get_or_insert(i);
+            }
+            (None, c) if c.is_whitespace() => {
+                if let Some(start) = start.take() {
+                    words.push(&command[start..i]);
+                }
+            }
+            (None, _) => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(start) = start {
+        words.push(&command[start..]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words("git commit -m \"fix the build\"  --amend"),
+            ["git", "commit", "-m", "\"fix the build\"", "--amend"]
+        );
+        assert_eq!(split_words("echo 'a b'c"), ["echo", "'a b'c"]);
+        assert!(split_words("  ").is_empty());
+    }
+
+    #[test]
+    fn test_styles() {
+        let example = Example::new("git log --format=short 'HEAD~2'").width(80);
+        assert_eq!(
+            example.to_string(),
+            format!(
+                "{} {} log {}=short {}\n",
+                Console::new("$").dim(),
+                Console::new("git").bold(),
+                Console::new("--format").cyan(),
+                Console::new("'HEAD~2'").green(),
+            )
+        );
+    }
+
+    #[test]
+    fn test_long_commands_wrap() {
+        let example = Example::new("mytool build --release --target x86_64-unknown-linux-gnu")
+            .description("Build for release")
+            .width(30);
+        assert_eq!(
+            strip_ansi(&example.to_string()),
+            "# Build for release\n\
+             $ mytool build --release \\\n    \
+             --target \\\n    \
+             x86_64-unknown-linux-gnu\n"
+        );
+    }
+}
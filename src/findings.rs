@@ -0,0 +1,246 @@
+use std::fmt::{self, Display};
+
+use crate::theme::{Tone, theme};
+use crate::{Console, Style, unicode};
+
+// One diagnostic from a linter or compiler. Its tone is its severity,
+// usually `Error`, `Warning` or `Info`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    tone: Tone,
+    path: String,
+    line: usize,
+    column: usize,
+    message: String,
+    rule: Option<String>,
+}
+
+impl Finding {
+    pub fn new<P: Into<String>, M: Into<String>>(
+        tone: Tone,
+        path: P,
+        line: usize,
+        column: usize,
+        message: M,
+    ) -> Self {
+        Finding {
+            tone,
+            path: path.into(),
+            line,
+            column,
+            message: message.into(),
+            rule: None,
+        }
+    }
+
+    pub fn error<P: Into<String>, M: Into<String>>(
+        path: P,
+        line: usize,
+        column: usize,
+        message: M,
+    ) -> Self {
+        Self::new(Tone::Error, path, line, column, message)
+    }
+
+    pub fn warning<P: Into<String>, M: Into<String>>(
+        path: P,
+        line: usize,
+        column: usize,
+        message: M,
+    ) -> Self {
+        Self::new(Tone::Warning, path, line, column, message)
+    }
+
+    // The name of the check that raised it, shown dimmed at the end
+    pub fn rule<T: Into<String>>(mut self, rule: T) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+}
+
+// Diagnostics grouped by file like eslint's default output: each file's
+// path as a header, then its findings in order of position as aligned
+// `line:col  level  message  [rule]` rows, and a count of the problems at
+// the end.
+//
+//   src/main.rs
+//     3:7   error    unused variable `x`  [unused]
+//     12:1  warning  line is too long     [max-width]
+//
+//   ✗ 2 problems (1 error, 1 warning)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Findings {
+    findings: Vec<Finding>,
+}
+
+impl Findings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, finding: Finding) {
+        self.findings.push(finding);
+    }
+
+    pub fn finding(mut self, finding: Finding) -> Self {
+        self.push(finding);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    pub fn print(&self) {
+        crate::ignore_broken_pipe(crate::group::write_stdout(&self.to_string()));
+    }
+
+    // Files in the order they were first seen, each with its findings
+    // sorted by position
+    fn by_file(&self) -> Vec<(&str, Vec<&Finding>)> {
+        let mut files: Vec<(&str, Vec<&Finding>)> = Vec::new();
+        for finding in &self.findings {
+            match files.iter_mut().find(|(path, _)| *path == finding.path) {
+                Some((_, findings)) => findings.push(finding),
+                None => files.push((&finding.path, vec![finding])),
+            }
+        }
+        for (_, findings) in &mut files {
+            findings.sort_by_key(|f| (f.line, f.column));
+        }
+        files
+    }
+}
+
+impl Extend<Finding> for Findings {
+    fn extend<I: IntoIterator<Item = Finding>>(&mut self, findings: I) {
+        self.findings.extend(findings);
+    }
+}
+
+impl Display for Findings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.findings.is_empty() {
+            return Ok(());
+        }
+        let theme = theme();
+        let muted = theme.style(Tone::Muted).clone();
+        for (path, findings) in self.by_file() {
+            writeln!(
+                f,
+                "{}",
+                Console::new_with_style(path, Style::new().bold().underline())
+            )?;
+            let positions: Vec<String> = findings
+                .iter()
+                .map(|f| format!("{}:{}", f.line, f.column))
+                .collect();
+            let levels: Vec<String> = findings
+                .iter()
+                .map(|f| f.tone.label().to_lowercase())
+                .collect();
+            let width = |texts: &[String]| texts.iter().map(|t| unicode::width(t)).max();
+            let position_width = width(&positions).unwrap_or(0);
+            let level_width = width(&levels).unwrap_or(0);
+            let message_width = findings
+                .iter()
+                .map(|f| unicode::width(&f.message))
+                .max()
+                .unwrap_or(0);
+            for ((finding, position), level) in findings.iter().zip(&positions).zip(&levels) {
+                let mut row = format!(
+                    "  {}  {}  {}",
+                    Console::new_with_style(
+                        format!("{:<1$}", position, position_width),
+                        muted.clone()
+                    ),
+                    Console::new_with_style(
+                        format!(
+                            "{}{}",
+                            level,
+                            " ".repeat(level_width - unicode::width(level))
+                        ),
+                        theme.style(finding.tone).clone()
+                    ),
+                    finding.message,
+                );
+                if let Some(rule) = &finding.rule {
+                    let padding = message_width - unicode::width(&finding.message);
+                    row.push_str(&" ".repeat(padding + 2));
+                    row.push_str(
+                        &Console::new_with_style(format!("[{}]", rule), muted.clone()).to_string(),
+                    );
+                }
+                writeln!(f, "{}", row)?;
+            }
+            writeln!(f)?;
+        }
+
+        let count = |tone: Tone| self.findings.iter().filter(|f| f.tone == tone).count();
+        let plural = |count: usize, word: &str| {
+            format!("{} {}{}", count, word, if count == 1 { "" } else { "s" })
+        };
+        let mut counts = Vec::new();
+        for tone in [Tone::Error, Tone::Warning, Tone::Info] {
+            let n = count(tone);
+            if n > 0 {
+                counts.push(plural(n, &tone.label().to_lowercase()));
+            }
+        }
+        let worst = if count(Tone::Error) > 0 {
+            Tone::Error
+        } else {
+            Tone::Warning
+        };
+        let mut summary = format!("{} {}", theme.icon(worst), plural(self.len(), "problem"));
+        if !counts.is_empty() {
+            summary.push_str(&format!(" ({})", counts.join(", ")));
+        }
+        writeln!(
+            f,
+            "{}",
+            Console::new_with_style(summary, theme.style(worst).clone().bold())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_grouped_by_file() {
+        let findings = Findings::new()
+            .finding(Finding::warning("src/main.rs", 12, 1, "line is too long").rule("max-width"))
+            .finding(Finding::error("src/lib.rs", 4, 9, "missing docs"))
+            .finding(Finding::error("src/main.rs", 3, 7, "unused variable `x`").rule("unused"));
+        assert_eq!(
+            strip_ansi(&findings.to_string()),
+            "src/main.rs\n  \
+             3:7   error    unused variable `x`  [unused]\n  \
+             12:1  warning  line is too long     [max-width]\n\
+             \n\
+             src/lib.rs\n  \
+             4:9  error  missing docs\n\
+             \n\
+             ✗ 3 problems (2 errors, 1 warning)\n"
+        );
+    }
+
+    #[test]
+    fn test_colors() {
+        let findings = Findings::new().finding(Finding::warning("a.rs", 1, 1, "hmm"));
+        let output = findings.to_string();
+        assert!(output.contains(&Console::new("warning").yellow().to_string()));
+        assert!(output.ends_with(&format!(
+            "{}\n",
+            Console::new("⚠ 1 problem (1 warning)").yellow().bold()
+        )));
+        assert_eq!(Findings::new().to_string(), "");
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt::{self, Display};
+use std::time::{Duration, SystemTime};
+
+use crate::theme::{Tone, theme};
+use crate::{Console, Style, StyledText};
+
+// Sizes in binary units, e.g. `1.4 GiB`, with the unit dimmed
+pub fn human_bytes(bytes: u64) -> StyledText {
+    const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    let (value, unit) = scale(bytes as f64, 1024.0, &UNITS);
+    quantity(&value, &format!(" {}", unit))
+}
+
+// Sizes in decimal units, e.g. `1.5 GB`, as disk and network vendors
+// count them, with the unit dimmed
+pub fn human_bytes_decimal(bytes: u64) -> StyledText {
+    const UNITS: [&str; 7] = ["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+    let (value, unit) = scale(bytes as f64, 1000.0, &UNITS);
+    quantity(&value, &format!(" {}", unit))
+}
+
+// Durations as their two largest units, e.g. `2m 13s` or `1h 5m`, down to
+// `4.2s`, `350ms` and `12µs` for short ones
+pub fn human_duration(duration: Duration) -> StyledText {
+    let seconds = duration.as_secs();
+    let parts: [(u64, &str); 2] = match seconds {
+        86_400.. => [(seconds / 86_400, "d"), (seconds % 86_400 / 3600, "h")],
+        3600.. => [(seconds / 3600, "h"), (seconds % 3600 / 60, "m")],
+        60.. => [(seconds / 60, "m"), (seconds % 60, "s")],
+        _ => {
+            let (value, unit) = match duration.as_micros() {
+                1_000_000.. => (one_decimal(duration.as_secs_f64()), "s"),
+                1000.. => (duration.as_millis().to_string(), "ms"),
+                micros => (micros.to_string(), "µs"),
+            };
+            return quantity(&value, unit);
+        }
+    };
+    let mut text = quantity(&parts[0].0.to_string(), parts[0].1);
+    if parts[1].0 > 0 {
+        text.extend(
+            quantity(&format!(" {}", parts[1].0), parts[1].1)
+                .segments()
+                .iter()
+                .cloned(),
+        );
+    }
+    text
+}
+
+// Large counts abbreviated with a suffix, e.g. `950`, `12.3k`, `4.5M`
+pub fn human_count(count: u64) -> StyledText {
+    const UNITS: [&str; 7] = ["", "k", "M", "B", "T", "P", "E"];
+    let (value, unit) = scale(count as f64, 1000.0, &UNITS);
+    quantity(&value, unit)
+}
+
+// How long ago a moment was, e.g. `3 minutes ago`, `in 2 days` for future
+// times, or `just now`
+pub fn human_time_ago(time: SystemTime) -> TimeAgo {
+    TimeAgo {
+        time,
+        now: None,
+        precision: 1,
+        dim: false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeAgo {
+    time: SystemTime,
+    now: Option<SystemTime>,
+    precision: usize,
+    dim: bool,
+}
+
+impl TimeAgo {
+    // How many units to show, e.g. 2 for `3 minutes 12 seconds ago`
+    pub fn precision(mut self, units: usize) -> Self {
+        self.precision = units.max(1);
+        self
+    }
+
+    // Renders in the muted style, for timestamps beside the main content
+    pub fn dim(mut self, dim: bool) -> Self {
+        self.dim = dim;
+        self
+    }
+
+    // Measures from `now` instead of the current time
+    pub fn relative_to(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    fn text(&self) -> String {
+        const UNITS: [(u64, &str); 7] = [
+            (365 * 86_400, "year"),
+            (30 * 86_400, "month"),
+            (7 * 86_400, "week"),
+            (86_400, "day"),
+            (3600, "hour"),
+            (60, "minute"),
+            (1, "second"),
+        ];
+        let now = self.now.unwrap_or_else(SystemTime::now);
+        let (mut seconds, future) = match now.duration_since(self.time) {
+            Ok(elapsed) => (elapsed.as_secs(), false),
+            Err(err) => (err.duration().as_secs(), true),
+        };
+
+        let mut parts = Vec::new();
+        for (size, name) in UNITS {
+            if parts.len() == self.precision {
+                break;
+            }
+            let count = seconds / size;
+            seconds %= size;
+            if count > 0 {
+                parts.push(format!(
+                    "{} {}{}",
+                    count,
+                    name,
+                    if count == 1 { "" } else { "s" }
+                ));
+            } else if !parts.is_empty() {
+                // Stop at the first gap so `1 hour 0 minutes` never shows
+                break;
+            }
+        }
+        match (parts.is_empty(), future) {
+            (true, _) => "just now".to_string(),
+            (false, false) => format!("{} ago", parts.join(" ")),
+            (false, true) => format!("in {}", parts.join(" ")),
+        }
+    }
+}
+
+impl Display for TimeAgo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = if self.dim {
+            theme().style(Tone::Muted).clone()
+        } else {
+            Style::new()
+        };
+        Console::new_with_style(self.text(), style).fmt(f)
+    }
+}
+
+// Divides `value` down until it's below `base`, with one decimal once
+// scaled and none for the base unit
+fn scale<'a>(mut value: f64, base: f64, units: &[&'a str]) -> (String, &'a str) {
+    let mut unit = 0;
+    // Round first so 1023.96 KiB becomes 1 MiB rather than 1024 KiB
+    while unit + 1 < units.len() && (value * 10.0).round() / 10.0 >= base {
+        value /= base;
+        unit += 1;
+    }
+    let value = if unit == 0 {
+        format!("{}", value as u64)
+    } else {
+        one_decimal(value)
+    };
+    (value, units[unit])
+}
+
+// One decimal place, dropping a trailing `.0`
+fn one_decimal(value: f64) -> String {
+    let text = format!("{:.1}", value);
+    match text.strip_suffix(".0") {
+        Some(whole) => whole.to_string(),
+        None => text,
+    }
+}
+
+// A number followed by its dimmed unit
+pub(crate) fn quantity(value: &str, unit: &str) -> StyledText {
+    let mut text = StyledText::from(Console::new_with_style(value, Default::default()));
+    if !unit.is_empty() {
+        text.push(Console::new_with_style(
+            unit,
+            theme().style(Tone::Muted).clone(),
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_bytes() {
+        assert_eq!(human_bytes(0).plain(), "0 B");
+        assert_eq!(human_bytes(1023).plain(), "1023 B");
+        assert_eq!(human_bytes(1024).plain(), "1 KiB");
+        assert_eq!(human_bytes(1536).plain(), "1.5 KiB");
+        assert_eq!(human_bytes(1_048_575).plain(), "1 MiB");
+        assert_eq!(human_bytes(1_503_238_554).plain(), "1.4 GiB");
+        assert_eq!(human_bytes(u64::MAX).plain(), "16 EiB");
+    }
+
+    #[test]
+    fn test_human_bytes_decimal() {
+        assert_eq!(human_bytes_decimal(999).plain(), "999 B");
+        assert_eq!(human_bytes_decimal(1000).plain(), "1 kB");
+        assert_eq!(human_bytes_decimal(1_500_000).plain(), "1.5 MB");
+        assert_eq!(human_bytes_decimal(1024).plain(), "1 kB");
+    }
+
+    #[test]
+    fn test_human_duration() {
+        let cases = [
+            (Duration::from_micros(12), "12µs"),
+            (Duration::from_millis(350), "350ms"),
+            (Duration::from_millis(4_230), "4.2s"),
+            (Duration::from_secs(133), "2m 13s"),
+            (Duration::from_secs(120), "2m"),
+            (Duration::from_secs(3_900), "1h 5m"),
+            (Duration::from_secs(273_600), "3d 4h"),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(human_duration(duration).plain(), expected);
+        }
+    }
+
+    #[test]
+    fn test_human_count() {
+        assert_eq!(human_count(950).plain(), "950");
+        assert_eq!(human_count(12_345).plain(), "12.3k");
+        assert_eq!(human_count(999_960).plain(), "1M");
+        assert_eq!(human_count(4_500_000).plain(), "4.5M");
+    }
+
+    #[test]
+    fn test_human_time_ago() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let ago = |seconds| human_time_ago(now - Duration::from_secs(seconds)).relative_to(now);
+        assert_eq!(ago(0).text(), "just now");
+        assert_eq!(ago(1).text(), "1 second ago");
+        assert_eq!(ago(200).text(), "3 minutes ago");
+        assert_eq!(ago(200).precision(2).text(), "3 minutes 20 seconds ago");
+        assert_eq!(ago(3_605).precision(2).text(), "1 hour ago");
+        assert_eq!(ago(90 * 86_400).precision(3).text(), "3 months ago");
+        assert_eq!(ago(400 * 86_400).text(), "1 year ago");
+
+        let later = human_time_ago(now + Duration::from_secs(2 * 86_400)).relative_to(now);
+        assert_eq!(later.to_string(), "in 2 days");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_units_are_dimmed() {
+        assert_eq!(
+            human_bytes(2048).to_string(),
+            format!("2{}", Console::new(" KiB").dim())
+        );
+        let now = SystemTime::UNIX_EPOCH;
+        assert_eq!(
+            human_time_ago(now).relative_to(now).dim(true).to_string(),
+            Console::new("just now").dim().to_string()
+        );
+    }
+}
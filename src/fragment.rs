@@ -0,0 +1,211 @@
+use textwrap::core::Fragment;
+use textwrap::wrap_algorithms::wrap_first_fit;
+
+use crate::{StyledText, unicode};
+
+// A word of styled text and the spaces after it, measured in terminal
+// columns so textwrap's wrapping algorithms can lay it out like its own
+// `Word`s
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StyledWord {
+    word: StyledText,
+    whitespace: StyledText,
+}
+
+impl StyledWord {
+    pub fn word(&self) -> &StyledText {
+        &self.word
+    }
+
+    pub fn whitespace(&self) -> &StyledText {
+        &self.whitespace
+    }
+
+    // Cuts a word wider than `width` into pieces that fit, the spaces going
+    // with the last one
+    fn split(self, width: usize) -> Vec<StyledWord> {
+        let mut pieces = Vec::new();
+        let mut rest = self.word;
+        while rest.width() > width {
+            let (mut head, mut tail) = rest.split_at_visible(width);
+            if head.is_empty() {
+                // A cluster wider than the line still has to go somewhere
+                let first = tail
+                    .graphemes()
+                    .next()
+                    .map_or(0, |(g, _)| unicode::width(g));
+                (head, tail) = tail.split_at_visible(first);
+            }
+            rest = tail;
+            pieces.push(StyledWord {
+                word: head,
+                whitespace: StyledText::new(),
+            });
+        }
+        match pieces.last_mut() {
+            Some(last) if rest.is_empty() => last.whitespace = self.whitespace,
+            _ => pieces.push(StyledWord {
+                word: rest,
+                whitespace: self.whitespace,
+            }),
+        }
+        pieces
+    }
+}
+
+impl Fragment for StyledWord {
+    fn width(&self) -> f64 {
+        self.word.width() as f64
+    }
+
+    fn whitespace_width(&self) -> f64 {
+        self.whitespace.width() as f64
+    }
+
+    fn penalty_width(&self) -> f64 {
+        0.0
+    }
+}
+
+impl StyledText {
+    // Splits at spaces into fragments for textwrap's wrapping algorithms,
+    // keeping each character's style. Line breaks are not treated specially;
+    // wrap one line at a time.
+    pub fn words(&self) -> Vec<StyledWord> {
+        let mut words = Vec::new();
+        let mut current = StyledWord::default();
+        for (cluster, style) in self.graphemes() {
+            if cluster == " " {
+                current.whitespace.push_styled(cluster, style);
+                continue;
+            }
+            if !current.whitespace.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.word.push_styled(cluster, style);
+        }
+        if !current.word.is_empty() || !current.whitespace.is_empty() {
+            words.push(current);
+        }
+        words
+    }
+
+    // Joins a wrapped line back together, leaving off the spaces after its
+    // last word
+    pub fn from_words(words: &[StyledWord]) -> StyledText {
+        let mut line = StyledText::new();
+        for (i, word) in words.iter().enumerate() {
+            let mut parts = vec![&word.word];
+            if i + 1 < words.len() {
+                parts.push(&word.whitespace);
+            }
+            for segment in parts.into_iter().flat_map(StyledText::segments) {
+                line.push_styled(&segment.text, &segment.style);
+            }
+        }
+        line
+    }
+}
+
+// Wraps styled text to `width` columns with textwrap's first-fit algorithm.
+// Existing line breaks are kept and words longer than a line are split.
+pub fn wrap_styled(text: &StyledText, width: usize) -> Vec<StyledText> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in split_lines(text) {
+        let words: Vec<StyledWord> = paragraph
+            .words()
+            .into_iter()
+            .flat_map(|word| word.split(width))
+            .collect();
+        if words.is_empty() {
+            lines.push(StyledText::new());
+            continue;
+        }
+        for line in wrap_first_fit(&words, &[width as f64]) {
+            lines.push(StyledText::from_words(line));
+        }
+    }
+    lines
+}
+
+fn split_lines(text: &StyledText) -> Vec<StyledText> {
+    let mut lines = vec![StyledText::new()];
+    for (cluster, style) in text.graphemes() {
+        match cluster {
+            "\n" | "\r\n" => lines.push(StyledText::new()),
+            _ => lines.last_mut().unwrap().push_styled(cluster, style),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Console, Style};
+
+    fn plain(lines: &[StyledText]) -> Vec<String> {
+        lines.iter().map(StyledText::plain).collect()
+    }
+
+    #[test]
+    fn test_words_keep_styles() {
+        let text = StyledText::new()
+            .then(Console::new("error: "))
+            .then(Console::new_with_style(
+                "bad  input",
+                Style::new().fg(Color::RED),
+            ));
+        let words = text.words();
+        let parts: Vec<(String, String)> = words
+            .iter()
+            .map(|w| (w.word().plain(), w.whitespace().plain()))
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                ("error:".into(), " ".into()),
+                ("bad".into(), "  ".into()),
+                ("input".into(), "".into())
+            ]
+        );
+        assert_eq!(
+            words[1].word().segments()[0].style.foreground,
+            Some(Color::RED)
+        );
+        assert_eq!(words[1].whitespace_width(), 2.0);
+        assert_eq!(StyledText::from_words(&words), text);
+    }
+
+    #[test]
+    fn test_wrap_styled() {
+        let text = StyledText::new()
+            .then(Console::new("the quick "))
+            .then(Console::new_with_style("brown", Style::new().bold()))
+            .then(Console::new(" fox\n\njumps over"));
+        let lines = wrap_styled(&text, 10);
+        assert_eq!(plain(&lines), ["the quick", "brown fox", "", "jumps over"]);
+        assert_eq!(lines[1].segments()[0].text, "brown");
+        assert_eq!(lines[1].segments()[0].style, Style::new().bold());
+
+        assert_eq!(
+            plain(&wrap_styled(&StyledText::from("abcdefghij xy"), 4)),
+            ["abcd", "efgh", "ij", "xy"]
+        );
+        assert_eq!(
+            plain(&wrap_styled(&StyledText::from("漢字"), 1)),
+            ["漢", "字"]
+        );
+    }
+
+    #[test]
+    fn test_works_with_textwrap_algorithms() {
+        let words = StyledText::from("aaa bb cc ddddd").words();
+        let lines: Vec<String> = wrap_first_fit(&words, &[6.0])
+            .into_iter()
+            .map(|line| StyledText::from_words(line).plain())
+            .collect();
+        assert_eq!(lines, ["aaa bb", "cc", "ddddd"]);
+    }
+}
@@ -0,0 +1,321 @@
+use std::io::{self, Write};
+
+use crate::draw_target::stderr_target;
+use crate::events::{self, Key, RawMode};
+use crate::live::LiveRegion;
+use crate::select::page_range;
+use crate::symbols::symbols;
+use crate::transcript::finish_prompt;
+use crate::{Color, Console, Style};
+
+#[derive(Debug, Clone)]
+pub struct FuzzySelect {
+    prompt: String,
+    items: Vec<String>,
+    max_length: usize,
+    highlight: Style,
+    accent: Style,
+    transcript: bool,
+}
+
+impl FuzzySelect {
+    pub fn new<T: Into<String>>(prompt: T) -> Self {
+        FuzzySelect {
+            prompt: prompt.into(),
+            items: Vec::new(),
+            max_length: 10,
+            highlight: Style::new().fg(Color::CYAN).bold(),
+            accent: Style::new().fg(Color::YELLOW).bold(),
+            transcript: true,
+        }
+    }
+
+    pub fn items<T: ToString>(mut self, items: &[T]) -> Self {
+        self.items.extend(items.iter().map(ToString::to_string));
+        self
+    }
+
+    pub fn item<T: Into<String>>(mut self, item: T) -> Self {
+        self.items.push(item.into());
+        self
+    }
+
+    // Number of matches shown at a time, defaults to 10
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn highlight(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    // Style of the characters matched by the query
+    pub fn accent(mut self, style: Style) -> Self {
+        self.accent = style;
+        self
+    }
+
+    // Whether the chosen item stays on screen as `? Prompt · item` after
+    // the prompt closes, defaults to true
+    pub fn transcript(mut self, enabled: bool) -> Self {
+        self.transcript = enabled;
+        self
+    }
+
+    // Returns the index of the chosen item, or `None` if the user pressed escape
+    pub fn interact(&self) -> io::Result<Option<usize>> {
+        let _raw = RawMode::enable()?;
+        let mut region =
+            LiveRegion::new(stderr_target()).columns(crate::terminal::terminal_width());
+        region.hide_cursor()?;
+        let result = self.interact_on(&mut region, events::read_key);
+        region.show_cursor()?;
+        result
+    }
+
+    fn interact_on<W, F>(
+        &self,
+        region: &mut LiveRegion<W>,
+        mut read_key: F,
+    ) -> io::Result<Option<usize>>
+    where
+        W: Write,
+        F: FnMut() -> io::Result<Key>,
+    {
+        if self.items.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "fuzzy-select prompt has no items",
+            ));
+        }
+
+        let mut query = String::new();
+        let mut matches = self.filter(&query);
+        let mut cursor = 0;
+
+        loop {
+            region.draw(self.render(&query, &matches, cursor))?;
+            match read_key()? {
+                Key::Up if !matches.is_empty() => {
+                    cursor = (cursor + matches.len() - 1) % matches.len()
+                }
+                Key::Down | Key::Tab if !matches.is_empty() => {
+                    cursor = (cursor + 1) % matches.len()
+                }
+                Key::Char(c) => {
+                    query.push(c);
+                    matches = self.filter(&query);
+                    cursor = 0;
+                }
+                Key::Backspace => {
+                    query.pop();
+                    matches = self.filter(&query);
+                    cursor = 0;
+                }
+                Key::Enter if !matches.is_empty() => {
+                    let index = matches[cursor].index;
+                    finish_prompt(region, self.transcript, &self.prompt, &self.items[index])?;
+                    return Ok(Some(index));
+                }
+                Key::Escape => {
+                    region.clear()?;
+                    return Ok(None);
+                }
+                Key::CtrlC => {
+                    region.clear()?;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn filter(&self, query: &str) -> Vec<Match> {
+        let mut matches: Vec<Match> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                fuzzy_match(query, item).map(|(score, positions)| Match {
+                    index,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        // Stable sort keeps the original order among equally good matches
+        matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+        matches
+    }
+
+    fn render(&self, query: &str, matches: &[Match], cursor: usize) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{} {} {} {}",
+            Console::new("?").green().bold(),
+            Console::new(self.prompt.as_str()).bold(),
+            Console::new(symbols().separator).dim(),
+            query
+        )];
+
+        if matches.is_empty() {
+            lines.push(format!("  {}", Console::new("no matches").dim()));
+            return lines;
+        }
+
+        let (start, end) = page_range(cursor, matches.len(), Some(self.max_length));
+        for (i, m) in matches.iter().enumerate().take(end).skip(start) {
+            let base = if i == cursor {
+                self.highlight.clone()
+            } else {
+                Style::new()
+            };
+            let marker = if i == cursor {
+                Console::new_with_style(symbols().pointer, self.highlight.clone()).to_string()
+            } else {
+                " ".to_string()
+            };
+
+            // Group consecutive characters so each run gets a single escape code
+            let mut line = format!("{} ", marker);
+            let mut run = String::new();
+            let mut run_matched = false;
+            for (pos, ch) in self.items[m.index].chars().enumerate() {
+                let matched = m.positions.contains(&pos);
+                if matched != run_matched && !run.is_empty() {
+                    line.push_str(&self.styled_run(&run, run_matched, &base));
+                    run.clear();
+                }
+                run_matched = matched;
+                run.push(ch);
+            }
+            line.push_str(&self.styled_run(&run, run_matched, &base));
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    fn styled_run(&self, run: &str, matched: bool, base: &Style) -> String {
+        let style = if matched { &self.accent } else { base };
+        Console::new_with_style(run, style.clone()).to_string()
+    }
+}
+
+struct Match {
+    index: usize,
+    score: i64,
+    positions: Vec<usize>,
+}
+
+// Case-insensitive subsequence match. Returns a score (higher is better) and
+// the char positions of the matched characters in `candidate`.
+fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+
+    for (pos, ch) in candidate.chars().enumerate() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        if ch.to_lowercase().eq(std::iter::once(wanted)) {
+            score += 1;
+            if positions.last().is_some_and(|&last| last + 1 == pos) {
+                score += 4;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            positions.push(pos);
+            query_chars.next();
+        }
+        previous = Some(ch);
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    // Prefer shorter candidates when everything else is equal
+    score -= candidate.chars().count() as i64 / 8;
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(select: &FuzzySelect, keys: &[Key]) -> io::Result<Option<usize>> {
+        let mut keys = keys.iter().copied();
+        let mut region = LiveRegion::new(Vec::new());
+        select.interact_on(&mut region, || Ok(keys.next().expect("ran out of keys")))
+    }
+
+    fn typed(text: &str) -> Vec<Key> {
+        text.chars().map(Key::Char).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("", "main").map(|m| m.1), Some(vec![]));
+        assert_eq!(
+            fuzzy_match("fb", "feature/bar").map(|m| m.1),
+            Some(vec![0, 8])
+        );
+        assert_eq!(
+            fuzzy_match("MAIN", "main").map(|m| m.1),
+            Some(vec![0, 1, 2, 3])
+        );
+        assert_eq!(fuzzy_match("xyz", "main"), None);
+        assert_eq!(fuzzy_match("nm", "main"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_match_ranking() {
+        let consecutive = fuzzy_match("fix", "fix/login").unwrap().0;
+        let scattered = fuzzy_match("fix", "feature/index").unwrap().0;
+        assert!(consecutive > scattered);
+    }
+
+    #[test]
+    fn test_fuzzy_select_filters_items() {
+        let select = FuzzySelect::new("Branch").items(&["main", "feature/login", "fix/logout"]);
+
+        let mut keys = typed("lgo");
+        keys.push(Key::Enter);
+        assert_eq!(run(&select, &keys).unwrap(), Some(2));
+
+        let mut keys = typed("login");
+        keys.push(Key::Enter);
+        assert_eq!(run(&select, &keys).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_fuzzy_select_backspace_and_navigation() {
+        let select = FuzzySelect::new("Branch").items(&["main", "feature/login", "fix/logout"]);
+
+        let keys = [
+            Key::Char('z'),
+            // No matches, so enter is ignored
+            Key::Enter,
+            Key::Backspace,
+            Key::Down,
+            Key::Enter,
+        ];
+        assert_eq!(run(&select, &keys).unwrap(), Some(1));
+        assert_eq!(run(&select, &[Key::Escape]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_fuzzy_select_render() {
+        let select = FuzzySelect::new("Branch").items(&["main", "feature/login"]);
+        let matches = select.filter("ma");
+        insta::assert_yaml_snapshot!(select.render("ma", &matches, 0));
+    }
+}
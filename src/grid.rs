@@ -0,0 +1,167 @@
+use std::fmt::Write;
+
+use crate::{Block, Style, unicode};
+
+// One terminal cell. A wide character is followed by a cell with no text
+// for the column it spills into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cell {
+    text: String,
+    style: Style,
+}
+
+impl Cell {
+    fn blank() -> Cell {
+        Cell {
+            text: " ".to_string(),
+            style: Style::new(),
+        }
+    }
+}
+
+// A screenful of cells, kept from one frame to the next so only the cells
+// that changed are written again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Grid {
+    columns: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+}
+
+impl Grid {
+    pub(crate) fn new(columns: usize, rows: usize) -> Grid {
+        Grid {
+            columns,
+            rows,
+            cells: vec![Cell::blank(); columns * rows],
+        }
+    }
+
+    // `block` from the top-left corner, cut to the grid
+    pub(crate) fn from_block(block: &Block, columns: usize, rows: usize) -> Grid {
+        let mut grid = Grid::new(columns, rows);
+        for (row, line) in block.lines().iter().take(rows).enumerate() {
+            let mut column = 0;
+            for (text, style) in line.graphemes() {
+                let width = unicode::width(text);
+                if width == 0 {
+                    continue;
+                }
+                if column + width > columns {
+                    break;
+                }
+                let start = row * columns + column;
+                grid.cells[start] = Cell {
+                    text: text.to_string(),
+                    style: style.clone(),
+                };
+                for cell in &mut grid.cells[start + 1..start + width] {
+                    *cell = Cell {
+                        text: String::new(),
+                        style: style.clone(),
+                    };
+                }
+                column += width;
+            }
+        }
+        grid
+    }
+
+    // The output that turns `previous`, what's on screen, into this grid:
+    // a cursor move before each run of changed cells and only the style
+    // changes between them. Without a previous frame of the same size the
+    // screen is cleared first.
+    pub(crate) fn diff(&self, previous: Option<&Grid>) -> String {
+        let mut out = String::new();
+        let blank;
+        let previous = match previous {
+            Some(previous) if (previous.columns, previous.rows) == (self.columns, self.rows) => {
+                previous
+            }
+            _ => {
+                out.push_str("\x1b[0m\x1b[2J");
+                blank = Grid::new(self.columns, self.rows);
+                &blank
+            }
+        };
+
+        let mut style = Style::new();
+        // Where the cursor is after the last cell written
+        let mut cursor = None;
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let i = row * self.columns + column;
+                let cell = &self.cells[i];
+                if cell.text.is_empty() || *cell == previous.cells[i] {
+                    continue;
+                }
+                if cursor != Some((row, column)) {
+                    let _ = write!(out, "\x1b[{};{}H", row + 1, column + 1);
+                }
+                let delta = style.diff(&cell.style);
+                if !delta.is_empty() {
+                    let _ = write!(out, "{}", delta);
+                    style = cell.style.clone();
+                }
+                out.push_str(&cell.text);
+                cursor = Some((row, column + unicode::width(&cell.text)));
+            }
+        }
+        if style != Style::new() && !cfg!(feature = "no-color") {
+            out.push_str("\x1b[0m");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Console, StyledText};
+
+    #[test]
+    fn test_first_frame_clears_and_skips_blanks() {
+        let grid = Grid::from_block(&Block::from("ab\n  c"), 4, 2);
+        assert_eq!(grid.diff(None), "\x1b[0m\x1b[2J\x1b[1;1Hab\x1b[2;3Hc");
+    }
+
+    #[test]
+    fn test_only_changed_cells_are_written() {
+        let before = Grid::from_block(&Block::from("cpu 10%\nmem 2GB"), 8, 2);
+        let after = Grid::from_block(&Block::from("cpu 12%\nmem 2GB"), 8, 2);
+        assert_eq!(after.diff(Some(&before)), "\x1b[1;6H2");
+        assert_eq!(after.diff(Some(&after)), "");
+
+        // A different size starts over
+        let resized = Grid::from_block(&Block::from("cpu 12%"), 9, 2);
+        assert!(resized.diff(Some(&after)).starts_with("\x1b[0m\x1b[2J"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_style_changes_are_minimal() {
+        let line = StyledText::new()
+            .then(Console::new("ok").fg(Color::GREEN))
+            .then(Console::new("!").fg(Color::GREEN).bold());
+        let grid = Grid::from_block(&Block::from(line), 3, 1);
+        assert_eq!(
+            grid.diff(Some(&Grid::new(3, 1))),
+            "\x1b[1;1H\x1b[38;5;2mok\x1b[1m!\x1b[0m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "no-color")]
+    fn test_no_color_writes_plain_cells() {
+        let line = StyledText::new().then(Console::new("ok").fg(Color::GREEN).bold());
+        let grid = Grid::from_block(&Block::from(line), 2, 1);
+        assert_eq!(grid.diff(Some(&Grid::new(2, 1))), "\x1b[1;1Hok");
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_wide_characters() {
+        let grid = Grid::from_block(&Block::from("日本語"), 5, 1);
+        assert_eq!(grid.diff(Some(&Grid::new(5, 1))), "\x1b[1;1H日本");
+    }
+}
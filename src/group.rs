@@ -0,0 +1,283 @@
+use std::cell::Cell;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use crate::draw_target::{DrawTarget, stderr_target, stdout_target};
+use crate::format::human_duration;
+use crate::indent::LinePrefixer;
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::{Console, ignore_broken_pipe};
+
+thread_local! {
+    // Nesting depth of the groups this thread has open
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+// Whether a GitHub Actions log group is open; those can't nest
+static GITHUB_OPEN: AtomicBool = AtomicBool::new(false);
+
+// A titled section of output. Until the guard is dropped, everything the
+// crate prints from the thread that opened it, and from commands that
+// thread runs, is indented under a header, and a footer with the elapsed
+// time closes it. Other threads, like a progress bar's ticker, print as
+// usual. On GitHub Actions the outermost group becomes a collapsible
+// `::group::` in the job log instead.
+#[derive(Debug)]
+pub struct Group {
+    start: Instant,
+    github: bool,
+    ended: bool,
+    // Where the header and footer go; the stdout stream when `None`
+    out: Option<DrawTarget>,
+}
+
+impl Group {
+    pub fn begin<T: Into<String>>(title: T) -> Self {
+        let github_actions = std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true");
+        Self::open(None, &title.into(), github_actions)
+    }
+
+    fn open(out: Option<DrawTarget>, title: &str, github_actions: bool) -> Self {
+        let github = github_actions && !GITHUB_OPEN.swap(true, Ordering::SeqCst);
+        let group = Group {
+            start: Instant::now(),
+            github,
+            ended: false,
+            out,
+        };
+        if github {
+            group.write(&format!("::group::{}\n", title));
+        } else {
+            group.write(&format!("{}\n", header(title)));
+            DEPTH.set(DEPTH.get() + 1);
+        }
+        group
+    }
+
+    pub fn end(mut self) {
+        self.close();
+    }
+
+    fn close(&mut self) {
+        self.ended = true;
+        let elapsed = human_duration(self.start.elapsed()).plain();
+        if self.github {
+            self.write("::endgroup::\n");
+            GITHUB_OPEN.store(false, Ordering::SeqCst);
+        } else {
+            DEPTH.set(DEPTH.get().saturating_sub(1));
+            self.write(&format!("{}\n", footer(&elapsed)));
+        }
+    }
+
+    fn write(&self, text: &str) {
+        ignore_broken_pipe(match &self.out {
+            None => write_stdout(text),
+            Some(out) => out.write_locked(|out| out.write_all(prefix_lines(text).as_bytes())),
+        });
+    }
+}
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        if !self.ended {
+            self.close();
+        }
+    }
+}
+
+fn header(title: &str) -> String {
+    format!(
+        "{} {}",
+        Console::new_with_style(
+            symbols().border.top_left,
+            theme().style(Tone::Muted).clone()
+        ),
+        Console::new_with_style(title, theme().style(Tone::Info).clone().bold())
+    )
+}
+
+fn footer(elapsed: &str) -> String {
+    Console::new_with_style(
+        format!("{} {}", symbols().border.bottom_left, elapsed),
+        theme().style(Tone::Muted).clone(),
+    )
+    .to_string()
+}
+
+// What goes in front of each line at the given depth
+fn prefix_for(depth: usize) -> String {
+    if depth == 0 {
+        return String::new();
+    }
+    Console::new_with_style(
+        format!("{} ", symbols().border.vertical).repeat(depth),
+        theme().style(Tone::Muted).clone(),
+    )
+    .to_string()
+}
+
+fn current_prefix(_line: usize) -> String {
+    prefix_for(DEPTH.get())
+}
+
+// The calling thread's group depth, for a thread started on its behalf to
+// take on with `set_depth`
+pub(crate) fn depth() -> usize {
+    DEPTH.get()
+}
+
+pub(crate) fn set_depth(depth: usize) {
+    DEPTH.set(depth);
+}
+
+// Prefixes whole lines for writers the crate doesn't track, like a
+// `Reporter`'s, which always start writing at the beginning of a line
+pub(crate) fn prefix_lines(text: &str) -> String {
+    if DEPTH.get() == 0 {
+        return text.to_string();
+    }
+    let mut out = Vec::new();
+    LinePrefixer::with(current_prefix).process(text.as_bytes(), &mut out);
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Stdout and stderr each remember whether they're mid-line, so text printed
+// in pieces is only prefixed once per line
+fn stream(stderr: bool) -> &'static Mutex<LinePrefixer> {
+    static STDOUT: OnceLock<Mutex<LinePrefixer>> = OnceLock::new();
+    static STDERR: OnceLock<Mutex<LinePrefixer>> = OnceLock::new();
+    let cell = if stderr { &STDERR } else { &STDOUT };
+    cell.get_or_init(|| Mutex::new(LinePrefixer::with(current_prefix)))
+}
+
+fn prefix_stream(stderr: bool, text: &str) -> Vec<u8> {
+    let mut prefixer = stream(stderr).lock().unwrap_or_else(|e| e.into_inner());
+    let mut out = Vec::with_capacity(text.len());
+    prefixer.process(text.as_bytes(), &mut out);
+    out
+}
+
+// The target's lock is always taken before the prefixer's so callers that
+// already hold it, like `Console::print_locked`, can't deadlock
+fn write_stream(stderr: bool, text: &str) -> io::Result<()> {
+    let target = if stderr {
+        stderr_target()
+    } else {
+        stdout_target()
+    };
+    target.write_locked(|out| out.write_all(&prefix_stream(stderr, text)))
+}
+
+// Prefixes text about to be written to stdout by a caller holding its lock
+pub(crate) fn prefix_stdout(text: &str) -> String {
+    String::from_utf8_lossy(&prefix_stream(false, text)).into_owned()
+}
+
+pub(crate) fn write_stdout(text: &str) -> io::Result<()> {
+    write_stream(false, text)
+}
+
+pub(crate) fn write_stderr(text: &str) -> io::Result<()> {
+    write_stream(true, text)
+}
+
+// Held by tests that redirect stdout or stderr, so they don't capture each
+// other's output
+#[cfg(test)]
+pub(crate) fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    // The lines `run` writes through `print` while groups write to the same
+    // buffer, without styling
+    fn capture<F: FnOnce(&DrawTarget)>(run: F) -> Vec<String> {
+        let buffer = DrawTarget::buffer();
+        run(&buffer);
+        strip_ansi(&buffer.contents())
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    fn print(out: &DrawTarget, line: &str) {
+        let text = prefix_lines(&format!("{}\n", line));
+        out.write_locked(|out| out.write_all(text.as_bytes()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_nested_groups_indent() {
+        let lines = capture(|out| {
+            let _outer = Group::open(Some(out.clone()), "Build", false);
+            print(out, "compiling");
+            let inner = Group::open(Some(out.clone()), "Test", false);
+            print(out, "ok");
+            inner.end();
+        });
+        assert_eq!(lines[..4], ["╭ Build", "│ compiling", "│ ╭ Test", "│ │ ok"]);
+        // Footers close each group with the time it took
+        assert_eq!(lines.len(), 6);
+        assert!(lines[4].starts_with("│ ╰ ") && lines[4].ends_with('s'));
+        assert!(lines[5].starts_with("╰ ") && lines[5].ends_with('s'));
+        assert_eq!(depth(), 0);
+    }
+
+    #[test]
+    fn test_github_actions_groups() {
+        let lines = capture(|out| {
+            let _outer = Group::open(Some(out.clone()), "Build", true);
+            print(out, "compiling");
+            // Log groups can't nest, so inner ones indent as usual
+            let _inner = Group::open(Some(out.clone()), "Test", true);
+            print(out, "ok");
+        });
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[..4],
+            ["::group::Build", "compiling", "╭ Test", "│ ok"]
+        );
+        assert!(lines[4].starts_with("╰ "));
+        assert_eq!(lines[5], "::endgroup::");
+        assert!(!GITHUB_OPEN.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_groups_indent_their_own_thread() {
+        let _group = Group::open(Some(DrawTarget::hidden()), "Build", false);
+        assert_eq!(strip_ansi(&prefix_lines("a\n")), "│ a\n");
+        let other = std::thread::spawn(|| prefix_lines("b\n")).join().unwrap();
+        assert_eq!(other, "b\n");
+        let inherited = std::thread::spawn({
+            let depth = depth();
+            move || {
+                set_depth(depth);
+                prefix_lines("c\n")
+            }
+        })
+        .join()
+        .unwrap();
+        assert_eq!(strip_ansi(&inherited), "│ c\n");
+    }
+
+    #[test]
+    fn test_prefix_for_depth() {
+        assert_eq!(prefix_for(0), "");
+        assert_eq!(strip_ansi(&prefix_for(1)), "│ ");
+        assert_eq!(strip_ansi(&prefix_for(2)), "│ │ ");
+    }
+
+    #[test]
+    fn test_header_and_footer() {
+        assert_eq!(strip_ansi(&header("Running tests")), "╭ Running tests");
+        assert_eq!(strip_ansi(&footer("2.3s")), "╰ 2.3s");
+    }
+}
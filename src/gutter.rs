@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+
+use crate::Console;
+use crate::indent::LinePrefixer;
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+
+// Prefixes every line of the content with its right-aligned number, for
+// file previews and diagnostics. Numbers are muted except on highlighted
+// lines, which get a marker and a bold number.
+#[derive(Debug, Clone)]
+pub struct Gutter<T: Display> {
+    content: T,
+    start: usize,
+    separator: String,
+    highlighted: BTreeSet<usize>,
+}
+
+impl<T: Display> Gutter<T> {
+    pub fn new(content: T) -> Self {
+        Gutter {
+            content,
+            start: 1,
+            separator: format!(" {} ", symbols().border.vertical),
+            highlighted: BTreeSet::new(),
+        }
+    }
+
+    // The number of the first line, e.g. when showing an excerpt
+    pub fn start(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    // Takes line numbers as shown, i.e. counted from `start`
+    pub fn highlight(mut self, line: usize) -> Self {
+        self.highlighted.insert(line);
+        self
+    }
+
+    pub fn highlight_lines<I: IntoIterator<Item = usize>>(mut self, lines: I) -> Self {
+        self.highlighted.extend(lines);
+        self
+    }
+}
+
+impl<T: Display> Display for Gutter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let content = self.content.to_string();
+        let last = self.start + content.lines().count().saturating_sub(1);
+        let width = last.to_string().len();
+
+        let theme = theme();
+        let muted = theme.style(Tone::Muted).clone();
+        let marked = theme.style(Tone::Warning).clone().bold();
+        let pointer = symbols().pointer;
+        let marker_width = if self.highlighted.is_empty() {
+            0
+        } else {
+            crate::unicode::width(pointer) + 1
+        };
+        let (start, separator, highlighted) =
+            (self.start, self.separator.clone(), self.highlighted.clone());
+
+        let mut prefixer = LinePrefixer::with(move |index| {
+            let number = start + index;
+            let is_marked = highlighted.contains(&number);
+            let marker = match (marker_width, is_marked) {
+                (0, _) => String::new(),
+                (_, true) => format!("{} ", Console::new_with_style(pointer, marked.clone())),
+                (width, false) => " ".repeat(width),
+            };
+            let style = if is_marked { &marked } else { &muted };
+            format!(
+                "{}{}{}",
+                marker,
+                Console::new_with_style(format!("{:>width$}", number), style.clone()),
+                Console::new_with_style(separator.as_str(), muted.clone())
+            )
+        });
+        let mut out = Vec::new();
+        prefixer.process(content.as_bytes(), &mut out);
+        f.write_str(&String::from_utf8_lossy(&out))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_numbers_are_right_aligned() {
+        let source = (1..=10)
+            .map(|n| format!("line {}\n", n))
+            .collect::<String>();
+        let output = strip_ansi(&Gutter::new(source).start(95).to_string());
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], " 95 │ line 1");
+        assert_eq!(lines[9], "104 │ line 10");
+        assert_eq!(lines.len(), 10);
+    }
+
+    #[test]
+    fn test_highlighted_lines() {
+        let gutter = Gutter::new("fn main() {\n    oops\n}")
+            .highlight(2)
+            .separator(" | ");
+        assert_eq!(
+            strip_ansi(&gutter.to_string()),
+            "  1 | fn main() {\n❯ 2 |     oops\n  3 | }"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_styles_do_not_leak_into_gutter() {
+        let output = Gutter::new(Console::new("a\nb").red()).to_string();
+        assert_eq!(
+            output,
+            "\x1b[2m1\x1b[0m\x1b[2m │ \x1b[0m\x1b[38;5;1ma\n\
+             \x1b[0m\x1b[2m2\x1b[0m\x1b[2m │ \x1b[0m\x1b[38;5;1mb\x1b[0m"
+        );
+    }
+}
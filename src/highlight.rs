@@ -0,0 +1,130 @@
+use std::ops::Range;
+
+use crate::{Console, Style, StyledText};
+
+// Highlighting splits text into segments, layering the highlight style on
+// top of the text's own style for every match so e.g. a match inside a red
+// line keeps its color unless the highlight sets one.
+impl Console {
+    pub fn highlight_matches(&self, pattern: &str, style: Style) -> StyledText {
+        highlight(self, literal_matches(&self.text, pattern), &style)
+            .into_iter()
+            .collect()
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn highlight_regex(&self, regex: &regex::Regex, style: Style) -> StyledText {
+        highlight(self, regex_matches(&self.text, regex), &style)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl StyledText {
+    // Matches are found within each segment
+    pub fn highlight_matches(&self, pattern: &str, style: Style) -> StyledText {
+        self.segments()
+            .iter()
+            .flat_map(|s| highlight(s, literal_matches(&s.text, pattern), &style))
+            .collect()
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn highlight_regex(&self, regex: &regex::Regex, style: Style) -> StyledText {
+        self.segments()
+            .iter()
+            .flat_map(|s| highlight(s, regex_matches(&s.text, regex), &style))
+            .collect()
+    }
+}
+
+fn literal_matches<'a>(text: &'a str, pattern: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+    text.match_indices(pattern)
+        .filter(|_| !pattern.is_empty())
+        .map(|(start, m)| start..start + m.len())
+}
+
+#[cfg(feature = "regex")]
+fn regex_matches<'a>(
+    text: &'a str,
+    regex: &'a regex::Regex,
+) -> impl Iterator<Item = Range<usize>> + 'a {
+    regex.find_iter(text).map(|m| m.range())
+}
+
+fn highlight<I>(console: &Console, matches: I, style: &Style) -> Vec<Console>
+where
+    I: IntoIterator<Item = Range<usize>>,
+{
+    let highlighted = console.style.merge(style);
+    let mut segments = Vec::new();
+    let mut end = 0;
+    for range in matches {
+        // Empty regex matches would only add empty segments
+        if range.is_empty() {
+            continue;
+        }
+        if range.start > end {
+            segments.push(console.with_text(&console.text[end..range.start]));
+        }
+        segments.push(Console::new_with_style(
+            &console.text[range.clone()],
+            highlighted.clone(),
+        ));
+        end = range.end;
+    }
+    if end < console.text.len() || segments.is_empty() {
+        segments.push(console.with_text(&console.text[end..]));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Attribute, Color};
+
+    fn parts(text: &StyledText) -> Vec<&str> {
+        text.segments().iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_highlight_matches() {
+        let line = Console::new("error: disk error on sda").red();
+        let text = line.highlight_matches("error", Style::new().bold());
+
+        assert_eq!(parts(&text), ["error", ": disk ", "error", " on sda"]);
+        let first = &text.segments()[0].style;
+        assert!(first.attributes.contains(&Attribute::Bold));
+        assert_eq!(first.foreground, Some(Color::RED));
+        assert!(text.segments()[1].style.attributes.is_empty());
+    }
+
+    #[test]
+    fn test_no_match_keeps_text() {
+        let text = Console::new("fine").highlight_matches("error", Style::new().bold());
+        assert_eq!(parts(&text), ["fine"]);
+        let text = Console::new("fine").highlight_matches("", Style::new().bold());
+        assert_eq!(parts(&text), ["fine"]);
+    }
+
+    #[test]
+    fn test_highlight_styled_text() {
+        let text = StyledText::new()
+            .then(Console::new("a-b").green())
+            .then(Console::new("-c"))
+            .highlight_matches("-", Style::new().fg(Color::YELLOW));
+        assert_eq!(parts(&text), ["a", "-", "b", "-", "c"]);
+        assert_eq!(text.segments()[1].style.foreground, Some(Color::YELLOW));
+        assert_eq!(text.segments()[2].style.foreground, Some(Color::GREEN));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_highlight_regex() {
+        let regex = regex::Regex::new(r"\d+|x*").unwrap();
+        let text =
+            Console::new("took 25ms, 3 retries").highlight_regex(&regex, Style::new().bold());
+        assert_eq!(parts(&text), ["took ", "25", "ms, ", "3", " retries"]);
+    }
+}
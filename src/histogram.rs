@@ -0,0 +1,198 @@
+use std::fmt::{self, Display};
+
+use crate::theme::{Tone, theme};
+use crate::{ColorScale, Console, Style};
+
+const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// A vertical bar chart of bucket counts drawn with eighth-block characters,
+// with the peak and zero marked on the y axis and each bar colored by its
+// height
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    counts: Vec<f64>,
+    height: usize,
+    bar_width: usize,
+    range: Option<(f64, f64)>,
+    scale: ColorScale,
+}
+
+impl Histogram {
+    pub fn new<I: IntoIterator<Item = f64>>(counts: I) -> Self {
+        Histogram {
+            counts: counts.into_iter().collect(),
+            height: 8,
+            bar_width: 1,
+            range: None,
+            scale: ColorScale::heat(),
+        }
+    }
+
+    // Buckets `samples` into `buckets` equal-width bins between their
+    // minimum and maximum, which label the x axis
+    pub fn from_samples(samples: &[f64], buckets: usize) -> Self {
+        let buckets = buckets.max(1);
+        let finite = samples.iter().copied().filter(|s| s.is_finite());
+        let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), s| {
+            (lo.min(s), hi.max(s))
+        });
+        if min > max {
+            return Histogram::new(vec![0.0; buckets]);
+        }
+
+        let mut counts = vec![0.0; buckets];
+        let span = max - min;
+        for &sample in samples.iter().filter(|s| s.is_finite()) {
+            let bucket = if span == 0.0 {
+                0
+            } else {
+                (((sample - min) / span) * buckets as f64) as usize
+            };
+            counts[bucket.min(buckets - 1)] += 1.0;
+        }
+        Histogram::new(counts).range(min, max)
+    }
+
+    // Rows of characters, defaults to 8
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height.max(1);
+        self
+    }
+
+    pub fn bar_width(mut self, bar_width: usize) -> Self {
+        self.bar_width = bar_width.max(1);
+        self
+    }
+
+    // Values shown under the left and right ends of the x axis
+    pub fn range(mut self, start: f64, end: f64) -> Self {
+        self.range = Some((start, end));
+        self
+    }
+
+    pub fn scale(mut self, scale: ColorScale) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let axis = theme.style(Tone::Muted).clone();
+        let peak = self.counts.iter().copied().fold(0.0, f64::max);
+        let top_label = number(peak);
+        let gutter = top_label.chars().count().max(1);
+        let chart_width = self.counts.len() * self.bar_width;
+
+        for row in (0..self.height).rev() {
+            let label = match row {
+                r if r == self.height - 1 => top_label.clone(),
+                _ => String::new(),
+            };
+            write!(
+                f,
+                "{:>gutter$} {}",
+                label,
+                Console::new_with_style("┤", axis.clone())
+            )?;
+            for &count in &self.counts {
+                let fraction = if peak > 0.0 {
+                    count.max(0.0) / peak
+                } else {
+                    0.0
+                };
+                let eighths = (fraction * (self.height * 8) as f64).round() as usize;
+                let cell = EIGHTHS[eighths.saturating_sub(row * 8).min(8)];
+                let bar: String = std::iter::repeat_n(cell, self.bar_width).collect();
+                if cell == ' ' {
+                    f.write_str(&bar)?;
+                } else {
+                    let style = Style::new().fg(self.scale.at(fraction));
+                    write!(f, "{}", Console::new_with_style(bar, style))?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        let baseline = format!("┼{}", "─".repeat(chart_width));
+        writeln!(
+            f,
+            "{:>gutter$} {}",
+            "0",
+            Console::new_with_style(baseline, axis.clone())
+        )?;
+
+        if let Some((start, end)) = self.range {
+            let (start, end) = (number(start), number(end));
+            let space =
+                (chart_width + 1).saturating_sub(start.chars().count() + end.chars().count());
+            let labels = format!("{}{}{}", start, " ".repeat(space.max(1)), end);
+            writeln!(
+                f,
+                "{:gutter$} {}",
+                "",
+                Console::new_with_style(labels, axis)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Formats axis values without needless decimals, e.g. `12` or `0.25`
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        let formatted = format!("{:.2}", value);
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number(12.0), "12");
+        assert_eq!(number(0.25), "0.25");
+        assert_eq!(number(1.0 / 3.0), "0.33");
+        assert_eq!(number(2.5), "2.5");
+    }
+
+    #[test]
+    fn test_histogram_layout() {
+        let histogram = Histogram::new([1.0, 4.0, 2.0, 0.0]).height(2);
+        assert_eq!(
+            strip_ansi(&histogram.to_string()),
+            "4 ┤ █  \n  \
+             ┤▄██ \n\
+             0 ┼────\n"
+        );
+    }
+
+    #[test]
+    fn test_from_samples() {
+        let samples = [1.0, 1.5, 2.0, 9.0, 10.0, 10.0];
+        let histogram = Histogram::from_samples(&samples, 3).height(1).bar_width(2);
+        assert_eq!(histogram.counts, vec![3.0, 0.0, 3.0]);
+        assert_eq!(
+            strip_ansi(&histogram.to_string()),
+            "3 ┤██  ██\n\
+             0 ┼──────\n  \
+             1    10\n"
+        );
+        assert_eq!(Histogram::from_samples(&[], 2).counts, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_histogram_render() {
+        let histogram = Histogram::new([1.0, 3.0, 6.0, 4.0, 2.0]).height(3);
+        insta::assert_yaml_snapshot!(histogram.to_string());
+    }
+}
@@ -0,0 +1,383 @@
+use crate::{Attribute, Color, Console, Style, StyledText, UnderlineStyle};
+
+// Renders styled text as HTML `<span>`s, either with inline `style`
+// attributes (the default) or with CSS classes from `stylesheet()`.
+// Newlines are kept as-is, so wrap the output in a `<pre>` to preserve them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlRenderer {
+    class_prefix: Option<String>,
+}
+
+impl HtmlRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Uses classes such as `{prefix}bold` and `{prefix}fg-1` instead of inline
+    // styles. RGB colors have no class and are still emitted inline.
+    pub fn classes<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.class_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn render(&self, text: &StyledText) -> String {
+        text.segments()
+            .iter()
+            .map(|segment| self.render_console(segment))
+            .collect()
+    }
+
+    pub fn render_console(&self, console: &Console) -> String {
+        self.span(&console.style, &console.text)
+    }
+
+    // CSS rules for the classes emitted in class mode
+    pub fn stylesheet(&self) -> String {
+        let p = self.class_prefix.as_deref().unwrap_or("");
+        let mut css = format!(
+            ".{p}bold {{ font-weight: bold; }}\n\
+             .{p}dim {{ opacity: 0.6; }}\n\
+             .{p}italic {{ font-style: italic; }}\n\
+             .{p}hidden {{ visibility: hidden; }}\n\
+             .{p}reverse {{ color: Canvas; background-color: CanvasText; }}\n\
+             .{p}framed {{ outline: 1px solid currentColor; }}\n\
+             .{p}encircled {{ outline: 1px solid currentColor; border-radius: 1em; }}\n\
+             .{p}superscript {{ vertical-align: super; font-size: smaller; }}\n\
+             .{p}subscript {{ vertical-align: sub; font-size: smaller; }}\n"
+        );
+        // Each decoration class would override the others' `text-decoration`,
+        // so every combination gets a rule of its own listing all its lines
+        for mask in 1..1 << DECORATIONS.len() {
+            let chosen: Vec<_> = DECORATIONS
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| mask & (1 << i) != 0)
+                .map(|(_, &decoration)| decoration)
+                .collect();
+            let selector: String = chosen
+                .iter()
+                .map(|&(attr, _)| format!(".{p}{}", class_name(attr)))
+                .collect();
+            let lines: Vec<&str> = chosen.iter().map(|&(_, line)| line).collect();
+            css.push_str(&format!(
+                "{selector} {{ text-decoration: {}; }}\n",
+                lines.join(" ")
+            ));
+        }
+        for n in 0..=255 {
+            let color = css_color(Color::Named(n));
+            css.push_str(&format!(".{p}fg-{n} {{ color: {color}; }}\n"));
+            css.push_str(&format!(".{p}bg-{n} {{ background-color: {color}; }}\n"));
+        }
+        css
+    }
+
+    fn span(&self, style: &Style, text: &str) -> String {
+        let text = escape(text);
+        let has = |attr| style.attributes.contains(&attr);
+
+        let (mut fg, mut bg) = (style.foreground, style.background);
+        let reversed = has(Attribute::Reverse);
+        if reversed {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+
+        let mut classes = Vec::new();
+        let mut declarations = Vec::new();
+
+        if let Some(prefix) = &self.class_prefix {
+            for attr in [
+                Attribute::Bold,
+                Attribute::Dim,
+                Attribute::Italic,
+                Attribute::Underline,
+                Attribute::Strikethrough,
+                Attribute::Blink,
+                Attribute::Hidden,
+                Attribute::Reverse,
+                Attribute::Overline,
+                Attribute::Framed,
+                Attribute::Encircled,
+                Attribute::Superscript,
+                Attribute::Subscript,
+            ] {
+                if has(attr) {
+                    classes.push(format!("{}{}", prefix, class_name(attr)));
+                }
+            }
+            match fg {
+                Some(Color::Named(n)) => classes.push(format!("{}fg-{}", prefix, n)),
+                Some(color) => declarations.push(format!("color: {}", css_color(color))),
+                None => {}
+            }
+            match bg {
+                Some(Color::Named(n)) => classes.push(format!("{}bg-{}", prefix, n)),
+                Some(color) => declarations.push(format!("background-color: {}", css_color(color))),
+                None => {}
+            }
+            declarations.extend(underline_css(style));
+        } else {
+            declarations = inline_css(style);
+        }
+
+        if classes.is_empty() && declarations.is_empty() {
+            return text;
+        }
+
+        let mut open = String::from("<span");
+        if !classes.is_empty() {
+            open.push_str(&format!(" class=\"{}\"", classes.join(" ")));
+        }
+        if !declarations.is_empty() {
+            open.push_str(&format!(" style=\"{}\"", declarations.join("; ")));
+        }
+        format!("{}>{}</span>", open, text)
+    }
+}
+
+impl Console {
+    pub fn to_html(&self) -> String {
+        HtmlRenderer::new().render_console(self)
+    }
+}
+
+impl StyledText {
+    pub fn to_html(&self) -> String {
+        HtmlRenderer::new().render(self)
+    }
+}
+
+// Inline CSS declarations for a style, e.g. `color: #cd0000` and
+// `font-weight: bold`
+pub(crate) fn inline_css(style: &Style) -> Vec<String> {
+    let has = |attr| style.attributes.contains(&attr);
+    let (mut fg, mut bg) = (style.foreground, style.background);
+    let reversed = has(Attribute::Reverse);
+    if reversed {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    let mut declarations = Vec::new();
+    match fg {
+        Some(color) => declarations.push(format!("color: {}", css_color(color))),
+        None if reversed => declarations.push("color: Canvas".into()),
+        None => {}
+    }
+    match bg {
+        Some(color) => declarations.push(format!("background-color: {}", css_color(color))),
+        None if reversed => declarations.push("background-color: CanvasText".into()),
+        None => {}
+    }
+    if has(Attribute::Bold) {
+        declarations.push("font-weight: bold".into());
+    }
+    if has(Attribute::Dim) {
+        declarations.push("opacity: 0.6".into());
+    }
+    if has(Attribute::Italic) {
+        declarations.push("font-style: italic".into());
+    }
+    let decorations: Vec<&str> = DECORATIONS
+        .into_iter()
+        .filter(|&(attr, _)| has(attr))
+        .map(|(_, value)| value)
+        .collect();
+    if !decorations.is_empty() {
+        declarations.push(format!("text-decoration: {}", decorations.join(" ")));
+    }
+    if has(Attribute::Framed) || has(Attribute::Encircled) {
+        declarations.push("outline: 1px solid currentColor".into());
+    }
+    if has(Attribute::Encircled) {
+        declarations.push("border-radius: 1em".into());
+    }
+    if has(Attribute::Superscript) {
+        declarations.push("vertical-align: super; font-size: smaller".into());
+    } else if has(Attribute::Subscript) {
+        declarations.push("vertical-align: sub; font-size: smaller".into());
+    }
+    if has(Attribute::Hidden) {
+        declarations.push("visibility: hidden".into());
+    }
+    declarations.extend(underline_css(style));
+    declarations
+}
+
+// The attributes drawn as `text-decoration` lines, in the order they're listed
+const DECORATIONS: [(Attribute, &str); 4] = [
+    (Attribute::Underline, "underline"),
+    (Attribute::Overline, "overline"),
+    (Attribute::Strikethrough, "line-through"),
+    (Attribute::Blink, "blink"),
+];
+
+fn underline_css(style: &Style) -> Vec<String> {
+    let mut declarations = Vec::new();
+    if style.attributes.contains(&Attribute::Underline) {
+        match style.underline_style {
+            Some(UnderlineStyle::Double) => {
+                declarations.push("text-decoration-style: double".into())
+            }
+            Some(UnderlineStyle::Curly) => declarations.push("text-decoration-style: wavy".into()),
+            Some(UnderlineStyle::Dotted) => {
+                declarations.push("text-decoration-style: dotted".into())
+            }
+            Some(UnderlineStyle::Dashed) => {
+                declarations.push("text-decoration-style: dashed".into())
+            }
+            Some(UnderlineStyle::Single) | None => {}
+        }
+        if let Some(color) = style.underline_color {
+            declarations.push(format!("text-decoration-color: {}", css_color(color)));
+        }
+    }
+    declarations
+}
+
+fn class_name(attr: Attribute) -> &'static str {
+    match attr {
+        Attribute::Bold => "bold",
+        Attribute::Dim => "dim",
+        Attribute::Italic => "italic",
+        Attribute::Underline => "underline",
+        Attribute::Blink => "blink",
+        Attribute::Reverse => "reverse",
+        Attribute::Hidden => "hidden",
+        Attribute::Strikethrough => "strikethrough",
+        Attribute::Framed => "framed",
+        Attribute::Encircled => "encircled",
+        Attribute::Overline => "overline",
+        Attribute::Superscript => "superscript",
+        Attribute::Subscript => "subscript",
+    }
+}
+
+pub(crate) fn css_color(color: Color) -> String {
+    let (r, g, b) = color.to_rgb();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unstyled_text_has_no_span() {
+        assert_eq!(Console::new("plain").to_html(), "plain");
+    }
+
+    #[test]
+    fn test_inline_styles() {
+        let html = Console::new("error").red().bold().underline().to_html();
+        assert_eq!(
+            html,
+            "<span style=\"color: #cd0000; font-weight: bold; text-decoration: underline\">error</span>"
+        );
+
+        let html = Console::new("rgb")
+            .fg_rgb(255, 0, 128)
+            .bg_rgb(0, 0, 0)
+            .to_html();
+        assert_eq!(
+            html,
+            "<span style=\"color: #ff0080; background-color: #000000\">rgb</span>"
+        );
+    }
+
+    #[test]
+    fn test_underline_style_and_color() {
+        let html = Console::new("typo")
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::RED)
+            .to_html();
+        assert_eq!(
+            html,
+            "<span style=\"text-decoration: underline; text-decoration-style: wavy; text-decoration-color: #cd0000\">typo</span>"
+        );
+    }
+
+    #[test]
+    fn test_extra_attributes() {
+        let html = Console::new("2").superscript().overline().to_html();
+        assert_eq!(
+            html,
+            "<span style=\"text-decoration: overline; vertical-align: super; font-size: smaller\">2</span>"
+        );
+    }
+
+    #[test]
+    fn test_reverse_swaps_colors() {
+        let html = Console::new("rev").red().reverse().to_html();
+        assert_eq!(
+            html,
+            "<span style=\"color: Canvas; background-color: #cd0000\">rev</span>"
+        );
+    }
+
+    #[test]
+    fn test_html_escaping() {
+        let html = Console::new("<a href=\"x\">&</a>").to_html();
+        assert_eq!(html, "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+
+    #[test]
+    fn test_class_mode() {
+        let renderer = HtmlRenderer::new().classes("pc-");
+        let html = renderer.render_console(&Console::new("ok").green().on_black().italic());
+        assert_eq!(html, "<span class=\"pc-italic pc-fg-2 pc-bg-0\">ok</span>");
+
+        // RGB colors have no class to refer to
+        let html = renderer.render_console(&Console::new("rgb").bold().fg_rgb(1, 2, 3));
+        assert_eq!(
+            html,
+            "<span class=\"pc-bold\" style=\"color: #010203\">rgb</span>"
+        );
+    }
+
+    #[test]
+    fn test_stylesheet() {
+        let css = HtmlRenderer::new().classes("pc-").stylesheet();
+        assert!(css.contains(".pc-bold { font-weight: bold; }"));
+        assert!(css.contains(".pc-fg-1 { color: #cd0000; }"));
+        assert!(css.contains(".pc-bg-255 { background-color: #eeeeee; }"));
+    }
+
+    #[test]
+    fn test_stylesheet_combines_decorations() {
+        let css = HtmlRenderer::new().classes("pc-").stylesheet();
+        assert!(css.contains(".pc-underline { text-decoration: underline; }"));
+        assert!(css.contains(".pc-underline.pc-overline { text-decoration: underline overline; }"));
+        assert!(css.contains(
+            ".pc-underline.pc-overline.pc-strikethrough.pc-blink \
+             { text-decoration: underline overline line-through blink; }"
+        ));
+        assert_eq!(css.matches("text-decoration:").count(), 15);
+
+        let html = HtmlRenderer::new()
+            .classes("pc-")
+            .render_console(&Console::new("x").underline().overline());
+        assert_eq!(html, "<span class=\"pc-underline pc-overline\">x</span>");
+    }
+
+    #[test]
+    fn test_styled_text_to_html() {
+        let text = StyledText::new()
+            .then(Console::new("warning").yellow().bold())
+            .then(Console::new(": disk almost full"));
+        insta::assert_yaml_snapshot!(text.to_html());
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::timestamp::Timestamp;
+use crate::{Console, StyledText};
+
+// Prepends a prefix, which may be styled itself, to every line written
+// through it. Styles that are active across a line break are closed before
+// the prefix and re-opened after it, so the prefix never picks them up.
+pub struct IndentWriter<W: Write> {
+    inner: W,
+    prefixer: LinePrefixer,
+}
+
+impl<W: Write> IndentWriter<W> {
+    pub fn new<P: Display>(inner: W, prefix: P) -> Self {
+        IndentWriter {
+            inner,
+            prefixer: LinePrefixer::new(prefix.to_string()),
+        }
+    }
+
+    pub fn indent(inner: W, width: usize) -> Self {
+        Self::new(inner, " ".repeat(width))
+    }
+
+    // Prefixes every line with a dim timestamp
+    pub fn timestamped(inner: W, timestamp: Timestamp) -> Self {
+        IndentWriter {
+            inner,
+            prefixer: LinePrefixer::with(timestamp.prefixer()),
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for IndentWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut out = Vec::with_capacity(buf.len());
+        self.prefixer.process(buf, &mut out);
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Displays `content` with a prefix on every line
+#[derive(Debug, Clone)]
+pub struct Indented<T: Display> {
+    content: T,
+    prefix: String,
+}
+
+impl<T: Display> Indented<T> {
+    pub fn new<P: Display>(content: T, prefix: P) -> Self {
+        Indented {
+            content,
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
+impl<T: Display> Display for Indented<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = Vec::new();
+        LinePrefixer::new(self.prefix.clone())
+            .process(self.content.to_string().as_bytes(), &mut out);
+        f.write_str(&String::from_utf8_lossy(&out))
+    }
+}
+
+impl Console {
+    pub fn indent(self, width: usize) -> Indented<Console> {
+        Indented::new(self, " ".repeat(width))
+    }
+
+    pub fn prefix<P: Display>(self, prefix: P) -> Indented<Console> {
+        Indented::new(self, prefix)
+    }
+}
+
+impl StyledText {
+    pub fn indent(self, width: usize) -> Indented<StyledText> {
+        Indented::new(self, " ".repeat(width))
+    }
+
+    pub fn prefix<P: Display>(self, prefix: P) -> Indented<StyledText> {
+        Indented::new(self, prefix)
+    }
+}
+
+// Called with the zero-based index of each line to get its prefix
+pub(crate) struct LinePrefixer {
+    prefix: Box<dyn FnMut(usize) -> String + Send + Sync>,
+    line: usize,
+    at_line_start: bool,
+    // SGR sequences in effect since the last reset
+    active: Vec<u8>,
+    sequence: Option<Vec<u8>>,
+}
+
+impl LinePrefixer {
+    fn new(prefix: String) -> Self {
+        Self::with(move |_| prefix.clone())
+    }
+
+    pub(crate) fn with<F>(prefix: F) -> Self
+    where
+        F: FnMut(usize) -> String + Send + Sync + 'static,
+    {
+        LinePrefixer {
+            prefix: Box::new(prefix),
+            line: 0,
+            at_line_start: true,
+            active: Vec::new(),
+            sequence: None,
+        }
+    }
+
+    pub(crate) fn process(&mut self, bytes: &[u8], out: &mut Vec<u8>) {
+        for &byte in bytes {
+            if self.at_line_start && self.sequence.is_none() {
+                self.at_line_start = false;
+                let prefix = (self.prefix)(self.line);
+                if !prefix.is_empty() {
+                    if !self.active.is_empty() {
+                        out.extend_from_slice(b"\x1b[0m");
+                    }
+                    out.extend_from_slice(prefix.as_bytes());
+                    out.extend_from_slice(&self.active);
+                }
+            }
+            out.push(byte);
+
+            if let Some(sequence) = &mut self.sequence {
+                sequence.push(byte);
+                if sequence.len() == 2 && byte != b'[' {
+                    // Not a CSI sequence, nothing to track
+                    self.sequence = None;
+                } else if sequence.len() > 2 && (0x40..=0x7e).contains(&byte) {
+                    let sequence = self.sequence.take().unwrap();
+                    self.track(sequence);
+                }
+            } else if byte == 0x1b {
+                self.sequence = Some(vec![byte]);
+            } else if byte == b'\n' {
+                self.at_line_start = true;
+                self.line += 1;
+            }
+        }
+    }
+
+    fn track(&mut self, sequence: Vec<u8>) {
+        if sequence.last() != Some(&b'm') {
+            return;
+        }
+        let params = &sequence[2..sequence.len() - 1];
+        let first = params.split(|&b| b == b';').next().unwrap_or_default();
+        if first.is_empty() || first.iter().all(|&b| b == b'0') {
+            self.active.clear();
+            if params.len() <= 1 {
+                return;
+            }
+        }
+        self.active.extend_from_slice(&sequence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixed(prefix: &str, chunks: &[&str]) -> String {
+        let mut writer = IndentWriter::new(Vec::new(), prefix);
+        for chunk in chunks {
+            writer.write_all(chunk.as_bytes()).unwrap();
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_every_line_is_prefixed() {
+        assert_eq!(
+            prefixed("> ", &["one\ntwo\n\nthree"]),
+            "> one\n> two\n> \n> three"
+        );
+        // No dangling prefix after a trailing newline
+        assert_eq!(prefixed("> ", &["done\n"]), "> done\n");
+    }
+
+    #[test]
+    fn test_timestamped_lines() {
+        let mut writer = IndentWriter::timestamped(Vec::new(), Timestamp::Elapsed);
+        writer.write_all(b"one\ntwo\n").unwrap();
+        let output = crate::testing::strip_ansi(&String::from_utf8(writer.into_inner()).unwrap());
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("    0.0") && lines[0].ends_with("s one"));
+        assert!(lines[1].ends_with("s two"));
+    }
+
+    #[test]
+    fn test_styles_are_reopened_after_the_prefix() {
+        assert_eq!(
+            prefixed("│ ", &["\x1b[31mred\nstill red\x1b[0m\nplain"]),
+            "│ \x1b[31mred\n\x1b[0m│ \x1b[31mstill red\x1b[0m\n│ plain"
+        );
+        // An empty prefix leaves the styling untouched
+        assert_eq!(
+            prefixed("", &["\x1b[31mred\nstill red\x1b[0m"]),
+            "\x1b[31mred\nstill red\x1b[0m"
+        );
+        // Sequences split across writes are still tracked
+        assert_eq!(
+            prefixed("| ", &["\x1b[1", ";32ma\n", "b"]),
+            "| \x1b[1;32ma\n\x1b[0m| \x1b[1;32mb"
+        );
+    }
+
+    #[test]
+    fn test_console_indent_and_prefix() {
+        let indented = Console::new("a\nb").indent(2).to_string();
+        let quoted = StyledText::from(Console::new("a\nb"))
+            .prefix(Console::new("│ "))
+            .to_string();
+        assert_eq!(indented, "  a\n  b");
+        assert_eq!(quoted, "│ a\n│ b");
+
+        #[cfg(not(feature = "no-color"))]
+        {
+            let red = Console::new("x\ny")
+                .red()
+                .prefix(Console::new("│ ").dim())
+                .to_string();
+            assert_eq!(
+                red,
+                "\x1b[2m│ \x1b[0m\x1b[38;5;1mx\n\x1b[0m\x1b[2m│ \x1b[0m\x1b[38;5;1my\x1b[0m"
+            );
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use std::fmt::{self, Display};
+use std::io::{self, Cursor};
+use std::path::Path;
+
+use ::image::imageops::{self, FilterType};
+use ::image::{ImageFormat, Rgba, RgbaImage};
+
+use crate::capability::Capabilities;
+use crate::{Color, Console, group};
+
+// How an image reaches the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageProtocol {
+    // Kitty's graphics protocol, also spoken by Ghostty
+    Kitty,
+    // iTerm2's inline images (OSC 1337), also shown by WezTerm and mintty
+    ITerm2,
+    // Colored block characters, for every other color terminal
+    Blocks,
+}
+
+impl ImageProtocol {
+    pub fn detect() -> ImageProtocol {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env<F: Fn(&str) -> Option<String>>(var: F) -> ImageProtocol {
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        // tmux swallows both protocols unless passthrough is set up
+        if var("TMUX").is_some() {
+            return ImageProtocol::Blocks;
+        }
+        if var("KITTY_WINDOW_ID").is_some()
+            || matches!(term.as_str(), "xterm-kitty" | "xterm-ghostty")
+            || program == "ghostty"
+        {
+            ImageProtocol::Kitty
+        } else if matches!(program.as_str(), "iTerm.app" | "WezTerm" | "mintty")
+            || var("LC_TERMINAL").is_some_and(|t| t == "iTerm2")
+        {
+            ImageProtocol::ITerm2
+        } else {
+            ImageProtocol::Blocks
+        }
+    }
+}
+
+// A PNG or JPEG shown inline in the terminal, through whichever graphics
+// protocol it speaks, or drawn with colored blocks when it has none
+#[derive(Debug, Clone)]
+pub struct Image {
+    data: Vec<u8>,
+    format: ImageFormat,
+    pixels: RgbaImage,
+    columns: Option<usize>,
+    protocol: Option<ImageProtocol>,
+}
+
+impl Image {
+    pub fn from_bytes<T: Into<Vec<u8>>>(data: T) -> io::Result<Image> {
+        let data = data.into();
+        let invalid = |e: ::image::ImageError| io::Error::new(io::ErrorKind::InvalidData, e);
+        let format = ::image::guess_format(&data).map_err(invalid)?;
+        if !matches!(format, ImageFormat::Png | ImageFormat::Jpeg) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported image format {:?}, expected PNG or JPEG",
+                    format
+                ),
+            ));
+        }
+        let pixels = ::image::load_from_memory_with_format(&data, format)
+            .map_err(invalid)?
+            .to_rgba8();
+        Ok(Image {
+            data,
+            format,
+            pixels,
+            columns: None,
+            protocol: None,
+        })
+    }
+
+    // Raw RGBA pixels, row by row, e.g. a generated QR code. They're
+    // encoded as PNG for the graphics protocols.
+    pub fn from_rgba(width: u32, height: u32, rgba: Vec<u8>) -> io::Result<Image> {
+        let pixels = RgbaImage::from_raw(width, height, rgba).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected {} bytes of RGBA pixels", width * height * 4),
+            )
+        })?;
+        let mut data = Vec::new();
+        pixels
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Image {
+            data,
+            format: ImageFormat::Png,
+            pixels,
+            columns: None,
+            protocol: None,
+        })
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Image> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    // Width in terminal columns; the height follows the aspect ratio.
+    // Graphics protocols otherwise show the image at its own size, and
+    // blocks fill the terminal's width at most.
+    pub fn width(mut self, columns: usize) -> Self {
+        self.columns = Some(columns.max(1));
+        self
+    }
+
+    // Overrides `ImageProtocol::detect()`
+    pub fn protocol(mut self, protocol: ImageProtocol) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    pub fn print(&self) {
+        crate::ignore_broken_pipe(group::write_stdout(&format!("{}\n", self)));
+    }
+
+    fn kitty(&self) -> String {
+        let png = if self.format == ImageFormat::Png {
+            base64(&self.data)
+        } else {
+            let mut png = Vec::new();
+            // Encoding pixels that were just decoded can't fail
+            let _ = self
+                .pixels
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png);
+            base64(&png)
+        };
+        // Payloads go in chunks of at most 4096 bytes; `m=1` says more follow
+        let chunks: Vec<&[u8]> = png.as_bytes().chunks(4096).collect();
+        let mut out = String::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(i + 1 < chunks.len());
+            let keys = if i == 0 {
+                let mut keys = "a=T,f=100,q=2".to_string();
+                if let Some(columns) = self.columns {
+                    keys.push_str(&format!(",c={}", columns));
+                }
+                format!("{},m={}", keys, more)
+            } else {
+                format!("m={}", more)
+            };
+            out.push_str(&format!(
+                "\x1b_G{};{}\x1b\\",
+                keys,
+                String::from_utf8_lossy(chunk)
+            ));
+        }
+        out
+    }
+
+    fn iterm2(&self) -> String {
+        let width = self
+            .columns
+            .map_or(String::new(), |columns| format!(";width={}", columns));
+        format!(
+            "\x1b]1337;File=inline=1;size={}{};preserveAspectRatio=1:{}\x07",
+            self.data.len(),
+            width,
+            base64(&self.data)
+        )
+    }
+
+    // Draws the image `width` columns wide with `▀` half blocks: each cell
+    // shows one pixel in its foreground and the one below in its background,
+    // which keeps the aspect ratio since cells are about twice as tall as
+    // they are wide. Works on any color terminal, falling back to the
+    // 256-color palette where 24-bit color isn't supported. Transparent
+    // pixels are left blank.
+    pub fn blocks(&self, width: usize) -> String {
+        self.block_lines(width, Capabilities::get().truecolor)
+            .join("\n")
+    }
+
+    fn block_lines(&self, width: usize, truecolor: bool) -> Vec<String> {
+        let (image_width, image_height) = self.pixels.dimensions();
+        if image_width == 0 || image_height == 0 {
+            return Vec::new();
+        }
+        let columns = width.clamp(1, image_width as usize) as u32;
+        let rows = ((image_height as u64 * columns as u64) / image_width as u64).max(1) as u32;
+        // Pixel art and QR codes stay crisp when they fit as they are
+        let scaled = if (columns, rows) == (image_width, image_height) {
+            self.pixels.clone()
+        } else {
+            imageops::resize(&self.pixels, columns, rows, FilterType::Triangle)
+        };
+        let color = |pixel: &Rgba<u8>| {
+            let [r, g, b, a] = pixel.0;
+            let color = Color::RGB(r, g, b);
+            (a >= 128).then(|| if truecolor { color } else { color.to_256() })
+        };
+        (0..rows.div_ceil(2))
+            .map(|row| {
+                (0..columns)
+                    .map(|x| {
+                        let top = color(scaled.get_pixel(x, row * 2));
+                        let bottom = scaled.get_pixel_checked(x, row * 2 + 1).and_then(color);
+                        match (top, bottom) {
+                            (Some(top), Some(bottom)) => {
+                                Console::new("▀").fg(top).bg(bottom).to_string()
+                            }
+                            (Some(top), None) => Console::new("▀").fg(top).to_string(),
+                            (None, Some(bottom)) => Console::new("▄").fg(bottom).to_string(),
+                            (None, None) => " ".to_string(),
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.protocol.unwrap_or_else(ImageProtocol::detect) {
+            ImageProtocol::Kitty => f.write_str(&self.kitty()),
+            ImageProtocol::ITerm2 => f.write_str(&self.iterm2()),
+            ImageProtocol::Blocks => {
+                let width = self.columns.unwrap_or_else(crate::terminal::terminal_width);
+                f.write_str(&self.blocks(width))
+            }
+        }
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    fn png(width: u32, height: u32, pixel: [u8; 4]) -> Vec<u8> {
+        encode(RgbaImage::from_pixel(width, height, ::image::Rgba(pixel)))
+    }
+
+    fn encode(image: RgbaImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar!"), "Zm9vYmFyIQ==");
+    }
+
+    #[test]
+    fn test_detect() {
+        let detect = |vars: &[(&str, &str)]| {
+            ImageProtocol::from_env(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), ImageProtocol::Kitty);
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "iTerm.app")]),
+            ImageProtocol::ITerm2
+        );
+        assert_eq!(detect(&[("LC_TERMINAL", "iTerm2")]), ImageProtocol::ITerm2);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux")]),
+            ImageProtocol::Blocks
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ImageProtocol::Blocks);
+    }
+
+    #[test]
+    fn test_rejects_other_data() {
+        let error = Image::from_bytes(b"GIF89a...".to_vec()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(Image::from_bytes(b"not an image".to_vec()).is_err());
+        let error = Image::from_rgba(2, 2, vec![0; 15]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_iterm2_sequence() {
+        let data = png(2, 2, [255, 0, 0, 255]);
+        let image = Image::from_bytes(data.clone())
+            .unwrap()
+            .width(10)
+            .protocol(ImageProtocol::ITerm2);
+        assert_eq!(
+            image.to_string(),
+            format!(
+                "\x1b]1337;File=inline=1;size={};width=10;preserveAspectRatio=1:{}\x07",
+                data.len(),
+                base64(&data)
+            )
+        );
+    }
+
+    #[test]
+    fn test_kitty_chunks() {
+        // Noise doesn't compress, so the payload needs several chunks
+        let mut seed = 1u32;
+        let data = encode(RgbaImage::from_fn(64, 64, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            ::image::Rgba(seed.to_be_bytes())
+        }));
+        let image = Image::from_bytes(data.clone())
+            .unwrap()
+            .protocol(ImageProtocol::Kitty);
+        let output = image.to_string();
+        let encoded = base64(&data);
+        let commands: Vec<&str> = output.split("\x1b\\").filter(|c| !c.is_empty()).collect();
+        assert!(encoded.len() > 4096);
+        assert_eq!(commands.len(), encoded.len().div_ceil(4096));
+        assert!(commands[0].starts_with("\x1b_Ga=T,f=100,q=2,m="));
+        assert!(commands.last().unwrap().starts_with("\x1b_Gm=0;"));
+        let payload: String = commands
+            .iter()
+            .map(|c| c.split_once(';').unwrap().1)
+            .collect();
+        assert_eq!(payload, encoded);
+    }
+
+    #[test]
+    fn test_blocks_fallback() {
+        let image = Image::from_bytes(png(8, 8, [0, 128, 255, 255]))
+            .unwrap()
+            .width(4)
+            .protocol(ImageProtocol::Blocks);
+        let output = image.to_string();
+        assert_eq!(strip_ansi(&output), "▀▀▀▀\n▀▀▀▀");
+
+        let clear = Image::from_bytes(png(2, 4, [0, 0, 0, 0]))
+            .unwrap()
+            .protocol(ImageProtocol::Blocks);
+        assert_eq!(clear.to_string(), "  \n  ");
+    }
+
+    #[test]
+    fn test_half_blocks() {
+        // Red over blue in the left column, blue over nothing in the right
+        let pixels = RgbaImage::from_fn(2, 3, |x, y| match (x, y) {
+            (0, 0) | (1, 1) => Rgba([255, 0, 0, 255]),
+            (0, 1) | (0, 2) => Rgba([0, 0, 255, 255]),
+            _ => Rgba([0, 0, 0, 0]),
+        });
+        let image = Image::from_rgba(2, 3, pixels.into_raw()).unwrap();
+        let red = Color::RGB(255, 0, 0);
+        let blue = Color::RGB(0, 0, 255);
+        assert_eq!(
+            image.block_lines(2, true),
+            [
+                format!(
+                    "{}{}",
+                    Console::new("▀").fg(red).bg(blue),
+                    Console::new("▄").fg(red)
+                ),
+                format!("{} ", Console::new("▀").fg(blue)),
+            ]
+        );
+        assert_eq!(
+            image.block_lines(2, false)[1],
+            format!("{} ", Console::new("▀").fg(Color::Named(21)))
+        );
+    }
+}
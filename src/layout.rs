@@ -0,0 +1,203 @@
+use crate::Block;
+
+// How much of a layout's height (for rows) or width (for columns) a slot
+// takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Size {
+    Fixed(usize),
+    // An even share of whatever the fixed slots leave
+    Fill,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Rows,
+    Columns,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Slot {
+    Block,
+    Nested(Layout),
+}
+
+// Splits a screen into slots stacked top to bottom (`rows`) or side by
+// side (`columns`), each holding one block or a nested layout. Not a TUI
+// framework: `render` just fits blocks into the slots, to be drawn with
+// `AltScreen` whenever something changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    direction: Direction,
+    sizes: Vec<Size>,
+    slots: Vec<Slot>,
+}
+
+impl Layout {
+    pub fn rows<I: IntoIterator<Item = Size>>(sizes: I) -> Self {
+        Self::new(Direction::Rows, sizes)
+    }
+
+    pub fn columns<I: IntoIterator<Item = Size>>(sizes: I) -> Self {
+        Self::new(Direction::Columns, sizes)
+    }
+
+    fn new<I: IntoIterator<Item = Size>>(direction: Direction, sizes: I) -> Self {
+        let sizes: Vec<Size> = sizes.into_iter().collect();
+        Layout {
+            direction,
+            slots: vec![Slot::Block; sizes.len()],
+            sizes,
+        }
+    }
+
+    // Divides slot `index` further with another layout
+    pub fn split(mut self, index: usize, layout: Layout) -> Self {
+        if let Some(slot) = self.slots.get_mut(index) {
+            *slot = Slot::Nested(layout);
+        }
+        self
+    }
+
+    // Number of blocks `render` takes, counting those of nested layouts
+    pub fn len(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| match slot {
+                Slot::Block => 1,
+                Slot::Nested(layout) => layout.len(),
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Lays `blocks` out in a `width` by `height` area, filling the slots in
+    // order and nested layouts depth first. Each block is cut or padded to
+    // its slot; slots without a block are left blank.
+    pub fn render<I: IntoIterator<Item = Block>>(
+        &self,
+        width: usize,
+        height: usize,
+        blocks: I,
+    ) -> Block {
+        self.render_from(width, height, &mut blocks.into_iter())
+    }
+
+    fn render_from(
+        &self,
+        width: usize,
+        height: usize,
+        blocks: &mut dyn Iterator<Item = Block>,
+    ) -> Block {
+        let total = match self.direction {
+            Direction::Rows => height,
+            Direction::Columns => width,
+        };
+        let mut rendered = Block::new();
+        for (slot, extent) in self.slots.iter().zip(extents(&self.sizes, total)) {
+            let (slot_width, slot_height) = match self.direction {
+                Direction::Rows => (width, extent),
+                Direction::Columns => (extent, height),
+            };
+            let block = match slot {
+                Slot::Block => blocks
+                    .next()
+                    .unwrap_or_default()
+                    .pad_to_rect(slot_width, slot_height),
+                Slot::Nested(layout) => layout.render_from(slot_width, slot_height, blocks),
+            };
+            rendered = match self.direction {
+                Direction::Rows => rendered.above(block),
+                Direction::Columns if rendered.height() == 0 => block,
+                Direction::Columns => rendered.beside(block),
+            };
+        }
+        rendered.pad_to_rect(width, height)
+    }
+}
+
+// Fixed slots get their size while there's room left, in order, then the
+// rest is shared between the fill slots, the first ones taking any odd
+// line or column
+fn extents(sizes: &[Size], total: usize) -> Vec<usize> {
+    let mut left = total;
+    let mut extents: Vec<usize> = sizes
+        .iter()
+        .map(|size| match size {
+            Size::Fixed(n) => {
+                let n = (*n).min(left);
+                left -= n;
+                n
+            }
+            Size::Fill => 0,
+        })
+        .collect();
+    let fills = sizes.iter().filter(|&&size| size == Size::Fill).count();
+    let mut fill_index = 0;
+    for (extent, size) in extents.iter_mut().zip(sizes) {
+        if *size == Size::Fill {
+            *extent = left / fills + usize::from(fill_index < left % fills);
+            fill_index += 1;
+        }
+    }
+    extents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Size::{Fill, Fixed};
+    use super::*;
+
+    fn plain(block: &Block) -> Vec<String> {
+        block.lines().iter().map(|line| line.plain()).collect()
+    }
+
+    #[test]
+    fn test_extents() {
+        assert_eq!(extents(&[Fixed(3), Fill, Fixed(1)], 10), [3, 6, 1]);
+        assert_eq!(extents(&[Fill, Fill, Fill], 10), [4, 3, 3]);
+        assert_eq!(extents(&[Fixed(8), Fixed(4), Fill], 10), [8, 2, 0]);
+        assert_eq!(extents(&[Fixed(2)], 10), [2]);
+        assert!(extents(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn test_render_rows() {
+        let layout = Layout::rows([Fixed(1), Fill, Fixed(1)]);
+        let block = layout.render(
+            6,
+            5,
+            [
+                Block::from("status: ok"),
+                Block::from("a\nb"),
+                Block::from("q"),
+            ],
+        );
+        assert_eq!(
+            plain(&block),
+            ["status", "a     ", "b     ", "      ", "q     "]
+        );
+    }
+
+    #[test]
+    fn test_render_nested_columns() {
+        let layout = Layout::rows([Fixed(1), Fill]).split(1, Layout::columns([Fixed(3), Fill]));
+        assert_eq!(layout.len(), 3);
+        let block = layout.render(
+            8,
+            3,
+            [
+                Block::from("header"),
+                Block::from("nav\nlog"),
+                Block::from("main"),
+            ],
+        );
+        assert_eq!(plain(&block), ["header  ", "navmain ", "log     "]);
+
+        // Missing blocks leave their slots blank
+        let block = layout.render(8, 3, [Block::from("header")]);
+        assert_eq!(plain(&block), ["header  ", "        ", "        "]);
+    }
+}
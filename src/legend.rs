@@ -0,0 +1,101 @@
+use std::fmt::{self, Display};
+
+use crate::terminal::terminal_width;
+use crate::theme::{Tone, theme};
+use crate::{Console, Style, StyledText};
+
+// A one-line bar of key bindings for interactive widgets and dashboards,
+// each key on a reverse-video badge followed by a dimmed label:
+//
+//    q  quit   ↑/↓  move   enter  select
+//
+// Bindings that don't fit in the width are left off the end.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Legend {
+    keys: Vec<(String, String)>,
+    width: Option<usize>,
+}
+
+impl Legend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn key<K: Into<String>, L: Into<String>>(mut self, key: K, label: L) -> Self {
+        self.keys.push((key.into(), label.into()));
+        self
+    }
+
+    pub fn keys<I, K, L>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = (K, L)>,
+        K: Into<String>,
+        L: Into<String>,
+    {
+        self.keys
+            .extend(keys.into_iter().map(|(k, l)| (k.into(), l.into())));
+        self
+    }
+
+    // Columns available, defaults to the terminal width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // The bar as styled text, e.g. for the last line of a `Layout`
+    pub fn to_text(&self) -> StyledText {
+        let width = self.width.unwrap_or_else(terminal_width);
+        let label_style = theme().style(Tone::Muted).clone();
+        let mut text = StyledText::new();
+        for (key, label) in &self.keys {
+            let key = Console::new_with_style(format!(" {} ", key), Style::new().reverse());
+            let label = Console::new_with_style(format!(" {}", label), label_style.clone());
+            let gap = if text.is_empty() { 0 } else { 2 };
+            if text.width() + gap + key.width() + label.width() > width {
+                break;
+            }
+            if gap > 0 {
+                text.push(Console::repeat(' ', gap));
+            }
+            text.push(key);
+            text.push(label);
+        }
+        text
+    }
+}
+
+impl Display for Legend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legend() -> Legend {
+        Legend::new()
+            .key("q", "quit")
+            .keys([("↑/↓", "move"), ("enter", "select")])
+    }
+
+    #[test]
+    fn test_legend() {
+        assert_eq!(
+            legend().width(80).to_text().plain(),
+            " q  quit   ↑/↓  move   enter  select"
+        );
+        assert_eq!(
+            legend().width(80).to_text().segments()[0],
+            Console::new(" q ").reverse()
+        );
+    }
+
+    #[test]
+    fn test_narrow_legend_drops_keys() {
+        assert_eq!(legend().width(20).to_text().plain(), " q  quit   ↑/↓  move");
+        assert_eq!(legend().width(3).to_text().plain(), "");
+    }
+}
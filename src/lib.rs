@@ -1,6 +1,191 @@
-use std::io::Write;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// Lets the code generated by `#[derive(PrettyDisplay)]` refer to
+// `::pretty_console` from inside this crate too
+extern crate self as pretty_console;
+
+#[cfg(not(feature = "no-color"))]
+use capability::Capabilities;
+
+mod accessible;
+mod alt_screen;
+mod ansi;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod badge;
+mod banner;
+mod big_text;
+mod block;
+mod browser;
+mod calendar;
+mod callout;
+mod capability;
+mod cleanup;
+mod clock;
+mod color_scale;
+#[cfg(feature = "regex")]
+mod colorizer;
+mod command;
+pub mod cursor;
+mod debug;
+mod delta;
+mod draw_target;
+mod editor;
+mod emoji;
+mod estimator;
+pub mod events;
+mod example;
+mod findings;
+mod format;
+#[cfg(feature = "textwrap")]
+mod fragment;
+mod fuzzy_select;
+mod grid;
+mod group;
+mod gutter;
+mod highlight;
+mod histogram;
+mod html;
+mod indent;
+#[cfg(feature = "image")]
+mod inline_image;
+mod layout;
+mod legend;
+mod live;
+mod message;
+mod multi_select;
+mod multiplexer;
+mod nested_progress;
+mod normalize;
+mod notice;
+mod overwrite_prompt;
+mod pinned;
+mod policy;
+mod pool_progress;
+#[cfg(feature = "derive")]
+mod pretty_display;
+mod progress;
+mod progress_io;
+mod record;
+mod reporter;
+mod resize;
+mod rows;
+mod sanitize;
+mod select;
+mod spinner;
+mod steps;
+mod styled;
+mod suggest;
+mod summary;
+#[cfg(feature = "svg")]
+mod svg;
+mod symbols;
+mod table;
+mod task_list;
+mod tee;
+mod template;
+mod terminal;
+mod test_reporter;
+pub mod testing;
+mod text;
+mod theme;
+mod throttle;
+mod timer;
+mod timestamp;
+mod transcript;
+mod unicode;
+mod virtual_terminal;
+mod watch;
+mod wrap;
+
+pub use accessible::{accessible_mode, set_accessible_mode};
+pub use alt_screen::AltScreen;
+pub use ansi::parse_ansi;
+pub use badge::Badge;
+pub use banner::Banner;
+pub use big_text::{BigText, FigletFont};
+pub use block::Block;
+pub use browser::console_log_args;
+pub use calendar::Calendar;
+pub use callout::Callout;
+#[cfg(feature = "cleanup")]
+pub use cleanup::install_cleanup_handlers;
+pub use cleanup::{CleanupGuard, register_cleanup, run_cleanup};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use color_scale::ColorScale;
+#[cfg(feature = "regex")]
+pub use colorizer::Colorizer;
+pub use command::StyledCommand;
+pub use debug::{dbg_location, pretty_debug};
+pub use delta::StyleDelta;
+pub use draw_target::{
+    DrawTarget, set_stderr_target, set_stdout_target, stderr_target, stdout_target,
+};
+pub use editor::edit_in_editor;
+pub use emoji::{EmojiMode, emoji_mode, emojify, set_emoji_mode};
+pub use estimator::{AverageEstimator, Estimator, PhasedEstimator, SmoothedEstimator};
+pub use example::Example;
+pub use findings::{Finding, Findings};
+pub use format::{
+    TimeAgo, human_bytes, human_bytes_decimal, human_count, human_duration, human_time_ago,
+};
+#[cfg(feature = "textwrap")]
+pub use fragment::{StyledWord, wrap_styled};
+pub use fuzzy_select::FuzzySelect;
+pub use group::Group;
+pub use gutter::Gutter;
+pub use histogram::Histogram;
+pub use html::HtmlRenderer;
+pub use indent::{IndentWriter, Indented};
+#[cfg(feature = "image")]
+pub use inline_image::{Image, ImageProtocol};
+pub use layout::{Layout, Size};
+pub use legend::Legend;
+pub use live::LiveRegion;
+pub use message::print_message;
+pub use multi_select::MultiSelect;
+pub use multiplexer::{MultiplexStream, Multiplexer};
+pub use nested_progress::{ChildProgress, NestedProgress};
+pub use normalize::{expand_tabs, show_controls};
+pub use notice::Notice;
+pub use overwrite_prompt::{Overwrite, OverwritePrompt};
+pub use pinned::PinnedRegion;
+pub use policy::{EffectPolicy, set_effect_policy};
+pub use pool_progress::PoolProgress;
+#[cfg(feature = "derive")]
+pub use pretty_console_derive::PrettyDisplay;
+#[cfg(feature = "derive")]
+pub use pretty_display::{write_pretty_field, write_pretty_title};
+pub use progress::{ProgressBar, ProgressState};
+pub use progress_io::{ProgressReader, ProgressWriter};
+pub use record::Recorder;
+pub use reporter::{Reporter, Verbosity};
+pub use resize::ResizeWatcher;
+pub use rows::Rows;
+pub use sanitize::sanitize;
+pub use select::Select;
+pub use spinner::Spinner;
+pub use steps::Steps;
+pub use styled::Styled;
+pub use suggest::Suggestion;
+pub use summary::Summary;
+#[cfg(feature = "svg")]
+pub use svg::SvgRenderer;
+pub use symbols::{Border, Symbols, set_symbols, symbols};
+pub use table::{Align, Numbers, Table};
+pub use task_list::{TaskList, TaskState};
+pub use tee::TeeWriter;
+pub use template::Template;
+pub use terminal::{Background, terminal_background, terminal_size};
+pub use test_reporter::TestReporter;
+pub use text::StyledText;
+pub use theme::{Theme, Tone, set_theme, theme};
+pub use throttle::{max_fps, set_max_fps};
+pub use timer::Timer;
+pub use timestamp::Timestamp;
+pub use transcript::prompt_transcript;
+pub use watch::Watch;
+pub use wrap::rewrap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Color {
     Named(u8),
     RGB(u8, u8, u8),
@@ -24,22 +209,95 @@ impl Color {
     pub const BRIGHT_CYAN: Color = Color::Named(14);
     pub const BRIGHT_WHITE: Color = Color::Named(15);
 
-    fn to_fg_code(&self) -> String {
+    // Approximate RGB value, using the xterm palette for named colors
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 0, 0),
+            (0, 205, 0),
+            (205, 205, 0),
+            (0, 0, 238),
+            (205, 0, 205),
+            (0, 205, 205),
+            (229, 229, 229),
+            (127, 127, 127),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (92, 92, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+
+        match self {
+            Color::RGB(r, g, b) => (r, g, b),
+            Color::Named(n @ 0..=15) => BASIC[n as usize],
+            Color::Named(n @ 16..=231) => {
+                let level = |v: u8| if v == 0 { 0 } else { 55 + 40 * v };
+                let i = n - 16;
+                (level(i / 36), level(i / 6 % 6), level(i % 6))
+            }
+            Color::Named(n) => {
+                let gray = 8 + 10 * (n - 232);
+                (gray, gray, gray)
+            }
+        }
+    }
+
+    // Nearest entry of the 256-color palette, for terminals without
+    // 24-bit color. Named colors are returned unchanged.
+    pub fn to_256(self) -> Color {
+        let Color::RGB(r, g, b) = self else {
+            return self;
+        };
+        let distance = |(r2, g2, b2): (u8, u8, u8)| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, r2) + d(g, g2) + d(b, b2)
+        };
+        // Closest level of the 6x6x6 cube, per channel
+        let level = |v: u8| match v {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (v - 35) / 40,
+        };
+        let cube = Color::Named(16 + 36 * level(r) + 6 * level(g) + level(b));
+        // Closest step of the grayscale ramp
+        let average = (r as u16 + g as u16 + b as u16) / 3;
+        let gray = Color::Named(232 + ((average.saturating_sub(3)) / 10).min(23) as u8);
+        if distance(gray.to_rgb()) < distance(cube.to_rgb()) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    fn to_fg_code(self) -> String {
         match self {
             Color::Named(n) => format!("38;5;{}", n),
             Color::RGB(r, g, b) => format!("38;2;{};{};{}", r, g, b),
         }
     }
 
-    fn to_bg_code(&self) -> String {
+    #[cfg(not(feature = "no-color"))]
+    fn to_bg_code(self) -> String {
         match self {
             Color::Named(n) => format!("48;5;{}", n),
             Color::RGB(r, g, b) => format!("48;2;{};{};{}", r, g, b),
         }
     }
+
+    #[cfg(not(feature = "no-color"))]
+    fn to_underline_code(self) -> String {
+        match self {
+            Color::Named(n) => format!("58;5;{}", n),
+            Color::RGB(r, g, b) => format!("58;2;{};{};{}", r, g, b),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Attribute {
     Bold,
     Dim,
@@ -49,10 +307,16 @@ pub enum Attribute {
     Reverse,
     Hidden,
     Strikethrough,
+    Framed,
+    Encircled,
+    Overline,
+    Superscript,
+    Subscript,
 }
 
 impl Attribute {
-    fn to_code(&self) -> &'static str {
+    #[cfg(not(feature = "no-color"))]
+    fn to_code(self) -> &'static str {
         match self {
             Attribute::Bold => "1",
             Attribute::Dim => "2",
@@ -62,15 +326,108 @@ impl Attribute {
             Attribute::Reverse => "7",
             Attribute::Hidden => "8",
             Attribute::Strikethrough => "9",
+            Attribute::Framed => "51",
+            Attribute::Encircled => "52",
+            Attribute::Overline => "53",
+            Attribute::Superscript => "73",
+            Attribute::Subscript => "74",
+        }
+    }
+
+    // SGR 21 is double underline on most terminals rather than "bold off",
+    // so bold shares 22 with dim
+    fn to_off_code(self) -> &'static str {
+        match self {
+            Attribute::Bold | Attribute::Dim => "22",
+            Attribute::Italic => "23",
+            Attribute::Underline => "24",
+            Attribute::Blink => "25",
+            Attribute::Reverse => "27",
+            Attribute::Hidden => "28",
+            Attribute::Strikethrough => "29",
+            Attribute::Framed | Attribute::Encircled => "54",
+            Attribute::Overline => "55",
+            Attribute::Superscript | Attribute::Subscript => "75",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl UnderlineStyle {
+    #[cfg(not(feature = "no-color"))]
+    fn to_code(self) -> &'static str {
+        match self {
+            UnderlineStyle::Single => "4",
+            UnderlineStyle::Double => "4:2",
+            UnderlineStyle::Curly => "4:3",
+            UnderlineStyle::Dotted => "4:4",
+            UnderlineStyle::Dashed => "4:5",
         }
     }
 }
 
-#[derive(Clone, Default)]
+// Styles compare and hash by what they render: the order attributes were
+// added in, and adding one twice, make no difference
+#[derive(Debug, Clone, Default)]
 pub struct Style {
     foreground: Option<Color>,
     background: Option<Color>,
     attributes: Vec<Attribute>,
+    // Explicitly turned off, so a child style can undo what a parent set
+    removed: Vec<Attribute>,
+    clear_fg: bool,
+    clear_bg: bool,
+    // Terminals without support fall back to a plain underline
+    underline_style: Option<UnderlineStyle>,
+    underline_color: Option<Color>,
+    clear_underline_color: bool,
+    // Semantic name shown as a `[LABEL]` prefix in accessible mode
+    label: Option<String>,
+}
+
+// The attributes in `list` as a set, one bit each
+pub(crate) fn attribute_set(list: &[Attribute]) -> u16 {
+    list.iter().fold(0, |set, &attr| set | 1 << attr as u16)
+}
+
+impl PartialEq for Style {
+    fn eq(&self, other: &Self) -> bool {
+        self.foreground == other.foreground
+            && self.background == other.background
+            && attribute_set(&self.attributes) == attribute_set(&other.attributes)
+            && attribute_set(&self.removed) == attribute_set(&other.removed)
+            && self.clear_fg == other.clear_fg
+            && self.clear_bg == other.clear_bg
+            && self.underline_style == other.underline_style
+            && self.underline_color == other.underline_color
+            && self.clear_underline_color == other.clear_underline_color
+            && self.label == other.label
+    }
+}
+
+impl Eq for Style {}
+
+impl std::hash::Hash for Style {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.foreground.hash(state);
+        self.background.hash(state);
+        attribute_set(&self.attributes).hash(state);
+        attribute_set(&self.removed).hash(state);
+        self.clear_fg.hash(state);
+        self.clear_bg.hash(state);
+        self.underline_style.hash(state);
+        self.underline_color.hash(state);
+        self.clear_underline_color.hash(state);
+        self.label.hash(state);
+    }
 }
 
 impl Style {
@@ -80,19 +437,78 @@ impl Style {
 
     pub fn fg(mut self, color: Color) -> Self {
         self.foreground = Some(color);
+        self.clear_fg = false;
         self
     }
 
     pub fn bg(mut self, color: Color) -> Self {
         self.background = Some(color);
+        self.clear_bg = false;
         self
     }
 
     pub fn attr(mut self, attribute: Attribute) -> Self {
+        self.removed.retain(|&a| a != attribute);
         self.attributes.push(attribute);
         self
     }
 
+    // Clearing and negation
+    pub fn clear_fg(mut self) -> Self {
+        self.foreground = None;
+        self.clear_fg = true;
+        self
+    }
+
+    pub fn clear_bg(mut self) -> Self {
+        self.background = None;
+        self.clear_bg = true;
+        self
+    }
+
+    pub fn no_attr(mut self, attribute: Attribute) -> Self {
+        if attribute == Attribute::Underline {
+            self.underline_style = None;
+        }
+        self.attributes.retain(|&a| a != attribute);
+        if !self.removed.contains(&attribute) {
+            self.removed.push(attribute);
+        }
+        self
+    }
+
+    pub fn no_bold(self) -> Self {
+        self.no_attr(Attribute::Bold)
+    }
+
+    pub fn no_dim(self) -> Self {
+        self.no_attr(Attribute::Dim)
+    }
+
+    pub fn no_italic(self) -> Self {
+        self.no_attr(Attribute::Italic)
+    }
+
+    pub fn no_underline(self) -> Self {
+        self.no_attr(Attribute::Underline)
+    }
+
+    pub fn no_blink(self) -> Self {
+        self.no_attr(Attribute::Blink)
+    }
+
+    pub fn no_reverse(self) -> Self {
+        self.no_attr(Attribute::Reverse)
+    }
+
+    pub fn no_hidden(self) -> Self {
+        self.no_attr(Attribute::Hidden)
+    }
+
+    pub fn no_strikethrough(self) -> Self {
+        self.no_attr(Attribute::Strikethrough)
+    }
+
     pub fn bold(self) -> Self {
         self.attr(Attribute::Bold)
     }
@@ -109,6 +525,32 @@ impl Style {
         self.attr(Attribute::Underline)
     }
 
+    pub fn underline_style(mut self, underline_style: UnderlineStyle) -> Self {
+        self.underline_style = Some(underline_style);
+        if self.attributes.contains(&Attribute::Underline) {
+            self
+        } else {
+            self.attr(Attribute::Underline)
+        }
+    }
+
+    pub fn underline_color(mut self, color: Color) -> Self {
+        self.underline_color = Some(color);
+        self.clear_underline_color = false;
+        self
+    }
+
+    pub fn clear_underline_color(mut self) -> Self {
+        self.underline_color = None;
+        self.clear_underline_color = true;
+        self
+    }
+
+    pub fn label<T: Into<String>>(mut self, label: T) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     pub fn blink(self) -> Self {
         self.attr(Attribute::Blink)
     }
@@ -125,20 +567,81 @@ impl Style {
         self.attr(Attribute::Strikethrough)
     }
 
+    pub fn framed(self) -> Self {
+        self.attr(Attribute::Framed)
+    }
+
+    pub fn encircled(self) -> Self {
+        self.attr(Attribute::Encircled)
+    }
+
+    pub fn overline(self) -> Self {
+        self.attr(Attribute::Overline)
+    }
+
+    pub fn superscript(self) -> Self {
+        self.attr(Attribute::Superscript)
+    }
+
+    pub fn subscript(self) -> Self {
+        self.attr(Attribute::Subscript)
+    }
+
     #[cfg(not(feature = "no-color"))]
     fn to_ansi_start(&self) -> String {
+        match policy::apply_current(self) {
+            Some(style) => style.to_ansi_start_with(Capabilities::get()),
+            None => self.to_ansi_start_with(Capabilities::get()),
+        }
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    fn to_ansi_start_with(&self, capabilities: Capabilities) -> String {
         let mut codes: Vec<String> = Vec::new();
 
-        for attr in &self.attributes {
-            codes.push(attr.to_code().into());
+        // Negations go first so `22` can't cancel a dim set by this style
+        for attr in self.removed.iter().filter(|a| capabilities.supports(**a)) {
+            let code = attr.to_off_code();
+            if !codes.iter().any(|c| c == code) {
+                codes.push(code.into());
+            }
+        }
+
+        for attr in self
+            .attributes
+            .iter()
+            .filter(|a| capabilities.supports(**a))
+        {
+            match self.underline_style {
+                Some(underline) if *attr == Attribute::Underline => {
+                    if capabilities.styled_underline {
+                        codes.push(underline.to_code().into());
+                    } else {
+                        codes.push(attr.to_code().into());
+                    }
+                }
+                _ => codes.push(attr.to_code().into()),
+            }
         }
 
         if let Some(fg) = &self.foreground {
             codes.push(fg.to_fg_code());
+        } else if self.clear_fg {
+            codes.push("39".into());
         }
 
         if let Some(bg) = &self.background {
             codes.push(bg.to_bg_code());
+        } else if self.clear_bg {
+            codes.push("49".into());
+        }
+
+        if capabilities.styled_underline {
+            if let Some(color) = self.underline_color {
+                codes.push(color.to_underline_code());
+            } else if self.clear_underline_color {
+                codes.push("59".into());
+            }
         }
 
         if codes.is_empty() {
@@ -154,16 +657,45 @@ impl Style {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct Console {
     text: String,
     style: Style,
 }
 
+// Repeats the text, keeping the style: `Console::new("─").dim() * 40`
+impl std::ops::Mul<usize> for Console {
+    type Output = Console;
+
+    fn mul(mut self, count: usize) -> Console {
+        self.text = self.text.repeat(count);
+        self
+    }
+}
+
+impl From<&str> for Console {
+    fn from(text: &str) -> Self {
+        Console::new(text)
+    }
+}
+
+impl From<String> for Console {
+    fn from(text: String) -> Self {
+        Console::new(text)
+    }
+}
+
 impl Console {
+    // Known `:shortcodes:` in `text` become emoji, as `set_emoji_mode`
+    // allows
     pub fn new<T: Into<String>>(text: T) -> Self {
+        let text = text.into();
         Console {
-            text: text.into(),
+            text: if text.contains(':') {
+                emoji::emojify(&text)
+            } else {
+                text
+            },
             style: Style::default(),
         }
     }
@@ -182,6 +714,95 @@ impl Console {
         }
     }
 
+    // `ch` repeated `count` times, e.g. for separators
+    pub fn repeat(ch: char, count: usize) -> Self {
+        Console::new_with_style(ch.to_string().repeat(count), Style::new())
+    }
+
+    // `ch` repeated across the whole terminal width
+    pub fn fill_width(ch: char) -> Self {
+        let columns = unicode::width(ch.encode_utf8(&mut [0; 4])).max(1);
+        Self::repeat(ch, terminal::terminal_width() / columns)
+    }
+
+    // `✗ message` in the theme's error style, for one-line output from
+    // small tools; `warning`, `success`, `info` and `hint` are the same
+    // for the other tones
+    pub fn error<T: std::fmt::Display>(message: T) -> Self {
+        Self::preset(Tone::Error, message)
+    }
+
+    pub fn warning<T: std::fmt::Display>(message: T) -> Self {
+        Self::preset(Tone::Warning, message)
+    }
+
+    pub fn success<T: std::fmt::Display>(message: T) -> Self {
+        Self::preset(Tone::Success, message)
+    }
+
+    pub fn info<T: std::fmt::Display>(message: T) -> Self {
+        Self::preset(Tone::Info, message)
+    }
+
+    pub fn hint<T: std::fmt::Display>(message: T) -> Self {
+        Self::preset(Tone::Hint, message)
+    }
+
+    fn preset<T: std::fmt::Display>(tone: Tone, message: T) -> Self {
+        let theme = theme();
+        Console::new_with_style(
+            format!("{} {}", theme.icon(tone), message),
+            theme.labeled_style(tone),
+        )
+    }
+
+    pub fn with_style(&self, style: Style) -> Self {
+        Console {
+            text: self.text.clone(),
+            style,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    pub fn style_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    // Terminal columns taken by the text
+    pub fn width(&self) -> usize {
+        unicode::width(&self.text)
+    }
+
+    // The part that fits in `columns`, never splitting a grapheme cluster
+    pub fn take_visible(&self, columns: usize) -> Console {
+        self.with_text(unicode::take_width(&self.text, columns))
+    }
+
+    pub fn map_chars<F>(&self, style_for: F) -> StyledText
+    where
+        F: FnMut(usize, char) -> Style,
+    {
+        StyledText::from(self.clone()).map_chars(style_for)
+    }
+
+    pub fn split_at_visible(&self, columns: usize) -> (Console, Console) {
+        let (head, tail) = self
+            .text
+            .split_at(unicode::take_width(&self.text, columns).len());
+        (self.with_text(head), self.with_text(tail))
+    }
+
     // Color methods
     pub fn fg(self, color: Color) -> Self {
         Console {
@@ -370,6 +991,27 @@ impl Console {
         }
     }
 
+    pub fn underline_style(self, underline_style: UnderlineStyle) -> Self {
+        Console {
+            style: self.style.underline_style(underline_style),
+            ..self
+        }
+    }
+
+    pub fn underline_color(self, color: Color) -> Self {
+        Console {
+            style: self.style.underline_color(color),
+            ..self
+        }
+    }
+
+    pub fn label<T: Into<String>>(self, label: T) -> Self {
+        Console {
+            style: self.style.label(label),
+            ..self
+        }
+    }
+
     pub fn blink(self) -> Self {
         Console {
             style: self.style.blink(),
@@ -398,19 +1040,104 @@ impl Console {
         }
     }
 
+    pub fn framed(self) -> Self {
+        Console {
+            style: self.style.framed(),
+            ..self
+        }
+    }
+
+    pub fn encircled(self) -> Self {
+        Console {
+            style: self.style.encircled(),
+            ..self
+        }
+    }
+
+    pub fn overline(self) -> Self {
+        Console {
+            style: self.style.overline(),
+            ..self
+        }
+    }
+
+    pub fn superscript(self) -> Self {
+        Console {
+            style: self.style.superscript(),
+            ..self
+        }
+    }
+
+    pub fn subscript(self) -> Self {
+        Console {
+            style: self.style.subscript(),
+            ..self
+        }
+    }
+
     // Output methods
     pub fn print(&self) {
-        let mut stdout = std::io::stdout();
-        self.write_to(&mut stdout).unwrap();
+        ignore_broken_pipe(self.try_print());
     }
 
     pub fn println(&self) {
-        let mut stdout = std::io::stdout();
-        self.write_to(&mut stdout).unwrap();
-        writeln!(stdout).unwrap();
+        ignore_broken_pipe(self.try_println());
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+    pub fn try_print(&self) -> std::io::Result<()> {
+        group::write_stdout(&self.to_string())
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+    pub fn try_println(&self) -> std::io::Result<()> {
+        group::write_stdout(&format!("{}\n", self))
+    }
+
+    // In the browser both go to the devtools console, one message per call
+    #[cfg(all(target_arch = "wasm32", feature = "web"))]
+    pub fn try_print(&self) -> std::io::Result<()> {
+        browser::console_log(&StyledText::from(self.clone()));
+        Ok(())
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "web"))]
+    pub fn try_println(&self) -> std::io::Result<()> {
+        self.try_print()
+    }
+
+    // Like `print`/`println`, but the styled message is rendered up front
+    // and written under the stdout lock in one call, so messages from
+    // different threads can't interleave inside each other's escape codes
+    pub fn print_locked(&self) {
+        ignore_broken_pipe(
+            stdout_target().write_locked(|mut out| self.write_atomic(&mut out, false)),
+        );
+    }
+
+    pub fn println_locked(&self) {
+        ignore_broken_pipe(
+            stdout_target().write_locked(|mut out| self.write_atomic(&mut out, true)),
+        );
+    }
+
+    fn write_atomic<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        newline: bool,
+    ) -> std::io::Result<()> {
+        let mut rendered = self.to_string();
+        if newline {
+            rendered.push('\n');
+        }
+        writer.write_all(group::prefix_stdout(&rendered).as_bytes())?;
+        writer.flush()
     }
 
     pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if accessible::accessible_mode() {
+            return write!(writer, "{}", accessible::render(&self.style, &self.text));
+        }
         let ansi_code = self.style.to_ansi_start();
         if !ansi_code.is_empty() {
             write!(writer, "{}", ansi_code)?;
@@ -421,26 +1148,71 @@ impl Console {
         }
         Ok(())
     }
+}
 
-    pub fn to_string(&self) -> String {
-        format!("{}", self)
+// A closed pipe (e.g. `tool | head`) is not worth a panic; the reader
+// simply stopped listening
+fn ignore_broken_pipe(result: std::io::Result<()>) {
+    if let Err(e) = result
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        panic!("failed printing to stdout: {}", e);
     }
 }
 
+// Behaves like a `str` for the formatter's flags: precision truncates to
+// that many columns and width pads with the fill character outside the
+// styled text, so escape codes never count towards alignment
 impl std::fmt::Display for Console {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let ansi_code = self.style.to_ansi_start();
-        if !ansi_code.is_empty() {
-            write!(f, "{}", ansi_code)?;
-        }
-        write!(f, "{}", self.text)?;
-        if !ansi_code.is_empty() {
-            write!(f, "\x1b[0m")?;
-        }
-        Ok(())
+        let text = match f.precision() {
+            Some(columns) => unicode::take_width(&self.text, columns),
+            None => &self.text,
+        };
+        write_padded(f, unicode::width(text), |f| {
+            if accessible::accessible_mode() {
+                return f.write_str(&accessible::render(&self.style, text));
+            }
+            let ansi_code = self.style.to_ansi_start();
+            if !ansi_code.is_empty() {
+                write!(f, "{}", ansi_code)?;
+            }
+            f.write_str(text)?;
+            if !ansi_code.is_empty() {
+                write!(f, "\x1b[0m")?;
+            }
+            Ok(())
+        })
     }
 }
 
+// Writes `body`, which takes `columns` columns, padded to the formatter's
+// width. Text is left-aligned unless the formatter asks otherwise.
+pub(crate) fn write_padded<F>(
+    f: &mut std::fmt::Formatter,
+    columns: usize,
+    body: F,
+) -> std::fmt::Result
+where
+    F: FnOnce(&mut std::fmt::Formatter) -> std::fmt::Result,
+{
+    let padding = f.width().unwrap_or(0).saturating_sub(columns);
+    let (before, after) = match f.align() {
+        Some(std::fmt::Alignment::Right) => (padding, 0),
+        Some(std::fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (0, padding),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        write!(f, "{}", fill)?;
+    }
+    body(f)?;
+    for _ in 0..after {
+        write!(f, "{}", fill)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -466,6 +1238,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "no-color"))]
     fn test_color_codes() {
         // Test named colors
         let color = Color::RED;
@@ -486,6 +1259,32 @@ mod tests {
     }
 
     #[test]
+    fn test_color_to_rgb() {
+        assert_eq!(Color::RED.to_rgb(), (205, 0, 0));
+        assert_eq!(Color::BRIGHT_WHITE.to_rgb(), (255, 255, 255));
+        assert_eq!(Color::RGB(1, 2, 3).to_rgb(), (1, 2, 3));
+        // 6x6x6 color cube
+        assert_eq!(Color::Named(16).to_rgb(), (0, 0, 0));
+        assert_eq!(Color::Named(196).to_rgb(), (255, 0, 0));
+        assert_eq!(Color::Named(231).to_rgb(), (255, 255, 255));
+        // Grayscale ramp
+        assert_eq!(Color::Named(232).to_rgb(), (8, 8, 8));
+        assert_eq!(Color::Named(255).to_rgb(), (238, 238, 238));
+    }
+
+    #[test]
+    fn test_color_to_256() {
+        assert_eq!(Color::RGB(255, 0, 0).to_256(), Color::Named(196));
+        assert_eq!(Color::RGB(0, 0, 0).to_256(), Color::Named(16));
+        assert_eq!(Color::RGB(255, 255, 255).to_256(), Color::Named(231));
+        assert_eq!(Color::RGB(100, 160, 210).to_256(), Color::Named(74));
+        // Grays land on the ramp rather than the cube
+        assert_eq!(Color::RGB(128, 128, 128).to_256(), Color::Named(244));
+        assert_eq!(Color::RED.to_256(), Color::RED);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
     fn test_attribute_codes() {
         assert_eq!(Attribute::Bold.to_code(), "1");
         assert_eq!(Attribute::Dim.to_code(), "2");
@@ -835,6 +1634,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "no-color"))]
     fn test_ansi_code_ordering() {
         // Test that ANSI codes are generated in consistent order:
         // attributes first, then foreground, then background
@@ -844,23 +1644,17 @@ mod tests {
             .underline()
             .bold();
 
-        #[cfg(not(feature = "no-color"))]
-        {
-            let ansi = style.to_ansi_start();
-            // The order should be: 1 (bold), 4 (underline), 38;5;1 (red), 48;5;4 (blue)
-            let expected_parts = ["1", "4", "38;5;1", "48;5;4"];
-            let ansi_without_prefix = ansi.trim_start_matches("\x1b[").trim_end_matches('m');
-            let parts: Vec<&str> = ansi_without_prefix.split(';').collect();
-
-            // We can't easily test the exact order because it depends on Vec iteration order,
-            // but we can test that all expected parts are present
-            for expected in expected_parts {
-                assert!(
-                    ansi.contains(expected),
-                    "ANSI code should contain {}",
-                    expected
-                );
-            }
+        let ansi = style.to_ansi_start();
+        // The order should be: 1 (bold), 4 (underline), 38;5;1 (red), 48;5;4 (blue)
+        let expected_parts = ["1", "4", "38;5;1", "48;5;4"];
+        // We can't easily test the exact order because it depends on Vec iteration order,
+        // but we can test that all expected parts are present
+        for expected in expected_parts {
+            assert!(
+                ansi.contains(expected),
+                "ANSI code should contain {}",
+                expected
+            );
         }
     }
 
@@ -907,7 +1701,7 @@ mod tests {
     }
     #[test]
     fn test_style_combinations() {
-        let styles = vec![
+        let styles = [
             Console::new("Error style").red().bold(),
             Console::new("Warning style").yellow().italic(),
             Console::new("Success style").green().bold(),
@@ -917,6 +1711,102 @@ mod tests {
         insta::assert_yaml_snapshot!(outputs);
     }
 
+    #[test]
+    fn test_write_atomic_is_a_single_write() {
+        struct Counting(Vec<u8>, usize);
+        impl std::io::Write for Counting {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.extend_from_slice(buf);
+                self.1 += 1;
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let console = Console::new("locked").red().bold();
+        let mut writer = Counting(Vec::new(), 0);
+        console.write_atomic(&mut writer, true).unwrap();
+        assert_eq!(writer.1, 1);
+        assert_eq!(writer.0, format!("{}\n", console).into_bytes());
+    }
+
+    #[test]
+    fn test_ignore_broken_pipe() {
+        use std::io::{Error, ErrorKind};
+        ignore_broken_pipe(Ok(()));
+        ignore_broken_pipe(Err(Error::from(ErrorKind::BrokenPipe)));
+        let other = std::panic::catch_unwind(|| {
+            ignore_broken_pipe(Err(Error::from(ErrorKind::PermissionDenied)))
+        });
+        assert!(other.is_err());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_style_negation() {
+        let style = Style::new().no_bold().no_underline().clear_fg().clear_bg();
+        assert_eq!(style.to_ansi_start(), "\x1b[22;24;39;49m");
+
+        // Bold and dim share an off code, which must not undo the new dim
+        let style = Style::new().no_bold().no_dim().dim();
+        assert_eq!(style.to_ansi_start(), "\x1b[22;2m");
+
+        // Setting a property again replaces its negation and vice versa
+        let style = Style::new().no_italic().italic().clear_fg().fg(Color::RED);
+        assert_eq!(style.to_ansi_start(), "\x1b[3;38;5;1m");
+        let style = Style::new().bold().no_bold();
+        assert_eq!(style.to_ansi_start(), "\x1b[22m");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_underline_style_and_color() {
+        let style = Style::new()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Color::RED);
+        assert_eq!(
+            style.to_ansi_start_with(Capabilities::all()),
+            "\x1b[4:3;58;5;1m"
+        );
+        // Without support it degrades to a plain underline
+        assert_eq!(style.to_ansi_start_with(Capabilities::default()), "\x1b[4m");
+
+        let style = Style::new()
+            .underline()
+            .underline_style(UnderlineStyle::Double);
+        assert_eq!(style.attributes, vec![Attribute::Underline]);
+        let style = style.no_underline().clear_underline_color();
+        assert_eq!(style.to_ansi_start_with(Capabilities::all()), "\x1b[24;59m");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_extra_attributes() {
+        let style = Style::new().overline().framed().superscript();
+        assert_eq!(
+            style.to_ansi_start_with(Capabilities::all()),
+            "\x1b[53;51;73m"
+        );
+        assert_eq!(
+            style
+                .clone()
+                .no_attr(Attribute::Framed)
+                .to_ansi_start_with(Capabilities::all()),
+            "\x1b[54;53;73m"
+        );
+        // Unsupported attributes are dropped instead of sent to the terminal
+        assert_eq!(style.to_ansi_start_with(Capabilities::default()), "");
+        assert_eq!(
+            Style::new()
+                .bold()
+                .encircled()
+                .to_ansi_start_with(Capabilities::default()),
+            "\x1b[1m"
+        );
+    }
+
     #[test]
     fn test_reusable_style() {
         let error_style = Console::new("").red().bold();
@@ -929,4 +1819,97 @@ mod tests {
 
         insta::assert_yaml_snapshot!(messages);
     }
+
+    #[test]
+    fn test_style_accessors() {
+        let mut console = Console::new("done").green();
+        assert_eq!(console.text(), "done");
+        assert_eq!(console.style().foreground, Some(Color::GREEN));
+
+        let bold = console.style().clone().bold();
+        console.set_style(bold);
+        assert!(console.style().attributes.contains(&Attribute::Bold));
+
+        *console.style_mut() = console.style().clone().fg(Color::RED);
+        assert_eq!(console.style().foreground, Some(Color::RED));
+
+        let plain = console.with_style(Style::new());
+        assert_eq!(plain.text(), "done");
+        assert_eq!(plain.to_string(), "done");
+    }
+
+    #[test]
+    fn test_standard_traits() {
+        use std::collections::HashSet;
+
+        assert_eq!(Console::from("a"), Console::new("a"));
+        assert_eq!(Console::from(String::from("a")), Console::new("a"));
+        assert_ne!(Console::new("a").red(), Console::new("a"));
+        assert_eq!(Console::default(), Console::new(""));
+        assert_eq!(Style::new().bold(), Style::new().bold());
+
+        let set: HashSet<Console> = [Console::new("x").red(), Console::new("x").red()].into();
+        assert_eq!(set.len(), 1);
+
+        fn takes(console: impl Into<Console>) -> Console {
+            console.into()
+        }
+        assert_eq!(takes("hi").text(), "hi");
+        assert!(format!("{:?}", Console::new("x").red()).contains("foreground"));
+    }
+
+    #[test]
+    fn test_style_equality_ignores_attribute_order() {
+        use std::collections::HashSet;
+
+        let bold_italic = Style::new().bold().italic();
+        assert_eq!(bold_italic, Style::new().italic().bold());
+        assert_eq!(Style::new().bold().bold(), Style::new().bold());
+        assert_ne!(bold_italic, Style::new().bold());
+        assert_ne!(Style::new().bold(), Style::new().no_bold());
+
+        let set: HashSet<Style> = [bold_italic, Style::new().italic().bold().italic()].into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_presets() {
+        let error = Console::error(format_args!("could not read {}", "a.toml"));
+        assert_eq!(error.text(), "✗ could not read a.toml");
+        assert_eq!(error.style(), &theme().labeled_style(Tone::Error));
+        assert_eq!(Console::success("done").text(), "✓ done");
+        assert_eq!(
+            Console::hint("try --force").style(),
+            &theme().labeled_style(Tone::Hint)
+        );
+    }
+
+    #[test]
+    fn test_repeat() {
+        assert_eq!(Console::repeat('=', 3).text(), "===");
+        assert_eq!(Console::repeat('─', 0).text(), "");
+        assert_eq!((Console::new("ab").red() * 2).text(), "abab");
+        assert_eq!(
+            (Console::new("-").bold() * 3).style(),
+            Console::new("").bold().style()
+        );
+        assert!(Console::fill_width('─').width() >= 1);
+    }
+
+    #[test]
+    fn test_display_honors_format_flags() {
+        let plain = Console::new("ok");
+        assert_eq!(format!("[{:>5}]", plain), "[   ok]");
+        assert_eq!(format!("[{:-^6}]", plain), "[--ok--]");
+        assert_eq!(format!("[{:4}]", plain), "[ok  ]");
+        assert_eq!(format!("[{:.1}]", plain), "[o]");
+        assert_eq!(format!("[{:5.1}]", plain), "[o    ]");
+        assert_eq!(format!("[{:1}]", plain), "[ok]");
+
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(
+            format!("[{:>4.1}]", Console::new("ok").red()),
+            "[   \x1b[38;5;1mo\x1b[0m]"
+        );
+    }
 }
@@ -0,0 +1,269 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::cleanup::{CleanupGuard, register_sequence};
+
+// A block of lines at the bottom of the terminal that can be redrawn in place
+pub struct LiveRegion<W: Write> {
+    out: W,
+    // Rows on screen, counting the ones long lines wrap onto
+    height: usize,
+    // The terminal's width, when known
+    columns: Option<usize>,
+    // What's on screen, so it can be put back after `suspend`
+    lines: Vec<String>,
+    // Shows the cursor again if the process is interrupted while it's hidden
+    hidden_cursor: Option<CleanupGuard>,
+}
+
+impl<W: Write> LiveRegion<W> {
+    pub fn new(out: W) -> Self {
+        LiveRegion {
+            out,
+            height: 0,
+            columns: None,
+            lines: Vec::new(),
+            hidden_cursor: None,
+        }
+    }
+
+    // Counts lines wider than `columns` as the rows they wrap onto, so
+    // redraws erase all of them. Without it every line is taken to fit.
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    pub fn draw<I>(&mut self, lines: I) -> io::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let lines: Vec<String> = lines.into_iter().map(|line| line.to_string()).collect();
+        self.erase()?;
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                writeln!(self.out)?;
+            }
+            write!(self.out, "{}", line)?;
+            self.height += self.rows(line);
+        }
+        self.lines = lines;
+        self.out.flush()
+    }
+
+    // Takes the region off screen while `f` runs, e.g. to spawn `git` or a
+    // pager that writes to the terminal itself, then draws it again below
+    // whatever `f` printed
+    pub fn suspend<F, R>(&mut self, f: F) -> io::Result<R>
+    where
+        F: FnOnce() -> R,
+    {
+        let lines = std::mem::take(&mut self.lines);
+        self.clear()?;
+        let result = f();
+        self.draw(lines)?;
+        Ok(result)
+    }
+
+    // Prints `line` above the region for good, e.g. a log message, and
+    // draws the region again below it
+    pub fn println<T: Display>(&mut self, line: T) -> io::Result<()> {
+        let lines = std::mem::take(&mut self.lines);
+        self.erase()?;
+        let line = line.to_string();
+        // Colors left on mustn't leak into the region
+        let reset = if line.contains('\x1b') { "\x1b[0m" } else { "" };
+        writeln!(self.out, "{}{}", line, reset)?;
+        if lines.is_empty() {
+            return self.out.flush();
+        }
+        self.draw(lines)
+    }
+
+    // Removes the drawn lines, leaving the cursor where the region started
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.erase()?;
+        self.out.flush()
+    }
+
+    // Leaves the drawn lines on screen and moves below them
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.height > 0 {
+            writeln!(self.out)?;
+            self.height = 0;
+        }
+        self.lines.clear();
+        self.out.flush()
+    }
+
+    // Terminals rewrap what's on screen when they get narrower, so a line
+    // may now take several rows. Counts them at the new width so the next
+    // draw erases all of them.
+    pub fn resize(&mut self, columns: usize) {
+        self.columns = Some(columns);
+        if self.height == 0 {
+            return;
+        }
+        self.height = self.lines.iter().map(|line| self.rows(line)).sum();
+    }
+
+    // Rows `line` takes on screen
+    fn rows(&self, line: &str) -> usize {
+        match self.columns {
+            Some(columns) if columns > 0 => line
+                .split('\n')
+                .map(|row| crate::parse_ansi(row).width().div_ceil(columns).max(1))
+                .sum(),
+            _ => line.split('\n').count(),
+        }
+    }
+
+    pub fn hide_cursor(&mut self) -> io::Result<()> {
+        write!(self.out, "\x1b[?25l")?;
+        self.hidden_cursor
+            .get_or_insert_with(|| register_sequence("\x1b[?25h"));
+        self.out.flush()
+    }
+
+    pub fn show_cursor(&mut self) -> io::Result<()> {
+        write!(self.out, "\x1b[?25h")?;
+        self.hidden_cursor = None;
+        self.out.flush()
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn erase(&mut self) -> io::Result<()> {
+        self.lines.clear();
+        if self.height == 0 {
+            return Ok(());
+        }
+        write!(self.out, "\r\x1b[2K")?;
+        for _ in 1..self.height {
+            write!(self.out, "\x1b[1A\x1b[2K")?;
+        }
+        self.height = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, VirtualTerminal};
+
+    #[test]
+    fn test_first_draw_writes_lines() {
+        let mut region = LiveRegion::new(Vec::new());
+        region.draw(["one", "two"]).unwrap();
+
+        assert_eq!(region.height(), 2);
+        assert_eq!(region.into_inner(), b"one\ntwo");
+    }
+
+    #[test]
+    fn test_redraw_erases_previous_lines() {
+        let mut region = LiveRegion::new(Vec::new());
+        region.draw(["one", "two", "three"]).unwrap();
+        region.draw(["four"]).unwrap();
+
+        let output = String::from_utf8(region.into_inner()).unwrap();
+        assert_eq!(
+            output,
+            "one\ntwo\nthree\r\x1b[2K\x1b[1A\x1b[2K\x1b[1A\x1b[2Kfour"
+        );
+    }
+
+    #[test]
+    fn test_clear_and_finish() {
+        let mut region = LiveRegion::new(Vec::new());
+        region.draw(["done"]).unwrap();
+        region.finish().unwrap();
+        assert_eq!(region.height(), 0);
+
+        // Finished lines are never erased again
+        region.clear().unwrap();
+        assert_eq!(region.into_inner(), b"done\n");
+    }
+
+    #[test]
+    fn test_suspend_redraws_below_output() {
+        let out = CaptureWriter::new();
+        let mut region = LiveRegion::new(out.clone());
+        region.draw(["one", "two"]).unwrap();
+
+        let result = region
+            .suspend(|| {
+                // Whatever runs here sees a clean screen
+                let mut other = out.clone();
+                writeln!(other, "log").unwrap();
+                42
+            })
+            .unwrap();
+        assert_eq!(result, 42);
+        assert_eq!(region.height(), 2);
+        assert_eq!(
+            out.contents(),
+            "one\ntwo\r\x1b[2K\x1b[1A\x1b[2Klog\none\ntwo"
+        );
+
+        // A finished region has nothing to put back
+        region.finish().unwrap();
+        region.suspend(|| ()).unwrap();
+        assert_eq!(region.height(), 0);
+    }
+
+    #[test]
+    fn test_println_goes_above() {
+        let mut region = LiveRegion::new(Vec::new());
+        region.println("first").unwrap();
+        region.draw(["bar"]).unwrap();
+        region.println("\x1b[31mlog").unwrap();
+        assert_eq!(region.height(), 1);
+        assert_eq!(
+            String::from_utf8(region.into_inner()).unwrap(),
+            "first\nbar\r\x1b[2K\x1b[31mlog\x1b[0m\nbar"
+        );
+    }
+
+    #[test]
+    fn test_resize_counts_rewrapped_rows() {
+        let mut region = LiveRegion::new(Vec::new());
+        region.draw(["\x1b[36m0123456789\x1b[0m", "abc"]).unwrap();
+        region.resize(4);
+        assert_eq!(region.height(), 3 + 1);
+        region.resize(10);
+        assert_eq!(region.height(), 2);
+
+        region.draw(["x"]).unwrap();
+        let output = String::from_utf8(region.into_inner()).unwrap();
+        assert!(
+            output.ends_with("abc\r\x1b[2K\x1b[1A\x1b[2Kx"),
+            "{output:?}"
+        );
+    }
+
+    #[test]
+    fn test_redraw_erases_wrapped_rows() {
+        let terminal = VirtualTerminal::new(10, 6);
+        let mut region = LiveRegion::new(terminal.clone()).columns(10);
+        writeln!(terminal.clone(), "above").unwrap();
+        region
+            .draw([
+                "\x1b[1mheader\x1b[0m",
+                "a line that wraps twice",
+                "exactly 10",
+            ])
+            .unwrap();
+        assert_eq!(region.height(), 1 + 3 + 1);
+        region.draw(["short"]).unwrap();
+        assert_eq!(terminal.text(), "above\nshort");
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::Console;
+use crate::group;
+use crate::theme::{Tone, theme};
+use crate::unicode;
+
+// Prints a success message to stdout, like `println!`
+#[macro_export]
+macro_rules! success {
+    ($($arg:tt)*) => {
+        $crate::print_message($crate::Tone::Success, format_args!($($arg)*))
+    };
+}
+
+// Prints an info message to stdout, like `println!`
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::print_message($crate::Tone::Info, format_args!($($arg)*))
+    };
+}
+
+// Prints a warning to stderr, like `eprintln!`
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        $crate::print_message($crate::Tone::Warning, format_args!($($arg)*))
+    };
+}
+
+// Prints an error to stderr, like `eprintln!`
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => {
+        $crate::print_message($crate::Tone::Error, format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+pub fn print_message(tone: Tone, message: fmt::Arguments) {
+    let line = message_line(tone, &message.to_string());
+    let line = format!("{}\n", line);
+    crate::ignore_broken_pipe(match tone {
+        Tone::Warning | Tone::Error => group::write_stderr(&line),
+        _ => group::write_stdout(&line),
+    });
+}
+
+// `✓ success: message`, with the icon and label in the tone's style and
+// later lines of the message indented to line up with the first
+pub(crate) fn message_line(tone: Tone, message: &str) -> String {
+    let theme = theme();
+    let prefix = format!("{} {}:", theme.icon(tone), tone.label().to_lowercase());
+    let indent = " ".repeat(unicode::width(&prefix) + 1);
+    let mut lines = message.lines();
+    let mut output = format!(
+        "{} {}",
+        Console::new_with_style(prefix, theme.labeled_style(tone).bold()),
+        lines.next().unwrap_or("")
+    );
+    for line in lines {
+        output.push('\n');
+        output.push_str(&indent);
+        output.push_str(line);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DrawTarget;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_message_line() {
+        assert_eq!(
+            strip_ansi(&message_line(Tone::Warning, "disk almost full")),
+            "⚠ warning: disk almost full"
+        );
+        assert_eq!(
+            strip_ansi(&message_line(Tone::Error, "build failed\nsee log")),
+            "✗ error: build failed\n         see log"
+        );
+        assert_eq!(strip_ansi(&message_line(Tone::Info, "")), "ℹ info: ");
+    }
+
+    #[test]
+    fn test_macros_format_arguments() {
+        let _lock = crate::group::test_lock();
+        let (stdout, stderr) = (DrawTarget::buffer(), DrawTarget::buffer());
+        crate::set_stdout_target(stdout.clone());
+        crate::set_stderr_target(stderr.clone());
+        let count = 3;
+        crate::success!("built {count} crates");
+        crate::info!("{} cached", 2);
+        crate::warn!("{:>5}", "slow");
+        crate::error!("failed");
+        crate::set_stdout_target(DrawTarget::stdout());
+        crate::set_stderr_target(DrawTarget::stderr());
+
+        // Other tests may print while the buffers are in place
+        let (stdout, stderr) = (
+            strip_ansi(&stdout.contents()),
+            strip_ansi(&stderr.contents()),
+        );
+        assert!(stdout.contains("✓ success: built 3 crates\n"), "{stdout:?}");
+        assert!(stdout.contains("ℹ info: 2 cached\n"), "{stdout:?}");
+        assert!(stderr.contains("⚠ warning:  slow\n"), "{stderr:?}");
+        assert!(stderr.contains("✗ error: failed\n"), "{stderr:?}");
+        assert!(!stdout.contains("failed") && !stderr.contains("cached"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_message_prefix_uses_theme_style() {
+        assert!(
+            message_line(Tone::Success, "done")
+                .starts_with(&Console::new("✓ success:").green().bold().to_string())
+        );
+    }
+}
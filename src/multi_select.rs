@@ -0,0 +1,367 @@
+use std::io::{self, Write};
+
+use crate::draw_target::stderr_target;
+use crate::events::{self, Event, Key, MouseCapture, RawMode};
+use crate::live::LiveRegion;
+use crate::select::{mouse_key, page_range};
+use crate::symbols::symbols;
+use crate::terminal::query_cursor_row;
+use crate::transcript::finish_prompt;
+use crate::{Color, Console, Style};
+
+#[derive(Debug, Clone)]
+pub struct MultiSelect {
+    prompt: String,
+    items: Vec<String>,
+    checked: Vec<bool>,
+    max_length: Option<usize>,
+    highlight: Style,
+    checked_style: Style,
+    transcript: bool,
+    mouse: bool,
+}
+
+impl MultiSelect {
+    pub fn new<T: Into<String>>(prompt: T) -> Self {
+        MultiSelect {
+            prompt: prompt.into(),
+            items: Vec::new(),
+            checked: Vec::new(),
+            max_length: None,
+            highlight: Style::new().fg(Color::CYAN).bold(),
+            checked_style: Style::new().fg(Color::GREEN),
+            transcript: true,
+            mouse: false,
+        }
+    }
+
+    pub fn items<T: ToString>(mut self, items: &[T]) -> Self {
+        for item in items {
+            self = self.item_checked(item.to_string(), false);
+        }
+        self
+    }
+
+    pub fn item<T: Into<String>>(self, item: T) -> Self {
+        self.item_checked(item, false)
+    }
+
+    pub fn item_checked<T: Into<String>>(mut self, item: T, checked: bool) -> Self {
+        self.items.push(item.into());
+        self.checked.push(checked);
+        self
+    }
+
+    // Pre-checks items; missing entries are left unchecked
+    pub fn defaults(mut self, checked: &[bool]) -> Self {
+        for (slot, &value) in self.checked.iter_mut().zip(checked) {
+            *slot = value;
+        }
+        self
+    }
+
+    // Shows at most `max_length` items at a time, paging through the rest
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn highlight(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    pub fn checked_style(mut self, style: Style) -> Self {
+        self.checked_style = style;
+        self
+    }
+
+    // On by default: once answered, the prompt collapses to a single
+    // `? Prompt · first, second` line
+    pub fn transcript(mut self, enabled: bool) -> Self {
+        self.transcript = enabled;
+        self
+    }
+
+    // Clicking an item toggles it and the scroll wheel moves the cursor;
+    // off by default, since it stops the terminal selecting text
+    pub fn mouse(mut self, enabled: bool) -> Self {
+        self.mouse = enabled;
+        self
+    }
+
+    // Returns the checked indices in order, or `None` if the user pressed escape
+    pub fn interact(&self) -> io::Result<Option<Vec<usize>>> {
+        let _raw = RawMode::enable()?;
+        let _mouse = self.mouse.then(MouseCapture::enable).transpose()?;
+        let mut region =
+            LiveRegion::new(stderr_target()).columns(crate::terminal::terminal_width());
+        region.hide_cursor()?;
+        let result = if self.mouse {
+            self.interact_on(&mut region, events::read_event, query_cursor_row)
+        } else {
+            self.interact_on(&mut region, || events::read_key().map(Event::Key), || None)
+        };
+        region.show_cursor()?;
+        result
+    }
+
+    fn interact_on<W, F, L>(
+        &self,
+        region: &mut LiveRegion<W>,
+        mut read_event: F,
+        locate: L,
+    ) -> io::Result<Option<Vec<usize>>>
+    where
+        W: Write,
+        F: FnMut() -> io::Result<Event>,
+        L: FnOnce() -> Option<usize>,
+    {
+        if self.items.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "multi-select prompt has no items",
+            ));
+        }
+
+        let len = self.items.len();
+        let page = self.max_length.filter(|&size| size > 0).unwrap_or(len);
+        let mut cursor = 0;
+        let mut checked = self.checked.clone();
+        let mut locate = Some(locate);
+        let mut top = None;
+
+        loop {
+            let lines = self.render(cursor, &checked);
+            let height = lines.len();
+            region.draw(lines)?;
+            if let Some(locate) = locate.take() {
+                top = locate().and_then(|row| row.checked_sub(height - 1));
+            }
+            let key = match read_event()? {
+                Event::Key(key) => key,
+                Event::Mouse(mouse) => {
+                    let shown = page_range(cursor, len, self.max_length);
+                    mouse_key(mouse, top, shown, &mut cursor, Key::Char(' '))
+                }
+                _ => continue,
+            };
+            match key {
+                Key::Up | Key::Char('k') => cursor = (cursor + len - 1) % len,
+                Key::Down | Key::Char('j') | Key::Tab => cursor = (cursor + 1) % len,
+                Key::Left => cursor = cursor.saturating_sub(page),
+                Key::Right => cursor = (cursor + page).min(len - 1),
+                Key::Char(' ') => checked[cursor] = !checked[cursor],
+                Key::Char('a') => {
+                    let all = checked.iter().all(|&c| c);
+                    checked.iter_mut().for_each(|c| *c = !all);
+                }
+                Key::Enter => {
+                    let chosen: Vec<usize> = (0..len).filter(|&i| checked[i]).collect();
+                    let answer: Vec<&str> =
+                        chosen.iter().map(|&i| self.items[i].as_str()).collect();
+                    finish_prompt(region, self.transcript, &self.prompt, &answer.join(", "))?;
+                    return Ok(Some(chosen));
+                }
+                Key::Escape | Key::Char('q') => {
+                    region.clear()?;
+                    return Ok(None);
+                }
+                Key::CtrlC => {
+                    region.clear()?;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&self, cursor: usize, checked: &[bool]) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{} {} {}",
+            Console::new("?").green().bold(),
+            Console::new(self.prompt.as_str()).bold(),
+            Console::new("(space to toggle, a to toggle all)").dim()
+        )];
+
+        let (start, end) = page_range(cursor, self.items.len(), self.max_length);
+        let visible = self.items.iter().zip(checked).enumerate();
+        for (i, (item, &is_checked)) in visible.take(end).skip(start) {
+            let marker = if is_checked {
+                Console::new_with_style(symbols().checked, self.checked_style.clone())
+            } else {
+                Console::new(symbols().unchecked).dim()
+            };
+            if i == cursor {
+                lines.push(format!(
+                    "{} {} {}",
+                    Console::new_with_style(symbols().pointer, self.highlight.clone()),
+                    marker,
+                    Console::new_with_style(item.as_str(), self.highlight.clone())
+                ));
+            } else {
+                lines.push(format!("  {} {}", marker, item));
+            }
+        }
+
+        if let Some(size) = self.max_length.filter(|_| end - start < self.items.len()) {
+            let pages = self.items.len().div_ceil(size);
+            lines.push(format!(
+                "  {}",
+                Console::new(format!(
+                    "page {}/{} (←/→ to change page)",
+                    cursor / size + 1,
+                    pages
+                ))
+                .dim()
+            ));
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(select: &MultiSelect, keys: &[Key]) -> io::Result<Option<Vec<usize>>> {
+        let events: Vec<Event> = keys.iter().copied().map(Event::Key).collect();
+        run_events(select, &events)
+    }
+
+    // Drawn with its last line on screen row 10
+    fn run_events(select: &MultiSelect, events: &[Event]) -> io::Result<Option<Vec<usize>>> {
+        let mut events = events.iter().cloned();
+        let mut region = LiveRegion::new(Vec::new());
+        select.interact_on(
+            &mut region,
+            || Ok(events.next().expect("ran out of events")),
+            || Some(10),
+        )
+    }
+
+    #[test]
+    fn test_multi_select_toggle() {
+        let select = MultiSelect::new("Features").items(&["tls", "gzip", "http2"]);
+
+        assert_eq!(run(&select, &[Key::Enter]).unwrap(), Some(vec![]));
+        assert_eq!(
+            run(
+                &select,
+                &[
+                    Key::Char(' '),
+                    Key::Down,
+                    Key::Down,
+                    Key::Char(' '),
+                    Key::Enter
+                ]
+            )
+            .unwrap(),
+            Some(vec![0, 2])
+        );
+        // Toggling twice unchecks
+        assert_eq!(
+            run(&select, &[Key::Char(' '), Key::Char(' '), Key::Enter]).unwrap(),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_multi_select_toggle_all() {
+        let select = MultiSelect::new("Features")
+            .items(&["tls", "gzip", "http2"])
+            .defaults(&[true]);
+
+        assert_eq!(
+            run(&select, &[Key::Char('a'), Key::Enter]).unwrap(),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(
+            run(&select, &[Key::Char('a'), Key::Char('a'), Key::Enter]).unwrap(),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_multi_select_cancel() {
+        let select = MultiSelect::new("Features").item_checked("tls", true);
+
+        assert_eq!(run(&select, &[Key::Enter]).unwrap(), Some(vec![0]));
+        assert_eq!(run(&select, &[Key::Escape]).unwrap(), None);
+        assert_eq!(
+            run(&select, &[Key::CtrlC]).unwrap_err().kind(),
+            io::ErrorKind::Interrupted
+        );
+    }
+
+    #[test]
+    fn test_multi_select_paging() {
+        let items: Vec<String> = (0..10).map(|i| format!("item {}", i)).collect();
+        let select = MultiSelect::new("Pick").items(&items).max_length(4);
+
+        assert_eq!(
+            run(&select, &[Key::Right, Key::Char(' '), Key::Enter]).unwrap(),
+            Some(vec![4])
+        );
+        assert_eq!(
+            run(
+                &select,
+                &[
+                    Key::Right,
+                    Key::Right,
+                    Key::Right,
+                    Key::Char(' '),
+                    Key::Enter
+                ]
+            )
+            .unwrap(),
+            Some(vec![9])
+        );
+
+        // Only the current page is rendered, plus the page indicator
+        assert_eq!(select.render(5, &[false; 10]).len(), 1 + 4 + 1);
+        assert_eq!(select.render(9, &[false; 10]).len(), 1 + 2 + 1);
+    }
+
+    #[test]
+    fn test_multi_select_mouse() {
+        use crate::events::{MouseButton, MouseEvent, MouseKind};
+
+        // Header on row 5, four items on rows 6 to 9, page indicator on 10
+        let items: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let select = MultiSelect::new("Pick").items(&items).max_length(4);
+        let click = |row| {
+            Event::Mouse(MouseEvent {
+                kind: MouseKind::Press(MouseButton::Left),
+                column: 2,
+                row,
+            })
+        };
+        assert_eq!(
+            run_events(
+                &select,
+                &[click(6), click(8), click(10), Event::Key(Key::Enter)]
+            )
+            .unwrap(),
+            Some(vec![0, 2])
+        );
+        // Clicks land on the page being shown
+        assert_eq!(
+            run_events(
+                &select,
+                &[Event::Key(Key::Right), click(7), Event::Key(Key::Enter)]
+            )
+            .unwrap(),
+            Some(vec![5])
+        );
+    }
+
+    #[test]
+    fn test_multi_select_render() {
+        let select = MultiSelect::new("Features")
+            .item_checked("tls", true)
+            .item("gzip");
+        insta::assert_yaml_snapshot!(select.render(1, &[true, false]));
+    }
+}
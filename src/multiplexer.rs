@@ -0,0 +1,202 @@
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::{Color, Console};
+
+// Tag colors, skipping red so a tag never looks like an error
+const PALETTE: [Color; 10] = [
+    Color::CYAN,
+    Color::YELLOW,
+    Color::GREEN,
+    Color::MAGENTA,
+    Color::BLUE,
+    Color::BRIGHT_CYAN,
+    Color::BRIGHT_YELLOW,
+    Color::BRIGHT_GREEN,
+    Color::BRIGHT_MAGENTA,
+    Color::BRIGHT_BLUE,
+];
+
+struct Shared<W> {
+    out: W,
+    // Width of the longest name, so every tag lines up
+    width: usize,
+}
+
+// Interleaves the output of several processes or threads into one writer,
+// a whole line at a time, each tagged with its source's name in a color
+// picked from the name, like `docker compose up`:
+//
+//   web    | listening on :8080
+//   worker | picked up job 41
+pub struct Multiplexer<W: Write> {
+    shared: Arc<Mutex<Shared<W>>>,
+}
+
+// Clones share the writer and the tag width
+impl<W: Write> Clone for Multiplexer<W> {
+    fn clone(&self) -> Self {
+        Multiplexer {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<W: Write> Multiplexer<W> {
+    pub fn new(out: W) -> Self {
+        Multiplexer {
+            shared: Arc::new(Mutex::new(Shared { out, width: 0 })),
+        }
+    }
+
+    // A writer whose lines come out tagged with `name`. Partial lines are
+    // held back until they're complete so sources never split each other's
+    // lines; whatever is left when it's dropped comes out then.
+    pub fn stream<N: Into<String>>(&self, name: N) -> MultiplexStream<W> {
+        let name = name.into();
+        let width = crate::unicode::width(&name);
+        let mut shared = self.lock();
+        shared.width = shared.width.max(width);
+        MultiplexStream {
+            shared: Arc::clone(&self.shared),
+            tag: Console::new(name.as_str()).fg(tag_color(&name)).to_string(),
+            width,
+            line: Vec::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shared<W>> {
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<W: Write + Send + 'static> Multiplexer<W> {
+    // Copies `reader`, e.g. a child process's piped stdout, into a stream
+    // named `name` on a thread of its own until it ends
+    pub fn pipe<N, R>(&self, name: N, mut reader: R) -> JoinHandle<io::Result<()>>
+    where
+        N: Into<String>,
+        R: Read + Send + 'static,
+    {
+        let mut stream = self.stream(name);
+        thread::spawn(move || {
+            io::copy(&mut reader, &mut stream)?;
+            stream.finish()
+        })
+    }
+}
+
+pub struct MultiplexStream<W: Write> {
+    shared: Arc<Mutex<Shared<W>>>,
+    tag: String,
+    width: usize,
+    line: Vec<u8>,
+}
+
+impl<W: Write> MultiplexStream<W> {
+    // Writes out a final line that has no newline
+    fn finish(&mut self) -> io::Result<()> {
+        if self.line.is_empty() {
+            return Ok(());
+        }
+        let line = std::mem::take(&mut self.line);
+        self.write_line(&line)
+    }
+
+    fn write_line(&self, line: &[u8]) -> io::Result<()> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        let padding = " ".repeat(shared.width - self.width);
+        let mut out = format!("{}{} {} ", self.tag, padding, Console::new("|").dim()).into_bytes();
+        out.extend_from_slice(line);
+        // Colors left on by the source mustn't leak into the next tag
+        if line.contains(&0x1b) {
+            out.extend_from_slice(b"\x1b[0m");
+        }
+        out.push(b'\n');
+        shared.out.write_all(&out)?;
+        shared.out.flush()
+    }
+}
+
+impl<W: Write> Write for MultiplexStream<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(buf);
+        while let Some(end) = self.line.iter().position(|&b| b == b'\n') {
+            let rest = self.line.split_off(end + 1);
+            let line = std::mem::replace(&mut self.line, rest);
+            self.write_line(&line[..end])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        shared.out.flush()
+    }
+}
+
+impl<W: Write> Drop for MultiplexStream<W> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+// FNV-1a, so a name keeps its color from one run to the next
+fn tag_color(name: &str) -> Color {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    PALETTE[(hash % PALETTE.len() as u64) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+
+    #[test]
+    fn test_lines_are_tagged_and_atomic() {
+        let out = CaptureWriter::new();
+        let mux = Multiplexer::new(out.clone());
+        let mut web = mux.stream("web");
+        let mut worker = mux.stream("worker");
+
+        web.write_all(b"listening").unwrap();
+        worker.write_all(b"picked up job 41\r\nstarting").unwrap();
+        web.write_all(b" on :8080\n").unwrap();
+        drop(worker);
+
+        assert_eq!(
+            strip_ansi(&out.contents()),
+            "worker | picked up job 41\nweb    | listening on :8080\nworker | starting\n"
+        );
+    }
+
+    #[test]
+    fn test_source_colors_are_reset() {
+        let out = CaptureWriter::new();
+        let mux = Multiplexer::new(out.clone());
+        let mut web = mux.stream("web");
+        web.write_all(b"\x1b[31mpanic\n").unwrap();
+        assert!(out.contents().ends_with("\x1b[31mpanic\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_pipe() {
+        let out = CaptureWriter::new();
+        let mux = Multiplexer::new(out.clone());
+        mux.pipe("db", &b"ready\nno newline"[..])
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(strip_ansi(&out.contents()), "db | ready\ndb | no newline\n");
+    }
+
+    #[test]
+    fn test_tag_color_is_stable() {
+        assert_eq!(tag_color("web"), tag_color("web"));
+        assert!(PALETTE.contains(&tag_color("worker")));
+    }
+}
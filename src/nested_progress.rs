@@ -0,0 +1,317 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use crate::draw_target::{DrawTarget, stderr_target};
+use crate::progress::bar;
+use crate::theme::{Tone, theme};
+use crate::throttle::Throttle;
+use crate::{Console, LiveRegion, Style, StyledText};
+
+// Steps the overall bar is measured in, so fractions of children add up
+// without rounding each one
+const RESOLUTION: u64 = 10_000;
+
+// A parent bar over child bars, e.g. a whole build over each crate being
+// compiled, redrawn in place. The parent fills with its children's
+// progress, each counting in proportion to its weight. Finished children
+// collapse into a single line so only work in progress takes up rows.
+//
+//   Building ███████████─────────── 48%
+//   ✓ 3 done
+//     syn ██████████████──────── 120/180
+//     serde ██████────────────── 31/104
+#[derive(Clone)]
+pub struct NestedProgress {
+    state: Arc<Mutex<NestedState>>,
+}
+
+struct NestedState {
+    // `None` when hidden
+    region: Option<LiveRegion<Box<dyn Write + Send>>>,
+    label: String,
+    children: Vec<Child>,
+    width: usize,
+    throttle: Throttle,
+    finished: bool,
+    columns: Option<usize>,
+}
+
+struct Child {
+    label: String,
+    len: u64,
+    pos: u64,
+    weight: u64,
+    finished: bool,
+}
+
+impl Child {
+    fn fraction(&self) -> f64 {
+        if self.finished {
+            1.0
+        } else if self.len == 0 {
+            0.0
+        } else {
+            self.pos.min(self.len) as f64 / self.len as f64
+        }
+    }
+}
+
+impl NestedProgress {
+    // Draws on stderr when it's a terminal and stays hidden otherwise
+    pub fn new<T: Into<String>>(label: T) -> Self {
+        Self::with_target(stderr_target(), label)
+    }
+
+    // Draws on `target` if live components draw there, and stays hidden
+    // otherwise
+    pub fn with_target<T: Into<String>>(target: DrawTarget, label: T) -> Self {
+        if !target.is_live() {
+            return Self::hidden(label);
+        }
+        let terminal = target.is_terminal();
+        let progress = Self::with_writer(target, label);
+        if terminal {
+            progress.lock().columns = Some(crate::terminal::terminal_width());
+        }
+        progress
+    }
+
+    pub fn with_writer<W: Write + Send + 'static, T: Into<String>>(out: W, label: T) -> Self {
+        Self::with_region(Some(LiveRegion::new(Box::new(out))), label.into())
+    }
+
+    // Tracks progress without drawing anything
+    pub fn hidden<T: Into<String>>(label: T) -> Self {
+        Self::with_region(None, label.into())
+    }
+
+    fn with_region(region: Option<LiveRegion<Box<dyn Write + Send>>>, label: String) -> Self {
+        NestedProgress {
+            state: Arc::new(Mutex::new(NestedState {
+                region,
+                label,
+                children: Vec::new(),
+                width: 30,
+                throttle: Throttle::default(),
+                finished: false,
+                columns: None,
+            })),
+        }
+    }
+
+    // Columns taken by each bar
+    pub fn width(self, width: usize) -> Self {
+        self.lock().width = width;
+        self
+    }
+
+    // Adds a child bar of `len` steps. Its share of the parent is `weight`
+    // over the total weight of all children, e.g. a crate's line count.
+    pub fn child<T: Into<String>>(&self, label: T, len: u64, weight: u64) -> ChildProgress {
+        let mut state = self.lock();
+        state.children.push(Child {
+            label: label.into(),
+            len,
+            pos: 0,
+            weight,
+            finished: false,
+        });
+        state.draw(true);
+        ChildProgress {
+            parent: self.clone(),
+            index: state.children.len() - 1,
+        }
+    }
+
+    // How much of the whole job is done, from 0 to 1
+    pub fn fraction(&self) -> f64 {
+        self.lock().fraction()
+    }
+
+    // Collapses every child and leaves the full parent bar on screen
+    pub fn finish(&self) {
+        let mut state = self.lock();
+        for child in &mut state.children {
+            child.finished = true;
+        }
+        state.draw(true);
+        state.finished = true;
+        if let Some(region) = &mut state.region {
+            let _ = region.finish();
+        }
+    }
+
+    pub fn finish_and_clear(&self) {
+        let mut state = self.lock();
+        state.finished = true;
+        if let Some(region) = &mut state.region {
+            let _ = region.clear();
+        }
+    }
+
+    fn update<F: FnOnce(&mut Child)>(&self, index: usize, force: bool, update: F) {
+        let mut state = self.lock();
+        update(&mut state.children[index]);
+        state.draw(force);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, NestedState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// One child of a `NestedProgress`. Clones update the same child.
+#[derive(Clone)]
+pub struct ChildProgress {
+    parent: NestedProgress,
+    index: usize,
+}
+
+impl ChildProgress {
+    pub fn inc(&self, delta: u64) {
+        self.parent.update(self.index, false, |child| {
+            child.pos = child.pos.saturating_add(delta)
+        });
+    }
+
+    pub fn set_position(&self, pos: u64) {
+        self.parent
+            .update(self.index, false, |child| child.pos = pos);
+    }
+
+    pub fn set_length(&self, len: u64) {
+        self.parent
+            .update(self.index, true, |child| child.len = len);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.parent.lock().children[self.index].pos
+    }
+
+    // Counts the child as complete and folds it into the summary line
+    pub fn finish(&self) {
+        self.parent.update(self.index, true, |child| {
+            child.pos = child.pos.max(child.len);
+            child.finished = true;
+        });
+    }
+}
+
+impl NestedState {
+    fn fraction(&self) -> f64 {
+        let total: u64 = self.children.iter().map(|child| child.weight).sum();
+        if total == 0 {
+            let done = !self.children.is_empty() && self.children.iter().all(|c| c.finished);
+            return if done { 1.0 } else { 0.0 };
+        }
+        let done: f64 = self
+            .children
+            .iter()
+            .map(|child| child.fraction() * child.weight as f64)
+            .sum();
+        done / total as f64
+    }
+
+    fn draw(&mut self, force: bool) {
+        if self.finished || self.region.is_none() {
+            return;
+        }
+        let now = Instant::now();
+        if force {
+            self.throttle.drawn(now);
+        } else if !self.throttle.ready(now) {
+            return;
+        }
+        let mut lines = self.render();
+        if let Some(columns) = self.columns {
+            for line in &mut lines {
+                *line = line.take_visible(columns);
+            }
+        }
+        if let Some(region) = &mut self.region {
+            let _ = region.draw(lines);
+        }
+    }
+
+    fn render(&self) -> Vec<StyledText> {
+        let theme = theme();
+        let steps = (self.fraction() * RESOLUTION as f64) as u64;
+        let mut lines = vec![crate::parse_ansi(&format!(
+            "{} {} {}%",
+            Console::new_with_style(self.label.as_str(), Style::new().bold()),
+            bar(steps, RESOLUTION, self.width),
+            steps * 100 / RESOLUTION
+        ))];
+        let done = self.children.iter().filter(|child| child.finished).count();
+        if done > 0 {
+            lines.push(
+                StyledText::new()
+                    .then(Console::new_with_style(
+                        theme.icon(Tone::Success),
+                        theme.style(Tone::Success).clone(),
+                    ))
+                    .then(Console::new_with_style(
+                        format!(" {} done", done),
+                        theme.style(Tone::Muted).clone(),
+                    )),
+            );
+        }
+        for child in self.children.iter().filter(|child| !child.finished) {
+            lines.push(crate::parse_ansi(&format!(
+                "  {} {} {}/{}",
+                child.label,
+                bar(child.pos, child.len, self.width),
+                child.pos,
+                child.len
+            )));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+
+    fn rendered(progress: &NestedProgress) -> Vec<String> {
+        progress
+            .lock()
+            .render()
+            .iter()
+            .map(|line| line.plain())
+            .collect()
+    }
+
+    #[test]
+    fn test_children_are_weighted() {
+        let progress = NestedProgress::hidden("Building").width(10);
+        let small = progress.child("quote", 10, 1);
+        let large = progress.child("syn", 100, 3);
+        small.inc(5);
+        large.set_position(50);
+        assert_eq!(progress.fraction(), (0.5 + 0.5 * 3.0) / 4.0);
+        small.finish();
+        assert_eq!(progress.fraction(), (1.0 + 0.5 * 3.0) / 4.0);
+        assert_eq!(
+            rendered(&progress),
+            [
+                "Building ██████──── 62%",
+                "✓ 1 done",
+                "  syn █████───── 50/100",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finish_collapses_children() {
+        let out = CaptureWriter::new();
+        let progress = NestedProgress::with_writer(out.clone(), "Fetching").width(4);
+        progress.child("a", 2, 1).inc(1);
+        progress.child("b", 0, 0);
+        progress.finish();
+        assert_eq!(progress.fraction(), 1.0);
+        assert!(strip_ansi(&out.contents()).ends_with("Fetching ████ 100%\n✓ 2 done\n"));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::unicode;
+use crate::{Console, StyledText};
+
+// Expands tabs to the next multiple of `tab_width` columns. Columns restart
+// after each newline.
+pub fn expand_tabs(text: &str, tab_width: usize) -> String {
+    expand_tabs_from(text, tab_width, &mut 0)
+}
+
+fn expand_tabs_from(text: &str, tab_width: usize, column: &mut usize) -> String {
+    if !text.contains('\t') {
+        *column = match text.rsplit_once('\n') {
+            Some((_, last)) => unicode::width(last),
+            None => *column + unicode::width(text),
+        };
+        return text.to_string();
+    }
+    let tab_width = tab_width.max(1);
+    let mut expanded = String::with_capacity(text.len());
+    for cluster in unicode::graphemes(text) {
+        match cluster {
+            "\t" => {
+                let spaces = tab_width - *column % tab_width;
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                *column += spaces;
+            }
+            "\n" | "\r\n" => {
+                expanded.push_str(cluster);
+                *column = 0;
+            }
+            _ => {
+                expanded.push_str(cluster);
+                *column += unicode::width(cluster);
+            }
+        }
+    }
+    expanded
+}
+
+// Makes control characters visible instead of letting them move the cursor
+// or vanish: C0 controls and DEL in caret notation (`^M`, `^[`, `^?`), and
+// C1 controls, which have no caret form, as U+FFFD. Newlines and tabs are
+// kept.
+pub fn show_controls(text: &str) -> String {
+    if !text.chars().any(is_hidden_control) {
+        return text.to_string();
+    }
+    let mut shown = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\0'..='\x1f' if is_hidden_control(ch) => {
+                shown.push('^');
+                shown.push((ch as u8 + b'@') as char);
+            }
+            '\x7f' => shown.push_str("^?"),
+            '\u{80}'..='\u{9f}' => shown.push('\u{FFFD}'),
+            _ => shown.push(ch),
+        }
+    }
+    shown
+}
+
+fn is_hidden_control(ch: char) -> bool {
+    ch.is_control() && ch != '\n' && ch != '\t'
+}
+
+impl Console {
+    pub fn expand_tabs(mut self, tab_width: usize) -> Self {
+        self.text = expand_tabs(&self.text, tab_width);
+        self
+    }
+
+    pub fn show_controls(mut self) -> Self {
+        self.text = show_controls(&self.text);
+        self
+    }
+}
+
+impl StyledText {
+    // Tab stops are counted from the start of the line, across segments
+    pub fn expand_tabs(&self, tab_width: usize) -> StyledText {
+        let mut column = 0;
+        self.segments()
+            .iter()
+            .map(|s| s.with_text(expand_tabs_from(&s.text, tab_width, &mut column)))
+            .collect()
+    }
+
+    pub fn show_controls(&self) -> StyledText {
+        self.segments()
+            .iter()
+            .map(|s| s.with_text(show_controls(&s.text)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("a\tbc\td", 4), "a   bc  d");
+        assert_eq!(expand_tabs("\tx\n\ty", 2), "  x\n  y");
+        assert_eq!(expand_tabs("abcd\te", 4), "abcd    e");
+        assert_eq!(expand_tabs("no tabs", 4), "no tabs");
+    }
+
+    #[test]
+    fn test_expand_tabs_across_segments() {
+        let text = StyledText::new()
+            .then(Console::new("ab").red())
+            .then(Console::new("\tc"))
+            .then(Console::new("\n\t"));
+        assert_eq!(text.expand_tabs(4).plain(), "ab  c\n    ");
+        assert_eq!(text.expand_tabs(4).segments().len(), 3);
+    }
+
+    #[test]
+    fn test_show_controls() {
+        assert_eq!(show_controls("line\r\n"), "line^M\n");
+        assert_eq!(show_controls("\x1b[31m\tx\x7f"), "^[[31m\tx^?");
+        assert_eq!(show_controls("\0\u{85}"), "^@\u{FFFD}");
+        assert_eq!(show_controls("plain ✓"), "plain ✓");
+    }
+}
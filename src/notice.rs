@@ -0,0 +1,214 @@
+use std::fmt::{self, Display};
+
+use crate::draw_target::stderr_target;
+use crate::reporter::Verbosity;
+use crate::symbols::symbols;
+use crate::terminal::terminal_width;
+use crate::theme::{Tone, theme};
+use crate::wrap::wrap_words;
+use crate::{Console, unicode};
+
+// A boxed message in the warning color telling the user something about the
+// tool itself, such as a newer version or a deprecated flag, with a command
+// to run about it:
+//
+//   ╭─────────────────────────────────────────────────────╮
+//   │ A new version of mytool is available: 1.2.0 → 1.4.0 │
+//   │ Run cargo install mytool to update                  │
+//   ╰─────────────────────────────────────────────────────╯
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    message: String,
+    command: Option<(String, String)>,
+    verbosity: Verbosity,
+    width: Option<usize>,
+}
+
+impl Notice {
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Notice {
+            message: message.into(),
+            command: None,
+            verbosity: Verbosity::Normal,
+            width: None,
+        }
+    }
+
+    pub fn update<N: Display, C: Display, L: Display>(name: N, current: C, latest: L) -> Self {
+        Self::new(format!(
+            "A new version of {} is available: {} {} {}",
+            name,
+            current,
+            symbols().arrow,
+            latest
+        ))
+    }
+
+    pub fn deprecation<T: Display>(message: T) -> Self {
+        Self::new(format!("Deprecated: {}", message))
+    }
+
+    // A command to suggest, shown as "Run `command` to `purpose`", e.g.
+    // `.command("cargo install mytool", "update")`
+    pub fn command<C: Into<String>, P: Into<String>>(mut self, command: C, purpose: P) -> Self {
+        self.command = Some((command.into(), purpose.into()));
+        self
+    }
+
+    // `print` shows nothing when quiet
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    // Total width including the border, defaults to the terminal width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // Writes the notice to stderr, unless quiet or stderr isn't a terminal,
+    // so it never ends up in logs or in output another program reads. A
+    // stderr target set to a buffer or writer gets it too.
+    pub fn print(&self) {
+        if should_show(stderr_target().is_live(), self.verbosity) {
+            crate::ignore_broken_pipe(crate::group::write_stderr(&self.to_string()));
+        }
+    }
+}
+
+fn should_show(is_terminal: bool, verbosity: Verbosity) -> bool {
+    is_terminal && verbosity > Verbosity::Quiet
+}
+
+impl Display for Notice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let style = theme.style(Tone::Warning).clone();
+        let code = theme.style(Tone::Info).clone().bold();
+        let limit = self.width.unwrap_or_else(terminal_width);
+        let command = self.command.as_ref().map(|(command, purpose)| {
+            (format!("Run {} to {}", command, purpose), command, purpose)
+        });
+        let natural = self
+            .message
+            .lines()
+            .chain(command.iter().map(|(line, _, _)| line.as_str()))
+            .map(unicode::width)
+            .max()
+            .unwrap_or(0);
+        let inner = natural.min(limit.saturating_sub(4)).max(1);
+
+        // Each row is (visible width, rendered text)
+        let mut rows: Vec<(usize, String)> = wrap_words(&self.message, inner)
+            .into_iter()
+            .map(|line| {
+                let width = unicode::width(&line);
+                (
+                    width,
+                    Console::new_with_style(line, style.clone().bold()).to_string(),
+                )
+            })
+            .collect();
+        if let Some((line, command, purpose)) = command {
+            if unicode::width(&line) <= inner {
+                rows.push((
+                    unicode::width(&line),
+                    format!(
+                        "Run {} to {}",
+                        Console::new_with_style(command.as_str(), code),
+                        purpose
+                    ),
+                ));
+            } else {
+                // Too narrow for the sentence: the command alone, which
+                // stays whole so it can still be copied
+                rows.push((
+                    unicode::width(command),
+                    Console::new_with_style(command.as_str(), code).to_string(),
+                ));
+            }
+        }
+
+        let chars = symbols().border;
+        let edge = chars.horizontal.repeat(inner + 2);
+        writeln!(
+            f,
+            "{}",
+            Console::new_with_style(
+                format!("{}{}{}", chars.top_left, edge, chars.top_right),
+                style.clone()
+            )
+        )?;
+        let side = Console::new_with_style(chars.vertical, style.clone());
+        for (width, text) in rows {
+            let padding = " ".repeat(inner.saturating_sub(width));
+            writeln!(f, "{} {}{} {}", side, text, padding, side)?;
+        }
+        writeln!(
+            f,
+            "{}",
+            Console::new_with_style(
+                format!("{}{}{}", chars.bottom_left, edge, chars.bottom_right),
+                style
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_update_notice() {
+        let notice = Notice::update("mytool", "1.2.0", "1.4.0")
+            .command("cargo install mytool", "update")
+            .width(80);
+        assert_eq!(
+            strip_ansi(&notice.to_string()),
+            "╭─────────────────────────────────────────────────────╮\n\
+             │ A new version of mytool is available: 1.2.0 → 1.4.0 │\n\
+             │ Run cargo install mytool to update                  │\n\
+             ╰─────────────────────────────────────────────────────╯\n"
+        );
+        assert!(notice.to_string().contains(&format!(
+            "Run {} to",
+            Console::new("cargo install mytool").cyan().bold()
+        )));
+    }
+
+    #[test]
+    fn test_print_follows_stderr_target() {
+        let _lock = crate::group::test_lock();
+        let buffer = crate::DrawTarget::buffer();
+        crate::set_stderr_target(buffer.clone());
+        Notice::deprecation("--slow is gone").width(30).print();
+        crate::set_stderr_target(crate::DrawTarget::stderr());
+        assert!(strip_ansi(&buffer.contents()).contains("--slow is gone"));
+    }
+
+    #[test]
+    fn test_narrow_notice_wraps() {
+        let notice = Notice::deprecation("--fast is now the default")
+            .command("mytool build", "build")
+            .width(20);
+        assert_eq!(
+            strip_ansi(&notice.to_string()),
+            "╭──────────────────╮\n\
+             │ Deprecated:      │\n\
+             │ --fast is now    │\n\
+             │ the default      │\n\
+             │ mytool build     │\n\
+             ╰──────────────────╯\n"
+        );
+    }
+
+    #[test]
+    fn test_should_show() {
+        assert!(should_show(true, Verbosity::Normal));
+        assert!(!should_show(true, Verbosity::Quiet));
+        assert!(!should_show(false, Verbosity::Verbose));
+    }
+}
@@ -0,0 +1,277 @@
+use std::io::{self, Write};
+
+use crate::draw_target::stderr_target;
+use crate::events::{self, Key, RawMode};
+use crate::live::LiveRegion;
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::transcript::finish_prompt;
+use crate::{Console, unicode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overwrite {
+    Yes,
+    No,
+    All,
+    Quit,
+}
+
+impl Overwrite {
+    fn label(self) -> &'static str {
+        match self {
+            Overwrite::Yes => "yes",
+            Overwrite::No => "no",
+            Overwrite::All => "all",
+            Overwrite::Quit => "quit",
+        }
+    }
+}
+
+// Asks whether to replace each of many targets, answered with a single key:
+// y(es), n(o), a(ll) or q(uit), or enter for the default. Once "all" is
+// chosen, later calls return `All` without asking again.
+#[derive(Debug, Clone)]
+pub struct OverwritePrompt {
+    prompt: String,
+    default: Overwrite,
+    preview_lines: usize,
+    transcript: bool,
+    all: bool,
+}
+
+impl OverwritePrompt {
+    pub fn new() -> Self {
+        OverwritePrompt {
+            prompt: "Overwrite".to_string(),
+            default: Overwrite::No,
+            preview_lines: 5,
+            transcript: true,
+            all: false,
+        }
+    }
+
+    // The verb shown before the target, e.g. "Replace" or "Delete"
+    pub fn prompt<T: Into<String>>(mut self, prompt: T) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    // The answer for enter, `No` unless set
+    pub fn default(mut self, default: Overwrite) -> Self {
+        self.default = default;
+        self
+    }
+
+    // How many lines of a preview to show before eliding the rest
+    pub fn preview_lines(mut self, lines: usize) -> Self {
+        self.preview_lines = lines;
+        self
+    }
+
+    // Whether each answer is left behind as `? Overwrite path · yes`
+    pub fn transcript(mut self, enabled: bool) -> Self {
+        self.transcript = enabled;
+        self
+    }
+
+    // Whether "all" has been chosen
+    pub fn applies_to_all(&self) -> bool {
+        self.all
+    }
+
+    pub fn ask(&mut self, target: &str) -> io::Result<Overwrite> {
+        self.ask_with_preview(target, "")
+    }
+
+    // Shows the start of `preview`, e.g. the new contents or a diff, under
+    // the question
+    pub fn ask_with_preview(&mut self, target: &str, preview: &str) -> io::Result<Overwrite> {
+        if self.all {
+            return Ok(Overwrite::All);
+        }
+        let _raw = RawMode::enable()?;
+        let mut region =
+            LiveRegion::new(stderr_target()).columns(crate::terminal::terminal_width());
+        region.hide_cursor()?;
+        let result = self.interact_on(&mut region, target, preview, events::read_key);
+        region.show_cursor()?;
+        result
+    }
+
+    fn interact_on<W, F>(
+        &mut self,
+        region: &mut LiveRegion<W>,
+        target: &str,
+        preview: &str,
+        mut read_key: F,
+    ) -> io::Result<Overwrite>
+    where
+        W: Write,
+        F: FnMut() -> io::Result<Key>,
+    {
+        if self.all {
+            return Ok(Overwrite::All);
+        }
+        region.draw(self.render(target, preview))?;
+        let answer = loop {
+            match read_key()? {
+                Key::Enter => break self.default,
+                Key::Char('y' | 'Y') => break Overwrite::Yes,
+                Key::Char('n' | 'N') => break Overwrite::No,
+                Key::Char('a' | 'A') => break Overwrite::All,
+                Key::Char('q' | 'Q') | Key::Escape => break Overwrite::Quit,
+                Key::CtrlC => {
+                    region.clear()?;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                _ => {}
+            }
+        };
+        self.all = answer == Overwrite::All;
+        finish_prompt(
+            region,
+            self.transcript,
+            &format!("{} {}", self.prompt, target),
+            answer.label(),
+        )?;
+        Ok(answer)
+    }
+
+    fn render(&self, target: &str, preview: &str) -> Vec<String> {
+        let theme = theme();
+        let keys: Vec<String> = [
+            Overwrite::Yes,
+            Overwrite::No,
+            Overwrite::All,
+            Overwrite::Quit,
+        ]
+        .into_iter()
+        .map(|choice| {
+            let key = &choice.label()[..1];
+            if choice == self.default {
+                key.to_uppercase()
+            } else {
+                key.to_string()
+            }
+        })
+        .collect();
+        let mut lines = vec![format!(
+            "{} {} {}? {}",
+            Console::new_with_style("?", theme.style(Tone::Success).clone().bold()),
+            Console::new(self.prompt.as_str()).bold(),
+            Console::new_with_style(target, theme.style(Tone::Info).clone().bold()),
+            Console::new_with_style(
+                format!("[{}]", keys.join("/")),
+                theme.style(Tone::Muted).clone()
+            ),
+        )];
+
+        let bar = Console::new_with_style(
+            format!("  {} ", symbols().border.vertical),
+            theme.style(Tone::Muted).clone(),
+        );
+        let preview: Vec<&str> = preview.lines().collect();
+        let width = crate::terminal::terminal_width().saturating_sub(4);
+        for line in preview.iter().take(self.preview_lines) {
+            lines.push(format!(
+                "{}{}",
+                bar,
+                unicode::truncate(line, width, symbols().ellipsis)
+            ));
+        }
+        let hidden = preview.len().saturating_sub(self.preview_lines);
+        if hidden > 0 {
+            lines.push(format!(
+                "{}{}",
+                bar,
+                Console::new_with_style(
+                    format!(
+                        "{} {} more line{}",
+                        symbols().ellipsis,
+                        hidden,
+                        if hidden == 1 { "" } else { "s" }
+                    ),
+                    theme.style(Tone::Muted).clone().italic()
+                )
+            ));
+        }
+        lines
+    }
+}
+
+impl Default for OverwritePrompt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    fn run(prompt: &mut OverwritePrompt, keys: &[Key]) -> io::Result<Overwrite> {
+        let mut keys = keys.iter().copied();
+        let mut region = LiveRegion::new(Vec::new());
+        prompt.interact_on(&mut region, "a.txt", "", || {
+            Ok(keys.next().expect("ran out of keys"))
+        })
+    }
+
+    #[test]
+    fn test_shortcuts_and_default() {
+        let mut prompt = OverwritePrompt::new();
+        assert_eq!(run(&mut prompt, &[Key::Char('y')]).unwrap(), Overwrite::Yes);
+        assert_eq!(run(&mut prompt, &[Key::Char('N')]).unwrap(), Overwrite::No);
+        assert_eq!(run(&mut prompt, &[Key::Enter]).unwrap(), Overwrite::No);
+        assert_eq!(
+            run(&mut prompt, &[Key::Char('x'), Key::Escape]).unwrap(),
+            Overwrite::Quit
+        );
+        let mut prompt = OverwritePrompt::new().default(Overwrite::Yes);
+        assert_eq!(run(&mut prompt, &[Key::Enter]).unwrap(), Overwrite::Yes);
+
+        let err = run(&mut prompt, &[Key::CtrlC]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+
+    #[test]
+    fn test_all_stops_asking() {
+        let mut prompt = OverwritePrompt::new();
+        assert_eq!(run(&mut prompt, &[Key::Char('a')]).unwrap(), Overwrite::All);
+        assert!(prompt.applies_to_all());
+        // No keys left; asking again must not read any
+        assert_eq!(run(&mut prompt, &[]).unwrap(), Overwrite::All);
+    }
+
+    #[test]
+    fn test_render_with_preview() {
+        let prompt = OverwritePrompt::new().preview_lines(2);
+        let lines: Vec<String> = prompt
+            .render("src/main.rs", "fn main() {\n    run();\n}\n")
+            .iter()
+            .map(|line| strip_ansi(line))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "? Overwrite src/main.rs? [y/N/a/q]",
+                "  │ fn main() {",
+                "  │     run();",
+                "  │ … 1 more line",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transcript_records_answer() {
+        let mut prompt = OverwritePrompt::new();
+        let mut region = LiveRegion::new(Vec::new());
+        let mut keys = [Key::Char('y')].into_iter();
+        prompt
+            .interact_on(&mut region, "a.txt", "", || Ok(keys.next().unwrap()))
+            .unwrap();
+        let output = strip_ansi(&String::from_utf8(region.into_inner()).unwrap());
+        assert!(output.ends_with("? Overwrite a.txt · yes\n"), "{output:?}");
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::cleanup::{CleanupGuard, register_sequence};
+use crate::terminal::terminal_size;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Top,
+    Bottom,
+}
+
+// Lines kept in place at the top or bottom of the terminal, e.g. a status
+// header or a metrics footer, while everything else printed scrolls in the
+// rows between. Uses a scroll region (DECSTBM), so output written to the
+// terminal any other way scrolls around them too. Dropping it gives the
+// rows back.
+pub struct PinnedRegion<W: Write> {
+    out: W,
+    edge: Edge,
+    height: usize,
+    columns: usize,
+    rows: usize,
+    lines: Vec<String>,
+    // Resets the scroll region if the process is interrupted first
+    _cleanup: CleanupGuard,
+}
+
+impl<W: Write> PinnedRegion<W> {
+    pub fn top(out: W, height: usize) -> io::Result<Self> {
+        Self::open(out, Edge::Top, height)
+    }
+
+    pub fn bottom(out: W, height: usize) -> io::Result<Self> {
+        Self::open(out, Edge::Bottom, height)
+    }
+
+    fn open(out: W, edge: Edge, height: usize) -> io::Result<Self> {
+        let (columns, rows) = terminal_size().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "pinned lines need a terminal of known size",
+            )
+        })?;
+        Self::with_size(out, edge, height, columns, rows)
+    }
+
+    fn with_size(
+        mut out: W,
+        edge: Edge,
+        height: usize,
+        columns: usize,
+        rows: usize,
+    ) -> io::Result<Self> {
+        if height == 0 || height >= rows {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "can't pin {} of the terminal's {} rows and leave room to scroll",
+                    height, rows
+                ),
+            ));
+        }
+        // Scrolls what's on screen up out of the way of the pinned rows
+        write!(out, "{}\x1b[{}A", "\n".repeat(height), height)?;
+        let mut region = PinnedRegion {
+            out,
+            edge,
+            height,
+            columns,
+            rows,
+            lines: Vec::new(),
+            _cleanup: register_sequence("\x1b7\x1b[r\x1b8"),
+        };
+        region.set_scroll_region()?;
+        if edge == Edge::Top {
+            // Carry on printing at the bottom of the rows that scroll
+            write!(region.out, "\x1b[{};1H", rows)?;
+        }
+        region.redraw()?;
+        Ok(region)
+    }
+
+    // Replaces the pinned lines. Missing lines are left blank, extra lines
+    // are dropped and long ones cut at the terminal's width.
+    pub fn draw<I>(&mut self, lines: I) -> io::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        self.lines = lines
+            .into_iter()
+            .take(self.height)
+            .map(|line| line.to_string())
+            .collect();
+        self.redraw()
+    }
+
+    // Prints a line in the rows that scroll
+    pub fn println<T: Display>(&mut self, line: T) -> io::Result<()> {
+        writeln!(self.out, "{}", line)?;
+        self.out.flush()
+    }
+
+    // Pins the lines again for a terminal that's now `columns` by `rows`,
+    // e.g. from a `ResizeWatcher`, since resizing resets the scroll region
+    // on some terminals and leaves it in the wrong place on the others
+    pub fn resize(&mut self, columns: usize, rows: usize) -> io::Result<()> {
+        if rows <= self.height {
+            return Ok(());
+        }
+        self.columns = columns;
+        self.rows = rows;
+        self.set_scroll_region()?;
+        self.redraw()
+    }
+
+    fn first_row(&self) -> usize {
+        match self.edge {
+            Edge::Top => 1,
+            Edge::Bottom => self.rows - self.height + 1,
+        }
+    }
+
+    // Setting the region moves the cursor to the top-left, so it's saved
+    // and restored around it
+    fn set_scroll_region(&mut self) -> io::Result<()> {
+        let (first, last) = match self.edge {
+            Edge::Top => (self.height + 1, self.rows),
+            Edge::Bottom => (1, self.rows - self.height),
+        };
+        write!(self.out, "\x1b7\x1b[{};{}r\x1b8", first, last)
+    }
+
+    fn redraw(&mut self) -> io::Result<()> {
+        write!(self.out, "\x1b7")?;
+        for i in 0..self.height {
+            write!(self.out, "\x1b[{};1H\x1b[2K", self.first_row() + i)?;
+            if let Some(line) = self.lines.get(i) {
+                let line = crate::parse_ansi(line).take_visible(self.columns);
+                write!(self.out, "{}", line)?;
+            }
+        }
+        write!(self.out, "\x1b8")?;
+        self.out.flush()
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        write!(self.out, "\x1b7\x1b[r")?;
+        for i in 0..self.height {
+            write!(self.out, "\x1b[{};1H\x1b[2K", self.first_row() + i)?;
+        }
+        write!(self.out, "\x1b8")?;
+        self.out.flush()
+    }
+}
+
+impl<W: Write> Drop for PinnedRegion<W> {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    fn take(out: &CaptureWriter) -> String {
+        let written = out.contents();
+        out.clear();
+        written
+    }
+
+    #[test]
+    fn test_bottom_region() {
+        let out = CaptureWriter::new();
+        let mut region = PinnedRegion::with_size(out.clone(), Edge::Bottom, 2, 20, 10).unwrap();
+        assert_eq!(
+            take(&out),
+            "\n\n\x1b[2A\x1b7\x1b[1;8r\x1b8\x1b7\x1b[9;1H\x1b[2K\x1b[10;1H\x1b[2K\x1b8"
+        );
+
+        region.draw(["building", "3/10 crates", "extra"]).unwrap();
+        assert_eq!(
+            take(&out),
+            "\x1b7\x1b[9;1H\x1b[2Kbuilding\x1b[10;1H\x1b[2K3/10 crates\x1b8"
+        );
+
+        region.println("Compiling serde").unwrap();
+        assert_eq!(take(&out), "Compiling serde\n");
+
+        drop(region);
+        assert_eq!(
+            take(&out),
+            "\x1b7\x1b[r\x1b[9;1H\x1b[2K\x1b[10;1H\x1b[2K\x1b8"
+        );
+    }
+
+    #[test]
+    fn test_top_region_resize() {
+        let out = CaptureWriter::new();
+        let mut region = PinnedRegion::with_size(out.clone(), Edge::Top, 1, 8, 10).unwrap();
+        assert!(take(&out).starts_with("\n\x1b[1A\x1b7\x1b[2;10r\x1b8\x1b[10;1H"));
+
+        // Long lines are cut rather than wrapping into the rows that scroll
+        region.draw(["deploying to prod"]).unwrap();
+        assert_eq!(take(&out), "\x1b7\x1b[1;1H\x1b[2Kdeployin\x1b8");
+
+        region.resize(12, 20).unwrap();
+        assert_eq!(
+            take(&out),
+            "\x1b7\x1b[2;20r\x1b8\x1b7\x1b[1;1H\x1b[2Kdeploying to\x1b8"
+        );
+    }
+
+    #[test]
+    fn test_needs_room_to_scroll() {
+        let error = PinnedRegion::with_size(Vec::new(), Edge::Top, 10, 80, 10)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(PinnedRegion::with_size(Vec::new(), Edge::Top, 0, 80, 10).is_err());
+    }
+}
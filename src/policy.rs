@@ -0,0 +1,119 @@
+use std::sync::RwLock;
+
+use crate::{Attribute, Style};
+
+// Remaps or drops attributes for all terminal output, e.g. to show blink as
+// bold, without touching the code that builds the styles
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EffectPolicy {
+    rules: Vec<(Attribute, Option<Attribute>)>,
+}
+
+static CURRENT: RwLock<Option<EffectPolicy>> = RwLock::new(None);
+
+// Installs `policy` for everything printed afterwards; pass
+// `EffectPolicy::default()` to go back to rendering attributes as they are
+pub fn set_effect_policy(policy: EffectPolicy) {
+    let policy = if policy.rules.is_empty() {
+        None
+    } else {
+        Some(policy)
+    };
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+impl EffectPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remap(mut self, from: Attribute, to: Attribute) -> Self {
+        self.set(from, Some(to));
+        self
+    }
+
+    pub fn disable(mut self, attribute: Attribute) -> Self {
+        self.set(attribute, None);
+        self
+    }
+
+    fn set(&mut self, from: Attribute, to: Option<Attribute>) {
+        self.rules.retain(|&(attr, _)| attr != from);
+        self.rules.push((from, to));
+    }
+
+    // What `attribute` is rendered as, or `None` if it is dropped
+    pub fn resolve(&self, attribute: Attribute) -> Option<Attribute> {
+        match self.rules.iter().find(|&&(attr, _)| attr == attribute) {
+            Some(&(_, to)) => to,
+            None => Some(attribute),
+        }
+    }
+
+    pub fn apply(&self, style: &Style) -> Style {
+        let mut applied = style.clone();
+        applied.attributes.clear();
+        applied.removed.clear();
+        for &attr in &style.removed {
+            if let Some(attr) = self.resolve(attr)
+                && !applied.removed.contains(&attr)
+            {
+                applied.removed.push(attr);
+            }
+        }
+        for &attr in &style.attributes {
+            if let Some(attr) = self.resolve(attr)
+                && !applied.attributes.contains(&attr)
+            {
+                applied.removed.retain(|&a| a != attr);
+                applied.attributes.push(attr);
+            }
+        }
+        applied
+    }
+}
+
+// The style with the global policy applied, or `None` when there is no policy
+#[cfg(not(feature = "no-color"))]
+pub(crate) fn apply_current(style: &Style) -> Option<Style> {
+    let current = CURRENT.read().unwrap_or_else(|e| e.into_inner());
+    current.as_ref().map(|policy| policy.apply(style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn test_resolve() {
+        let policy = EffectPolicy::new()
+            .remap(Attribute::Blink, Attribute::Bold)
+            .disable(Attribute::Hidden);
+        assert_eq!(policy.resolve(Attribute::Blink), Some(Attribute::Bold));
+        assert_eq!(policy.resolve(Attribute::Hidden), None);
+        assert_eq!(policy.resolve(Attribute::Italic), Some(Attribute::Italic));
+
+        // A later rule for the same attribute replaces the earlier one
+        let policy = policy.remap(Attribute::Hidden, Attribute::Dim);
+        assert_eq!(policy.resolve(Attribute::Hidden), Some(Attribute::Dim));
+    }
+
+    #[test]
+    fn test_apply() {
+        let policy = EffectPolicy::new()
+            .remap(Attribute::Blink, Attribute::Bold)
+            .disable(Attribute::Hidden);
+        let style = Style::new()
+            .fg(Color::RED)
+            .bold()
+            .blink()
+            .hidden()
+            .no_italic();
+
+        let applied = policy.apply(&style);
+        assert_eq!(applied.attributes, vec![Attribute::Bold]);
+        assert_eq!(applied.removed, vec![Attribute::Italic]);
+        assert_eq!(applied.foreground, Some(Color::RED));
+    }
+}
@@ -0,0 +1,236 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use crate::draw_target::{DrawTarget, stderr_target};
+use crate::progress::{bar, percent};
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::throttle::Throttle;
+use crate::{Console, LiveRegion, Style, StyledText};
+
+// Progress for a pool of worker threads, e.g. a rayon pool: a row per
+// worker showing the item it's on, above a bar for the whole job, redrawn
+// in place. Clones share the same view, so every worker can hold one.
+// Workers are numbered from 0, like `rayon::current_thread_index()`, and
+// rows are added as new numbers turn up.
+//
+//   #0 → src/parser.rs
+//   #1 idle
+//   ██████──────── 42/96 43%
+#[derive(Clone)]
+pub struct PoolProgress {
+    state: Arc<Mutex<PoolState>>,
+}
+
+struct PoolState {
+    // `None` when hidden
+    region: Option<LiveRegion<Box<dyn Write + Send>>>,
+    // The item each worker is on
+    workers: Vec<Option<String>>,
+    len: u64,
+    pos: u64,
+    width: usize,
+    throttle: Throttle,
+    finished: bool,
+    columns: Option<usize>,
+}
+
+impl PoolProgress {
+    // Draws on stderr when it's a terminal and stays hidden otherwise
+    pub fn new(workers: usize, len: u64) -> Self {
+        Self::with_target(stderr_target(), workers, len)
+    }
+
+    // Draws on `target` if live components draw there, and stays hidden
+    // otherwise
+    pub fn with_target(target: DrawTarget, workers: usize, len: u64) -> Self {
+        if !target.is_live() {
+            return Self::hidden(workers, len);
+        }
+        let terminal = target.is_terminal();
+        let progress = Self::with_writer(target, workers, len);
+        if terminal {
+            progress.lock().columns = Some(crate::terminal::terminal_width());
+        }
+        progress
+    }
+
+    pub fn with_writer<W: Write + Send + 'static>(out: W, workers: usize, len: u64) -> Self {
+        Self::with_region(Some(LiveRegion::new(Box::new(out))), workers, len)
+    }
+
+    // Tracks progress without drawing anything
+    pub fn hidden(workers: usize, len: u64) -> Self {
+        Self::with_region(None, workers, len)
+    }
+
+    fn with_region(
+        region: Option<LiveRegion<Box<dyn Write + Send>>>,
+        workers: usize,
+        len: u64,
+    ) -> Self {
+        PoolProgress {
+            state: Arc::new(Mutex::new(PoolState {
+                region,
+                workers: vec![None; workers],
+                len,
+                pos: 0,
+                width: 30,
+                throttle: Throttle::default(),
+                finished: false,
+                columns: None,
+            })),
+        }
+    }
+
+    // Columns taken by the overall bar
+    pub fn width(self, width: usize) -> Self {
+        self.lock().width = width;
+        self
+    }
+
+    // Shows `worker` as working on `item`
+    pub fn start<T: Into<String>>(&self, worker: usize, item: T) {
+        let mut state = self.lock();
+        if state.workers.len() <= worker {
+            state.workers.resize(worker + 1, None);
+        }
+        state.workers[worker] = Some(item.into());
+        state.draw(false);
+    }
+
+    // Counts `worker`'s item as done and shows the worker as idle
+    pub fn complete(&self, worker: usize) {
+        let mut state = self.lock();
+        if let Some(item) = state.workers.get_mut(worker) {
+            *item = None;
+        }
+        state.pos = state.pos.saturating_add(1);
+        state.draw(false);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.lock().pos
+    }
+
+    // Replaces the worker rows with the final state of the bar
+    pub fn finish(&self) {
+        let mut state = self.lock();
+        state.workers.clear();
+        state.pos = state.pos.max(state.len);
+        state.draw(true);
+        state.finished = true;
+        if let Some(region) = &mut state.region {
+            let _ = region.finish();
+        }
+    }
+
+    pub fn finish_and_clear(&self) {
+        let mut state = self.lock();
+        state.finished = true;
+        if let Some(region) = &mut state.region {
+            let _ = region.clear();
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PoolState {
+    fn draw(&mut self, force: bool) {
+        if self.finished || self.region.is_none() {
+            return;
+        }
+        let now = Instant::now();
+        if force {
+            self.throttle.drawn(now);
+        } else if !self.throttle.ready(now) {
+            return;
+        }
+        let mut lines = self.render();
+        if let Some(columns) = self.columns {
+            for line in &mut lines {
+                *line = line.take_visible(columns);
+            }
+        }
+        if let Some(region) = &mut self.region {
+            let _ = region.draw(lines);
+        }
+    }
+
+    fn render(&self) -> Vec<StyledText> {
+        let theme = theme();
+        let muted = theme.style(Tone::Muted).clone();
+        let label_width = format!("#{}", self.workers.len().saturating_sub(1)).len();
+        let mut lines: Vec<StyledText> = self
+            .workers
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let label = Console::new_with_style(
+                    format!("{:<1$}", format!("#{}", i), label_width),
+                    theme.style(Tone::Info).clone(),
+                );
+                let line = StyledText::new().then(label).then(Console::new(" "));
+                match item {
+                    Some(item) => line
+                        .then(Console::new(format!("{} ", symbols().arrow)))
+                        .then(Console::new_with_style(item.as_str(), Style::new())),
+                    None => line.then(Console::new_with_style("idle", muted.clone())),
+                }
+            })
+            .collect();
+        lines.push(crate::parse_ansi(&format!(
+            "{} {}/{} {}%",
+            bar(self.pos, self.len, self.width),
+            self.pos,
+            self.len,
+            percent(self.pos, self.len)
+        )));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+
+    fn rendered(progress: &PoolProgress) -> Vec<String> {
+        progress
+            .lock()
+            .render()
+            .iter()
+            .map(|line| line.plain())
+            .collect()
+    }
+
+    #[test]
+    fn test_rows_per_worker() {
+        let progress = PoolProgress::hidden(2, 4).width(8);
+        progress.start(0, "src/parser.rs");
+        progress.start(11, "src/lexer.rs");
+        progress.complete(0);
+        assert_eq!(progress.position(), 1);
+        let lines = rendered(&progress);
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[0], "#0  idle");
+        assert_eq!(lines[1], "#1  idle");
+        assert_eq!(lines[11], "#11 → src/lexer.rs");
+        assert_eq!(lines[12], "██────── 1/4 25%");
+    }
+
+    #[test]
+    fn test_finish_leaves_the_bar() {
+        let out = CaptureWriter::new();
+        let progress = PoolProgress::with_writer(out.clone(), 1, 2).width(4);
+        progress.start(0, "a");
+        progress.complete(0);
+        progress.finish();
+        assert!(strip_ansi(&out.contents()).ends_with("████ 2/2 100%\n"));
+        assert!(progress.lock().workers.is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::{self, Display};
+
+use crate::{Console, Style, Styled};
+
+// Formatting used by the code `#[derive(PrettyDisplay)]` generates
+
+#[doc(hidden)]
+pub fn write_pretty_title(f: &mut fmt::Formatter, title: &str) -> fmt::Result {
+    writeln!(f, "{}", Console::new(title).bold())
+}
+
+// Writes `  label:  value`, padding labels to `width` and indenting the
+// continuation lines of multi-line values to the value column
+#[doc(hidden)]
+pub fn write_pretty_field(
+    f: &mut fmt::Formatter,
+    label: &str,
+    width: usize,
+    value: &dyn Display,
+    style: Style,
+) -> fmt::Result {
+    let padding = width.saturating_sub(label.chars().count());
+    write!(
+        f,
+        "  {}:{}  ",
+        Console::new(label).dim(),
+        " ".repeat(padding)
+    )?;
+
+    let value = value.to_string();
+    for (i, line) in value.split('\n').enumerate() {
+        if i > 0 {
+            write!(f, "\n{}", " ".repeat(width + 5))?;
+        }
+        if !line.is_empty() {
+            write!(f, "{}", Styled::with_style(line, style.clone()))?;
+        }
+    }
+    writeln!(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PrettyDisplay;
+    use crate::testing::strip_ansi;
+
+    #[derive(PrettyDisplay)]
+    struct Config {
+        #[style(fg = "cyan", bold)]
+        host: String,
+        #[style(fg = "#ff8800")]
+        port: u16,
+        #[pretty(label = "log level")]
+        log_level: &'static str,
+        #[pretty(skip)]
+        #[allow(dead_code)]
+        secret: String,
+        notes: String,
+    }
+
+    #[derive(PrettyDisplay)]
+    struct Pair(#[style(fg = "bright_green")] i32, i32);
+
+    fn config() -> Config {
+        Config {
+            host: "localhost".into(),
+            port: 8080,
+            log_level: "debug",
+            secret: "hunter2".into(),
+            notes: "first\nsecond".into(),
+        }
+    }
+
+    #[test]
+    fn test_derived_layout() {
+        assert_eq!(
+            strip_ansi(&config().pretty()),
+            "Config\n  \
+             host:       localhost\n  \
+             port:       8080\n  \
+             log level:  debug\n  \
+             notes:      first\n              \
+             second\n"
+        );
+        assert_eq!(
+            strip_ansi(&Pair(1, 2).to_string()),
+            "Pair\n  0:  1\n  1:  2\n"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_derived_styles() {
+        let output = config().to_string();
+        assert!(output.contains(&crate::Console::new("localhost").cyan().bold().to_string()));
+        assert!(output.contains(&crate::Console::new("8080").fg_rgb(255, 136, 0).to_string()));
+    }
+}
@@ -0,0 +1,928 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::clock::{Clock, SystemClock};
+use crate::draw_target::{DrawTarget, stderr_target};
+use crate::estimator::Estimator;
+use crate::format::{human_bytes, human_bytes_decimal, human_count, human_duration};
+use crate::resize::ResizeWatcher;
+use crate::symbols::symbols;
+use crate::template::{Field, Template};
+use crate::theme::{Tone, theme};
+use crate::throttle::Throttle;
+use crate::{Console, LiveRegion, Style, StyledText};
+
+const COUNT_TEMPLATE: &str = "{spinner:info} {bar} {pos}/{len} {percent}% {eta:muted} {msg}";
+const BYTES_TEMPLATE: &str =
+    "{spinner:info} {bar} {bytes}/{total_bytes} {bytes_per_sec:muted} {eta:muted} {msg}";
+// Until the length is known
+const UNKNOWN_COUNT_TEMPLATE: &str = "{spinner:info} {bar} {pos} {elapsed:muted} {msg}";
+const UNKNOWN_BYTES_TEMPLATE: &str =
+    "{spinner:info} {bar} {bytes} {bytes_per_sec:muted} {elapsed:muted} {msg}";
+// Rates are measured over this much recent time, so they follow changes in
+// speed without jumping around on every update
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+// A single-line bar for work of known length, redrawn in place. Clones share
+// the same bar, so one can be handed to a worker or an I/O adapter while
+// another finishes it. The layout is a `Template` with these placeholders:
+// `spinner`, `bar`, `pos`, `len`, `percent`, `per_sec`, `bytes`,
+// `total_bytes`, `bytes_per_sec`, `transfer`, `elapsed`, `eta` and `msg`,
+// plus any added with `key`. A width on `{bar}` sets its length and a
+// second style colors its unfilled part, e.g. `{bar:40.green/dim}`.
+#[derive(Clone)]
+pub struct ProgressBar {
+    state: Arc<Mutex<BarState>>,
+}
+
+// What a custom placeholder is computed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressState {
+    pub pos: u64,
+    pub len: u64,
+    pub elapsed: Duration,
+    pub message: String,
+}
+
+type KeyFn = Box<dyn Fn(&ProgressState) -> String + Send>;
+
+struct BarState {
+    // `None` when hidden
+    region: Option<LiveRegion<Box<dyn Write + Send>>>,
+    len: u64,
+    // The length isn't known yet, so the bar bounces instead of filling
+    indeterminate: bool,
+    pos: u64,
+    message: String,
+    template: Option<Template>,
+    keys: Vec<(String, KeyFn)>,
+    bytes: bool,
+    // Sizes in powers of 1000 rather than 1024
+    decimal: bool,
+    width: usize,
+    clock: Box<dyn Clock>,
+    start: Instant,
+    throttle: Throttle,
+    frame: usize,
+    // Positions at recent draws, by time since the start, oldest first
+    samples: VecDeque<(Duration, u64)>,
+    // Replaces the built-in rate and time left
+    estimator: Option<Box<dyn Estimator>>,
+    finished: bool,
+    // Set when drawing to the terminal, to keep the line from wrapping
+    columns: Option<usize>,
+    resize: Option<ResizeWatcher>,
+    ticker: Option<Ticker>,
+}
+
+// A thread redrawing the bar on an interval. Dropping it, e.g. along with
+// the last handle to the bar, lets the thread exit at its next wake-up.
+struct Ticker {
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl Ticker {
+    fn spawn(state: Weak<Mutex<BarState>>, interval: Duration) -> Option<Ticker> {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = std::thread::Builder::new()
+            .name("pretty-console-tick".into())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let Some(state) = state.upgrade() else {
+                        return;
+                    };
+                    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                    if state.finished {
+                        return;
+                    }
+                    state.draw(false);
+                }
+            })
+            .ok()?;
+        Some(Ticker { stop, thread })
+    }
+
+    // Waits for the thread so nothing is drawn after this returns. The bar
+    // must not be locked, or a tick in progress could never finish.
+    fn stop(self) {
+        drop(self.stop);
+        let _ = self.thread.join();
+    }
+}
+
+impl ProgressBar {
+    // Draws on stderr when it's a terminal and stays hidden otherwise, so
+    // piped output and CI logs don't fill up with redraws
+    pub fn new(len: u64) -> Self {
+        Self::with_target(stderr_target(), len)
+    }
+
+    // Draws on `target` if live components draw there, and stays hidden
+    // otherwise
+    pub fn with_target(target: DrawTarget, len: u64) -> Self {
+        if !target.is_live() {
+            return Self::hidden(len);
+        }
+        let terminal = target.is_terminal();
+        let bar = Self::with_writer(target, len);
+        if terminal {
+            bar.follow_terminal();
+        }
+        bar
+    }
+
+    pub fn with_writer<W: Write + Send + 'static>(out: W, len: u64) -> Self {
+        Self::with_region(Some(LiveRegion::new(Box::new(out))), len)
+    }
+
+    // Tracks progress without drawing anything
+    pub fn hidden(len: u64) -> Self {
+        Self::with_region(None, len)
+    }
+
+    fn with_region(region: Option<LiveRegion<Box<dyn Write + Send>>>, len: u64) -> Self {
+        ProgressBar {
+            state: Arc::new(Mutex::new(BarState {
+                region,
+                len,
+                indeterminate: false,
+                pos: 0,
+                message: String::new(),
+                template: None,
+                keys: Vec::new(),
+                bytes: false,
+                decimal: false,
+                width: 30,
+                clock: Box::new(SystemClock),
+                start: Instant::now(),
+                throttle: Throttle::default(),
+                frame: 0,
+                samples: VecDeque::new(),
+                estimator: None,
+                finished: false,
+                columns: None,
+                resize: None,
+                ticker: None,
+            })),
+        }
+    }
+
+    // Replaces the default layout, e.g.
+    // `"{msg:bold} {bar} {percent}% ({eta:muted} left)".parse()?`
+    pub fn template(self, template: Template) -> Self {
+        self.lock().template = Some(template);
+        self
+    }
+
+    // Adds a placeholder for templates, e.g. `{files}` with
+    // `.key("files", |state| format!("{} files", state.pos))`. Keys added
+    // here take the place of built-in ones of the same name.
+    pub fn key<F>(self, name: &str, value: F) -> Self
+    where
+        F: Fn(&ProgressState) -> String + Send + 'static,
+    {
+        self.lock().keys.push((name.to_string(), Box::new(value)));
+        self
+    }
+
+    // Computes rates and the time left with `estimator` instead of the
+    // built-in averages, e.g. `SmoothedEstimator` for speeds that drift or
+    // `PhasedEstimator` for work that gets slower towards the end
+    pub fn estimator<E: Estimator + 'static>(self, estimator: E) -> Self {
+        self.lock().estimator = Some(Box::new(estimator));
+        self
+    }
+
+    // Reads the time from `clock` instead of the system, e.g. a
+    // `ManualClock` in tests. The bar's elapsed time starts over from the
+    // clock's current time.
+    pub fn clock<C: Clock + 'static>(self, clock: C) -> Self {
+        {
+            let mut state = self.lock();
+            state.start = clock.now();
+            state.throttle = Throttle::default();
+            state.clock = Box::new(clock);
+        }
+        self
+    }
+
+    // Columns taken by the `{bar}` placeholder
+    pub fn width(self, width: usize) -> Self {
+        self.lock().width = width;
+        self
+    }
+
+    pub fn message<T: Into<String>>(self, message: T) -> Self {
+        self.lock().message = message.into();
+        self
+    }
+
+    // Counts bytes: positions show as sizes, and the default layout shows
+    // throughput instead of the count
+    pub fn bytes(self) -> Self {
+        self.lock().bytes = true;
+        self
+    }
+
+    // For work of unknown length: a segment bounces along the bar, and the
+    // default layout leaves out the length, percentage and time left, until
+    // `set_length` is called. Call `enable_steady_tick` too to keep it
+    // moving between updates.
+    pub fn indeterminate(self) -> Self {
+        self.lock().indeterminate = true;
+        self
+    }
+
+    // Shows sizes in decimal units (kB, MB) instead of binary ones (KiB,
+    // MiB)
+    pub fn decimal_units(self) -> Self {
+        self.lock().decimal = true;
+        self
+    }
+
+    pub fn set_message<T: Into<String>>(&self, message: T) {
+        let mut state = self.lock();
+        state.message = message.into();
+        state.draw(true);
+    }
+
+    pub fn set_length(&self, len: u64) {
+        let mut state = self.lock();
+        state.len = len;
+        state.indeterminate = false;
+        state.draw(true);
+    }
+
+    pub fn length(&self) -> u64 {
+        self.lock().len
+    }
+
+    pub fn position(&self) -> u64 {
+        self.lock().pos
+    }
+
+    pub fn set_position(&self, pos: u64) {
+        let mut state = self.lock();
+        state.pos = pos;
+        state.draw(false);
+    }
+
+    pub fn inc(&self, delta: u64) {
+        let mut state = self.lock();
+        state.pos = state.pos.saturating_add(delta);
+        state.draw(false);
+    }
+
+    // Redraws without progress, to keep the spinner and elapsed time moving
+    pub fn tick(&self) {
+        self.lock().draw(false);
+    }
+
+    // Ticks from a background thread every `interval`, so the spinner and
+    // the elapsed and remaining times keep moving while the work loop is
+    // busy elsewhere. Stops when the bar is finished.
+    pub fn enable_steady_tick(&self, interval: Duration) {
+        let previous = {
+            let mut state = self.lock();
+            if state.finished || state.region.is_none() {
+                return;
+            }
+            let ticker = Ticker::spawn(Arc::downgrade(&self.state), interval);
+            std::mem::replace(&mut state.ticker, ticker)
+        };
+        if let Some(ticker) = previous {
+            ticker.stop();
+        }
+    }
+
+    pub fn disable_steady_tick(&self) {
+        let ticker = self.lock().ticker.take();
+        if let Some(ticker) = ticker {
+            ticker.stop();
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        let state = self.lock();
+        state.clock.now().saturating_duration_since(state.start)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.lock().finished
+    }
+
+    // Fills the bar and leaves its final state on screen
+    pub fn finish(&self) {
+        self.disable_steady_tick();
+        let mut state = self.lock();
+        if state.indeterminate {
+            // Done, so the length is wherever it got to
+            state.len = state.pos;
+            state.indeterminate = false;
+        }
+        state.pos = state.pos.max(state.len);
+        state.draw(true);
+        state.finished = true;
+        state.resize = None;
+        if let Some(region) = &mut state.region {
+            // Drawing is best-effort; a closed stderr shouldn't fail the work
+            let _ = region.finish();
+        }
+    }
+
+    // Replaces the bar with `line` for good. A hidden bar writes the line to
+    // stderr by itself, so logs still get the outcome.
+    pub(crate) fn finish_with_line(&self, line: StyledText) {
+        self.disable_steady_tick();
+        let mut state = self.lock();
+        if state.finished {
+            return;
+        }
+        state.finished = true;
+        state.resize = None;
+        let line = match state.columns {
+            Some(columns) => line.take_visible(columns),
+            None => line,
+        };
+        let _ = match &mut state.region {
+            Some(region) => region.draw([line]).and_then(|_| region.finish()),
+            None => crate::group::write_stderr(&format!("{}\n", line)),
+        };
+    }
+
+    pub fn finish_and_clear(&self) {
+        self.disable_steady_tick();
+        let mut state = self.lock();
+        state.finished = true;
+        state.resize = None;
+        if let Some(region) = &mut state.region {
+            let _ = region.clear();
+        }
+    }
+
+    // Prints `line` for good above the bar, e.g. a log message, without
+    // garbling it. A bar hidden because stderr isn't a terminal writes the
+    // line to stderr by itself.
+    pub fn println<T: Display>(&self, line: T) {
+        let mut state = self.lock();
+        let finished = state.finished;
+        // Best-effort like drawing; a closed stderr shouldn't fail the work
+        let _ = match &mut state.region {
+            Some(region) if !finished => region.println(line),
+            _ => crate::group::write_stderr(&format!("{}\n", line)),
+        };
+    }
+
+    // Clears the bar while `f` runs and draws it again afterwards, so a
+    // spawned command's output isn't mixed into it. `f` must not update
+    // this bar, which is locked until it returns.
+    pub fn suspend<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let mut state = self.lock();
+        if let Some(region) = &mut state.region {
+            let _ = region.clear();
+        }
+        let result = f();
+        state.draw(true);
+        result
+    }
+
+    // Cuts the line at the terminal's width, and redraws it right away when
+    // the window is resized
+    fn follow_terminal(&self) {
+        let state = Arc::downgrade(&self.state);
+        let watcher = ResizeWatcher::on_resize(move |columns, _| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            state.columns = Some(columns);
+            if let Some(region) = &mut state.region {
+                region.resize(columns);
+            }
+            state.draw(true);
+        });
+        let columns = crate::terminal::terminal_width();
+        let mut state = self.lock();
+        state.columns = Some(columns);
+        if let Some(region) = &mut state.region {
+            region.resize(columns);
+        }
+        state.resize = watcher.ok();
+    }
+
+    // Used by the I/O adapters, which always count bytes
+    pub(crate) fn count_bytes(&self) {
+        self.lock().bytes = true;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BarState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl BarState {
+    fn draw(&mut self, force: bool) {
+        if self.finished || self.region.is_none() {
+            return;
+        }
+        let now = self.clock.now();
+        if force {
+            self.throttle.drawn(now);
+        } else if !self.throttle.ready(now) {
+            return;
+        }
+        self.sample(now - self.start);
+        let mut line = self.render(now - self.start);
+        if let Some(columns) = self.columns {
+            line = crate::parse_ansi(&line.to_string()).take_visible(columns);
+        }
+        self.frame = self.frame.wrapping_add(1);
+        if let Some(region) = &mut self.region {
+            let _ = region.draw([line]);
+        }
+    }
+
+    fn render(&self, elapsed: Duration) -> StyledText {
+        let default;
+        let template = match &self.template {
+            Some(template) => template,
+            None => {
+                let source = match (self.bytes, self.indeterminate) {
+                    (false, false) => COUNT_TEMPLATE,
+                    (true, false) => BYTES_TEMPLATE,
+                    (false, true) => UNKNOWN_COUNT_TEMPLATE,
+                    (true, true) => UNKNOWN_BYTES_TEMPLATE,
+                };
+                default = Template::parse(source).expect("default progress template");
+                &default
+            }
+        };
+        let rate = self.rate(elapsed);
+        let size = |bytes| {
+            if self.decimal {
+                human_bytes_decimal(bytes).plain()
+            } else {
+                human_bytes(bytes).plain()
+            }
+        };
+        let state = ProgressState {
+            pos: self.pos,
+            len: self.len,
+            elapsed,
+            message: self.message.clone(),
+        };
+        template.render_fields(|field| {
+            let name = field.name.as_str();
+            if let Some((_, value)) = self.keys.iter().find(|(key, _)| key == name) {
+                return Some(field.styled(value(&state)).into());
+            }
+            if name == "bar" {
+                return Some(crate::parse_ansi(&self.bar(field)));
+            }
+            let text = match name {
+                "spinner"
+                    if self.finished
+                        || (!self.indeterminate && self.len > 0 && self.pos >= self.len) =>
+                {
+                    theme().icon(Tone::Success).to_string()
+                }
+                "spinner" => {
+                    let frames = symbols().spinner;
+                    frames[self.frame % frames.len()].to_string()
+                }
+                "pos" if self.bytes => size(self.pos),
+                "len" if self.bytes => size(self.len),
+                "pos" => self.pos.to_string(),
+                "len" => self.len.to_string(),
+                "percent" => percent(self.pos, self.len).to_string(),
+                "per_sec" => format!("{}/s", human_count(rate as u64).plain()),
+                "bytes" => size(self.pos),
+                "total_bytes" => size(self.len),
+                "bytes_per_sec" => format!("{}/s", size(rate as u64)),
+                "transfer" => format!(
+                    "{}/{} ({}/s)",
+                    size(self.pos),
+                    size(self.len),
+                    size(rate as u64)
+                ),
+                "elapsed" => human_duration(Duration::from_secs(elapsed.as_secs())).plain(),
+                "eta" => format_eta(self.eta(elapsed)),
+                "msg" => self.message.clone(),
+                _ => return None,
+            };
+            Some(field.styled(text).into())
+        })
+    }
+
+    // The `{bar}` placeholder, filled or bouncing
+    fn bar(&self, field: &Field) -> String {
+        let width = field.width.unwrap_or(self.width);
+        let filled = if field.style == Style::new() {
+            theme().style(Tone::Info).clone()
+        } else {
+            field.style.clone()
+        };
+        let empty = field
+            .rest_style
+            .clone()
+            .unwrap_or_else(|| theme().style(Tone::Muted).clone());
+        if self.indeterminate {
+            bouncing_bar(self.frame, width, &filled, &empty)
+        } else {
+            styled_bar(self.pos, self.len, width, &filled, &empty)
+        }
+    }
+}
+
+impl BarState {
+    // Remembers the position at `elapsed`, keeping just enough samples to
+    // cover the rate window
+    fn sample(&mut self, elapsed: Duration) {
+        if let Some(estimator) = &mut self.estimator {
+            estimator.record(self.pos, elapsed);
+        }
+        self.samples.push_back((elapsed, self.pos));
+        let start = elapsed.saturating_sub(RATE_WINDOW);
+        while self.samples.len() > 1 && self.samples[1].0 <= start {
+            self.samples.pop_front();
+        }
+    }
+
+    // Units per second over the rate window, or since the start until
+    // there are samples to go by
+    fn rate(&self, elapsed: Duration) -> f64 {
+        if let Some(estimator) = &self.estimator {
+            return estimator.rate();
+        }
+        match self.samples.front() {
+            Some(&(then, pos)) if elapsed > then && self.samples.len() > 1 => {
+                rate(self.pos.saturating_sub(pos), elapsed - then)
+            }
+            _ => rate(self.pos, elapsed),
+        }
+    }
+
+    fn eta(&self, elapsed: Duration) -> Option<Duration> {
+        match &self.estimator {
+            Some(estimator) => estimator.eta(self.pos, self.len),
+            None => eta(self.pos, self.len, elapsed),
+        }
+    }
+}
+
+// Units per second so far
+fn rate(pos: u64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 {
+        0.0
+    } else {
+        pos as f64 / seconds
+    }
+}
+
+pub(crate) fn percent(pos: u64, len: u64) -> u64 {
+    if len == 0 {
+        0
+    } else {
+        (pos.min(len) as u128 * 100 / len as u128) as u64
+    }
+}
+
+// Time left at the average rate so far, unknown until something has moved
+fn eta(pos: u64, len: u64, elapsed: Duration) -> Option<Duration> {
+    if pos == 0 || len == 0 {
+        return None;
+    }
+    let left = len.saturating_sub(pos) as f64;
+    // Too far off to hold in a `Duration` reads as unknown
+    Duration::try_from_secs_f64(elapsed.as_secs_f64() * left / pos as f64).ok()
+}
+
+// Whole seconds, rounded up so the last second doesn't read `0s`
+fn format_eta(eta: Option<Duration>) -> String {
+    match eta {
+        None => "?".to_string(),
+        Some(eta) if eta.is_zero() => "0s".to_string(),
+        Some(eta) => {
+            let seconds = eta.as_secs() + u64::from(eta.subsec_nanos() > 0);
+            human_duration(Duration::from_secs(seconds)).plain()
+        }
+    }
+}
+
+pub(crate) fn bar(pos: u64, len: u64, width: usize) -> String {
+    let theme = theme();
+    styled_bar(
+        pos,
+        len,
+        width,
+        theme.style(Tone::Info),
+        theme.style(Tone::Muted),
+    )
+}
+
+fn styled_bar(pos: u64, len: u64, width: usize, filled: &Style, empty: &Style) -> String {
+    let done = if len == 0 {
+        0
+    } else {
+        (pos.min(len) as u128 * width as u128 / len as u128) as usize
+    };
+    let symbols = symbols();
+    format!(
+        "{}{}",
+        Console::new_with_style(symbols.block.to_string().repeat(done), filled.clone()),
+        Console::new_with_style(
+            symbols.border.horizontal.repeat(width - done),
+            empty.clone()
+        ),
+    )
+}
+
+// A segment a fifth of the bar wide moving a column per frame, turning
+// back at each end
+fn bouncing_bar(frame: usize, width: usize, moving: &Style, rest: &Style) -> String {
+    let segment = (width / 5).max(1).min(width);
+    let travel = width - segment;
+    let offset = if travel == 0 {
+        0
+    } else {
+        let step = frame % (2 * travel);
+        if step > travel {
+            2 * travel - step
+        } else {
+            step
+        }
+    };
+    let symbols = symbols();
+    format!(
+        "{}{}{}",
+        Console::new_with_style(symbols.border.horizontal.repeat(offset), rest.clone()),
+        Console::new_with_style(symbols.block.to_string().repeat(segment), moving.clone()),
+        Console::new_with_style(
+            symbols.border.horizontal.repeat(travel - offset),
+            rest.clone()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+    use crate::{ManualClock, PhasedEstimator};
+
+    fn rendered(bar: &ProgressBar, elapsed: Duration) -> String {
+        strip_ansi(&bar.lock().render(elapsed).to_string())
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn test_bar_and_percent() {
+        assert_eq!(strip_ansi(&bar(5, 10, 10)), "█████─────");
+        assert_eq!(strip_ansi(&bar(15, 10, 4)), "████");
+        assert_eq!(strip_ansi(&bar(3, 0, 4)), "────");
+        assert_eq!(percent(1, 3), 33);
+        assert_eq!(percent(7, 0), 0);
+    }
+
+    #[test]
+    fn test_rate_and_eta() {
+        assert_eq!(rate(500, Duration::from_secs(2)), 250.0);
+        assert_eq!(rate(500, Duration::ZERO), 0.0);
+        assert_eq!(eta(0, 100, Duration::from_secs(5)), None);
+        assert_eq!(
+            eta(25, 100, Duration::from_secs(10)),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(format_eta(None), "?");
+        assert_eq!(format_eta(Some(Duration::from_millis(200))), "1s");
+        assert_eq!(format_eta(Some(Duration::from_secs(133))), "2m 13s");
+    }
+
+    #[test]
+    fn test_eta_past_duration_range_is_unknown() {
+        let clock = ManualClock::new();
+        let out = CaptureWriter::new();
+        let bar = ProgressBar::with_writer(out.clone(), u64::MAX)
+            .clock(clock.clone())
+            .template("{pos} {eta}".parse().unwrap());
+        clock.advance(Duration::from_secs(10));
+        bar.inc(1);
+        assert_eq!(out.plain(), "1 ?");
+    }
+
+    #[test]
+    fn test_default_layouts() {
+        let bar = ProgressBar::hidden(200).width(10).message("files");
+        bar.set_position(50);
+        assert_eq!(
+            rendered(&bar, Duration::from_secs(5)),
+            format!("{} ██──────── 50/200 25% 15s files", symbols().spinner[0])
+        );
+
+        let bar = ProgressBar::hidden(4096).width(4).bytes();
+        bar.inc(1024);
+        assert_eq!(
+            rendered(&bar, Duration::from_secs(2)),
+            format!("{} █─── 1 KiB/4 KiB 512 B/s 6s", symbols().spinner[0])
+        );
+    }
+
+    #[test]
+    fn test_rate_follows_recent_speed() {
+        let bar = ProgressBar::hidden(10_000_000)
+            .bytes()
+            .decimal_units()
+            .template("{transfer}".parse().unwrap());
+        let mut state = bar.lock();
+        // 1 MB a second for 10 seconds, then 100 kB a second
+        for second in 0..=10 {
+            state.pos = second * 1_000_000;
+            state.sample(Duration::from_secs(second));
+        }
+        for second in 11..=16 {
+            state.pos = 10_000_000 + (second - 10) * 100_000;
+            state.sample(Duration::from_secs(second));
+        }
+        assert_eq!(state.samples.len(), 6);
+        assert_eq!(
+            state.render(Duration::from_secs(16)).plain(),
+            "10.6 MB/10 MB (100 kB/s)"
+        );
+    }
+
+    #[test]
+    fn test_estimator_replaces_rate_and_eta() {
+        let bar = ProgressBar::hidden(1000)
+            .estimator(PhasedEstimator::new([(500, 1.0), (1000, 3.0)]))
+            .template("{per_sec} {eta}".parse().unwrap());
+        let mut state = bar.lock();
+        state.pos = 250;
+        state.sample(Duration::from_secs(10));
+        // 250 light steps left and 500 heavy ones at 25 a second
+        assert_eq!(state.render(Duration::from_secs(10)).plain(), "25/s 1m 10s");
+    }
+
+    #[test]
+    fn test_clock_drives_elapsed_and_throttling() {
+        let clock = ManualClock::new();
+        let out = CaptureWriter::new();
+        let bar = ProgressBar::with_writer(out.clone(), 10)
+            .clock(clock.clone())
+            .template("{pos} {elapsed} {eta}".parse().unwrap());
+        clock.advance(Duration::from_secs(1));
+        bar.inc(1);
+        clock.advance(Duration::from_millis(10));
+        // Too soon after the last frame
+        bar.inc(1);
+        assert_eq!(out.plain(), "1 1s 9s");
+        clock.advance(Duration::from_secs(3));
+        bar.tick();
+        assert_eq!(bar.elapsed(), Duration::from_millis(4010));
+        assert!(out.plain().ends_with("2 4s 17s"));
+    }
+
+    #[test]
+    fn test_bouncing_bar() {
+        let frames: Vec<String> = (0..7)
+            .map(|frame| strip_ansi(&bouncing_bar(frame, 5, &Style::new(), &Style::new())))
+            .collect();
+        assert_eq!(
+            frames,
+            [
+                "█────",
+                "─█───",
+                "──█──",
+                "───█─",
+                "────█",
+                "───█─",
+                "──█──"
+            ]
+        );
+        assert_eq!(bouncing_bar(3, 0, &Style::new(), &Style::new()), "");
+    }
+
+    #[test]
+    fn test_indeterminate_until_length_is_set() {
+        let bar = ProgressBar::hidden(0).width(5).indeterminate();
+        bar.inc(7);
+        bar.lock().frame = 2;
+        assert_eq!(
+            rendered(&bar, Duration::from_secs(3)),
+            format!("{} ──█── 7 3s", symbols().spinner[2])
+        );
+        bar.set_length(14);
+        assert!(!bar.lock().indeterminate);
+        assert_eq!(
+            rendered(&bar, Duration::from_secs(3)),
+            format!("{} ██─── 7/14 50% 3s", symbols().spinner[2])
+        );
+    }
+
+    #[test]
+    fn test_println_above_bar() {
+        let out = CaptureWriter::new();
+        let bar = ProgressBar::with_writer(out.clone(), 2)
+            .width(2)
+            .template("{bar}".parse().unwrap());
+        bar.set_message("");
+        bar.println(Console::new("warning: slow mirror").yellow());
+        bar.inc(1);
+        bar.finish();
+        // The bar, then the line in its place and the bar below it
+        assert_eq!(
+            strip_ansi(&out.contents()),
+            "──\rwarning: slow mirror\n──\r██\n"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_bar_styles_and_custom_keys() {
+        let bar = ProgressBar::hidden(4)
+            .template("{bar:4.green/dim} {done} {msg:8}|".parse().unwrap())
+            .key("done", |state| {
+                format!("{} of {} files", state.pos, state.len)
+            })
+            .message("copy");
+        bar.inc(1);
+        let line = bar.lock().render(Duration::from_secs(1));
+        assert_eq!(line.plain(), "█─── 1 of 4 files copy    |");
+        assert_eq!(line.segments()[0], Console::new("█").green());
+        assert_eq!(line.segments()[1], Console::new("───").dim());
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let bar = ProgressBar::hidden(3).template("{msg}: {pos} of {len}".parse().unwrap());
+        bar.set_message("Linking");
+        bar.inc(2);
+        assert_eq!(rendered(&bar, Duration::from_secs(1)), "Linking: 2 of 3");
+    }
+
+    #[test]
+    fn test_finish_leaves_full_bar() {
+        let out = CaptureWriter::new();
+        let bar = ProgressBar::with_writer(out.clone(), 10).width(4);
+        bar.inc(3);
+        bar.finish();
+        assert!(bar.is_finished());
+        assert_eq!(bar.position(), 10);
+        let output = out.plain();
+        assert!(output.ends_with("████ 10/10 100% 0s \n"), "{output:?}");
+
+        // Nothing is drawn after finishing
+        bar.inc(1);
+        assert_eq!(out.plain(), output);
+    }
+
+    #[test]
+    fn test_suspend_redraws_bar() {
+        let out = CaptureWriter::new();
+        let bar = ProgressBar::with_writer(out.clone(), 4)
+            .width(4)
+            .template("{bar} {pos}/{len}".parse().unwrap());
+        bar.inc(1);
+        bar.suspend(|| out.clone().write_all(b"$ git status\n").unwrap());
+        assert_eq!(out.plain(), "█─── 1/4\r$ git status\n█─── 1/4");
+    }
+
+    #[test]
+    fn test_steady_tick_animates_until_finished() {
+        let out = CaptureWriter::new();
+        let bar =
+            ProgressBar::with_writer(out.clone(), 10).template("{spinner} {pos}".parse().unwrap());
+        bar.enable_steady_tick(Duration::from_millis(10));
+        std::thread::sleep(Duration::from_millis(300));
+        let frames = out.plain();
+        assert!(frames.contains(symbols().spinner[1]), "{frames:?}");
+
+        bar.finish();
+        let finished = out.contents();
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(out.contents(), finished);
+    }
+
+    #[test]
+    fn test_updates_are_throttled() {
+        let out = CaptureWriter::new();
+        let bar = ProgressBar::with_writer(out.clone(), 1000);
+        for _ in 0..100 {
+            bar.inc(1);
+        }
+        // The first update draws; the rest land within the same frame
+        assert_eq!(out.contents().matches("\r\x1b[2K").count(), 0);
+        bar.finish_and_clear();
+        assert_eq!(out.contents().matches("\r\x1b[2K").count(), 1);
+    }
+}
@@ -0,0 +1,119 @@
+use std::io::{self, BufRead, Read, Write};
+
+use crate::ProgressBar;
+
+// Advances a `ProgressBar` by every byte read through it, e.g. a response
+// body being downloaded
+pub struct ProgressReader<R> {
+    inner: R,
+    bar: ProgressBar,
+}
+
+impl<R: Read> ProgressReader<R> {
+    // Switches the bar to counting bytes, with throughput and time left
+    pub fn new(inner: R, bar: ProgressBar) -> Self {
+        bar.count_bytes();
+        ProgressReader { inner, bar }
+    }
+
+    pub fn bar(&self) -> &ProgressBar {
+        &self.bar
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bar.inc(read as u64);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for ProgressReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.bar.inc(amount as u64);
+    }
+}
+
+// Advances a `ProgressBar` by every byte written through it, e.g. a file
+// being copied
+pub struct ProgressWriter<W> {
+    inner: W,
+    bar: ProgressBar,
+}
+
+impl<W: Write> ProgressWriter<W> {
+    // Switches the bar to counting bytes, with throughput and time left
+    pub fn new(inner: W, bar: ProgressBar) -> Self {
+        bar.count_bytes();
+        ProgressWriter { inner, bar }
+    }
+
+    pub fn bar(&self) -> &ProgressBar {
+        &self.bar
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bar.inc(written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl ProgressBar {
+    pub fn wrap_read<R: Read>(&self, inner: R) -> ProgressReader<R> {
+        ProgressReader::new(inner, self.clone())
+    }
+
+    pub fn wrap_write<W: Write>(&self, inner: W) -> ProgressWriter<W> {
+        ProgressWriter::new(inner, self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_counts_bytes() {
+        let data = vec![7u8; 10_000];
+        let bar = ProgressBar::hidden(data.len() as u64);
+        let mut reader = bar.wrap_read(data.as_slice());
+        let mut writer = ProgressWriter::new(Vec::new(), ProgressBar::hidden(0));
+
+        let copied = io::copy(&mut reader, &mut writer).unwrap();
+        assert_eq!(copied, 10_000);
+        assert_eq!(bar.position(), 10_000);
+        assert_eq!(writer.bar().position(), 10_000);
+        assert_eq!(writer.into_inner(), data);
+    }
+
+    #[test]
+    fn test_buf_read_counts_consumed_bytes() {
+        let bar = ProgressBar::hidden(12);
+        let mut reader = bar.wrap_read(io::Cursor::new("one\ntwo\nthree"));
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "one\n");
+        assert_eq!(bar.position(), 4);
+    }
+}
@@ -0,0 +1,225 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Passes writes through to the inner writer while timestamping them, so the
+// session can be exported as an asciinema v2 `.cast` file. Wrap `io::sink()`
+// to record without echoing anything.
+pub struct Recorder<W: Write> {
+    inner: W,
+    start: Instant,
+    // Pauses added with `wait`
+    offset: Duration,
+    timestamp: Option<u64>,
+    width: u16,
+    height: u16,
+    title: Option<String>,
+    events: Vec<(Duration, String)>,
+    // Trailing bytes of a UTF-8 character split across two writes
+    partial: Vec<u8>,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(inner: W) -> Self {
+        Recorder {
+            inner,
+            start: Instant::now(),
+            offset: Duration::ZERO,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs()),
+            width: 80,
+            height: 24,
+            title: None,
+            events: Vec::new(),
+            partial: Vec::new(),
+        }
+    }
+
+    // Terminal size recorded in the cast header, defaults to 80x24
+    pub fn size(mut self, width: u16, height: u16) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    // Records a pause without writing anything, useful for scripted demos
+    pub fn wait(&mut self, duration: Duration) {
+        self.offset += duration;
+    }
+
+    pub fn write_cast<O: Write>(&self, out: &mut O) -> io::Result<()> {
+        write!(
+            out,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}",
+            self.width, self.height
+        )?;
+        if let Some(timestamp) = self.timestamp {
+            write!(out, ", \"timestamp\": {}", timestamp)?;
+        }
+        if let Some(title) = &self.title {
+            write!(out, ", \"title\": \"{}\"", json_escape(title))?;
+        }
+        writeln!(out, "}}")?;
+
+        for (time, data) in &self.events {
+            writeln!(
+                out,
+                "[{:.6}, \"o\", \"{}\"]",
+                time.as_secs_f64(),
+                json_escape(data)
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn to_cast(&self) -> String {
+        let mut cast = Vec::new();
+        self.write_cast(&mut cast)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(cast).expect("cast output is valid UTF-8")
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_cast(&mut file)?;
+        file.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn record(&mut self, time: Duration, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+        let valid = match std::str::from_utf8(&self.partial) {
+            Ok(_) => self.partial.len(),
+            // Keep an incomplete character for the next write
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                let text = String::from_utf8_lossy(&self.partial).into_owned();
+                self.partial.clear();
+                self.push_event(time, text);
+                return;
+            }
+        };
+        let rest = self.partial.split_off(valid);
+        let text = String::from_utf8(std::mem::replace(&mut self.partial, rest))
+            .expect("prefix was validated");
+        self.push_event(time, text);
+    }
+
+    fn push_event(&mut self, time: Duration, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        // Writes landing at the same instant are merged into one event
+        match self.events.last_mut() {
+            Some((last, data)) if *last == time => data.push_str(&text),
+            _ => self.events.push((time, text)),
+        }
+    }
+}
+
+impl<W: Write> Write for Recorder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        let time = self.start.elapsed() + self.offset;
+        self.record(time, &buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\x7f' => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Console;
+
+    fn recorder() -> Recorder<Vec<u8>> {
+        let mut recorder = Recorder::new(Vec::new()).size(100, 30).title("demo");
+        recorder.timestamp = Some(1_700_000_000);
+        recorder
+    }
+
+    #[test]
+    fn test_json_escape() {
+        assert_eq!(json_escape("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+        assert_eq!(json_escape("\x1b[31m"), "\\u001b[31m");
+    }
+
+    #[test]
+    fn test_writes_pass_through() {
+        let mut recorder = Recorder::new(Vec::new());
+        Console::new("hello").write_to(&mut recorder).unwrap();
+        assert_eq!(recorder.get_ref(), b"hello");
+        assert_eq!(recorder.events.len(), 1);
+        assert_eq!(recorder.events[0].1, "hello");
+    }
+
+    #[test]
+    fn test_split_utf8_is_kept_together() {
+        let mut recorder = recorder();
+        let bytes = "✓ ok".as_bytes();
+        recorder.record(Duration::from_millis(1), &bytes[..1]);
+        recorder.record(Duration::from_millis(2), &bytes[1..]);
+
+        assert_eq!(
+            recorder.events,
+            vec![(Duration::from_millis(2), "✓ ok".into())]
+        );
+    }
+
+    #[test]
+    fn test_cast_output() {
+        let mut recorder = recorder();
+        recorder.record(Duration::from_millis(0), b"\x1b[32m$\x1b[0m ");
+        recorder.record(Duration::from_millis(0), b"cargo build");
+        recorder.record(Duration::from_millis(1500), b"\r\nFinished\r\n");
+
+        assert_eq!(
+            recorder.to_cast(),
+            "{\"version\": 2, \"width\": 100, \"height\": 30, \"timestamp\": 1700000000, \"title\": \"demo\"}\n\
+             [0.000000, \"o\", \"\\u001b[32m$\\u001b[0m cargo build\"]\n\
+             [1.500000, \"o\", \"\\r\\nFinished\\r\\n\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_wait_shifts_later_events() {
+        let mut recorder = Recorder::new(io::sink());
+        recorder.wait(Duration::from_secs(2));
+        recorder.write_all(b"later").unwrap();
+        assert!(recorder.events[0].0 >= Duration::from_secs(2));
+    }
+}
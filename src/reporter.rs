@@ -0,0 +1,282 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::Console;
+use crate::draw_target::{DrawTarget, stderr_target};
+use crate::group;
+use crate::indent::LinePrefixer;
+use crate::message::message_line;
+use crate::parse_ansi;
+use crate::theme::{Tone, theme};
+use crate::timestamp::Timestamp;
+
+// How much a command-line tool prints, usually set from `-q` and `-v` flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Verbosity {
+    Quiet,
+    #[default]
+    Normal,
+    Verbose,
+    Debug,
+}
+
+impl Verbosity {
+    // `-q` wins over any number of `-v`s; `-vv` and up is debug
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        }
+    }
+}
+
+// Prints progress messages filtered by verbosity: errors always, warnings
+// and status lines unless quiet, details when verbose and debug output at
+// the debug level. Styling is dropped when color is off, which `new`
+// decides from the stderr target being a terminal and `NO_COLOR`/`CLICOLOR_FORCE`.
+pub struct Reporter<W: Write> {
+    out: W,
+    verbosity: Verbosity,
+    color: bool,
+    dry_run: bool,
+    timestamps: Option<LinePrefixer>,
+}
+
+impl Reporter<DrawTarget> {
+    pub fn new(verbosity: Verbosity) -> Self {
+        let out = stderr_target();
+        let color = color_wanted(out.is_terminal(), |name| {
+            std::env::var_os(name).is_some_and(|v| !v.is_empty())
+        });
+        Self::with_writer(out, verbosity).color(color)
+    }
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn with_writer(out: W, verbosity: Verbosity) -> Self {
+        Reporter {
+            out,
+            verbosity,
+            color: true,
+            dry_run: false,
+            timestamps: None,
+        }
+    }
+
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    // Marks `action` messages as things that would have been done
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    // Stamps every printed line, including each line of a multi-line message
+    pub fn timestamps(mut self, timestamp: Timestamp) -> Self {
+        self.timestamps = Some(LinePrefixer::with(timestamp.prefixer()));
+        self
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    pub fn set_verbosity(&mut self, verbosity: Verbosity) {
+        self.verbosity = verbosity;
+    }
+
+    // Whether messages at `level` are printed
+    pub fn enabled(&self, level: Verbosity) -> bool {
+        level <= self.verbosity
+    }
+
+    pub fn status<T: Display>(&mut self, message: T) -> io::Result<()> {
+        self.emit(Verbosity::Normal, message.to_string())
+    }
+
+    // A change the tool makes, e.g. `removing target/`. In dry-run mode it
+    // is tagged `[dry-run]` and dimmed.
+    pub fn action<T: Display>(&mut self, message: T) -> io::Result<()> {
+        if !self.dry_run {
+            return self.status(message);
+        }
+        let theme = theme();
+        let text = format!(
+            "{} {}",
+            Console::new_with_style("[dry-run]", theme.style(Tone::Warning).clone().bold()),
+            Console::new_with_style(
+                message.to_string(),
+                theme.style(Tone::Muted).clone().italic()
+            )
+        );
+        self.emit(Verbosity::Normal, text)
+    }
+
+    pub fn detail<T: Display>(&mut self, message: T) -> io::Result<()> {
+        let text = Console::new_with_style(message.to_string(), theme().style(Tone::Muted).clone());
+        self.emit(Verbosity::Verbose, text.to_string())
+    }
+
+    pub fn debug<T: Display>(&mut self, message: T) -> io::Result<()> {
+        let text = format!(
+            "{} {}",
+            Console::new_with_style("debug:", theme().style(Tone::Hint).clone()),
+            message
+        );
+        self.emit(Verbosity::Debug, text)
+    }
+
+    pub fn warn<T: Display>(&mut self, message: T) -> io::Result<()> {
+        self.emit(
+            Verbosity::Normal,
+            message_line(Tone::Warning, &message.to_string()),
+        )
+    }
+
+    pub fn error<T: Display>(&mut self, message: T) -> io::Result<()> {
+        self.emit(
+            Verbosity::Quiet,
+            message_line(Tone::Error, &message.to_string()),
+        )
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn emit(&mut self, level: Verbosity, text: String) -> io::Result<()> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+        let mut text = group::prefix_lines(&format!("{}\n", text));
+        if let Some(timestamps) = &mut self.timestamps {
+            let mut stamped = Vec::new();
+            timestamps.process(text.as_bytes(), &mut stamped);
+            text = String::from_utf8_lossy(&stamped).into_owned();
+        }
+        if !self.color {
+            text = parse_ansi(&text).plain();
+        }
+        self.out.write_all(text.as_bytes())
+    }
+}
+
+// Follows https://no-color.org and the `CLICOLOR_FORCE` convention
+fn color_wanted(is_terminal: bool, is_set: impl Fn(&str) -> bool) -> bool {
+    if is_set("CLICOLOR_FORCE") {
+        return true;
+    }
+    is_terminal && !is_set("NO_COLOR")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(verbosity: Verbosity) -> String {
+        let mut reporter = Reporter::with_writer(Vec::new(), verbosity).color(false);
+        reporter.status("building").unwrap();
+        reporter.detail("3 crates").unwrap();
+        reporter.debug("cache hit").unwrap();
+        reporter.warn("slow disk").unwrap();
+        reporter.error("failed").unwrap();
+        String::from_utf8(reporter.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert_eq!(report(Verbosity::Quiet), "✗ error: failed\n");
+        assert_eq!(
+            report(Verbosity::Normal),
+            "building\n⚠ warning: slow disk\n✗ error: failed\n"
+        );
+        assert_eq!(
+            report(Verbosity::Debug),
+            "building\n3 crates\ndebug: cache hit\n⚠ warning: slow disk\n✗ error: failed\n"
+        );
+    }
+
+    #[test]
+    fn test_timestamps_stamp_every_line() {
+        let mut reporter = Reporter::with_writer(Vec::new(), Verbosity::Normal)
+            .color(false)
+            .timestamps(Timestamp::Elapsed);
+        reporter.warn("slow disk\nsee docs").unwrap();
+        let output = String::from_utf8(reporter.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("    0.0"), "{output}");
+        assert!(lines[0].ends_with("s ⚠ warning: slow disk"), "{output}");
+        assert!(lines[1].ends_with("s            see docs"), "{output}");
+    }
+
+    #[test]
+    fn test_dry_run_actions() {
+        let mut reporter = Reporter::with_writer(Vec::new(), Verbosity::Normal)
+            .color(false)
+            .dry_run(true);
+        assert!(reporter.is_dry_run());
+        reporter.action("removing target/").unwrap();
+        reporter.status("done").unwrap();
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            "[dry-run] removing target/\ndone\n"
+        );
+
+        let mut reporter = Reporter::with_writer(Vec::new(), Verbosity::Quiet).color(false);
+        reporter.action("removing target/").unwrap();
+        assert_eq!(reporter.into_inner(), b"");
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_dry_run_styling() {
+        let mut reporter = Reporter::with_writer(Vec::new(), Verbosity::Normal).dry_run(true);
+        reporter.action("x").unwrap();
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            format!(
+                "{} {}\n",
+                Console::new("[dry-run]").yellow().bold(),
+                Console::new("x").dim().italic()
+            )
+        );
+    }
+
+    #[test]
+    fn test_verbosity_from_flags() {
+        assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, 5), Verbosity::Debug);
+        assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+    }
+
+    #[test]
+    fn test_color_wanted() {
+        let env = |vars: &'static [&'static str]| move |name: &str| vars.contains(&name);
+        assert!(color_wanted(true, env(&[])));
+        assert!(!color_wanted(false, env(&[])));
+        assert!(!color_wanted(true, env(&["NO_COLOR"])));
+        assert!(color_wanted(false, env(&["CLICOLOR_FORCE"])));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_detail_is_dimmed_when_colored() {
+        let mut reporter = Reporter::with_writer(Vec::new(), Verbosity::Verbose);
+        reporter.detail("3 crates").unwrap();
+        assert_eq!(
+            String::from_utf8(reporter.into_inner()).unwrap(),
+            format!("{}\n", Console::new("3 crates").dim())
+        );
+    }
+}
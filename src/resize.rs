@@ -0,0 +1,356 @@
+use std::io;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Mutex, MutexGuard};
+
+type Callback = Box<dyn FnMut(usize, usize) + Send>;
+
+#[derive(Default)]
+struct Subscribers {
+    next_id: usize,
+    entries: Vec<(usize, Callback)>,
+    // Removed while their callbacks were running, so they aren't put back
+    removed: Vec<usize>,
+}
+
+impl Subscribers {
+    fn add(&mut self, callback: Callback) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push((id, callback));
+        id
+    }
+
+    fn remove(&mut self, id: usize) {
+        let before = self.entries.len();
+        self.entries.retain(|(entry, _)| *entry != id);
+        if self.entries.len() == before {
+            self.removed.push(id);
+        }
+    }
+
+    // Callbacks run without the lock held, so they may subscribe or drop
+    // watchers themselves
+    fn take(&mut self) -> Vec<(usize, Callback)> {
+        std::mem::take(&mut self.entries)
+    }
+
+    fn restore(&mut self, mut ran: Vec<(usize, Callback)>) {
+        let removed = std::mem::take(&mut self.removed);
+        ran.retain(|(id, _)| !removed.contains(id));
+        ran.append(&mut self.entries);
+        self.entries = ran;
+    }
+}
+
+static SUBSCRIBERS: Mutex<Subscribers> = Mutex::new(Subscribers {
+    next_id: 0,
+    entries: Vec::new(),
+    removed: Vec::new(),
+});
+
+fn subscribers() -> MutexGuard<'static, Subscribers> {
+    SUBSCRIBERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn dispatch(columns: usize, rows: usize) {
+    let mut running = subscribers().take();
+    for (_, callback) in &mut running {
+        callback(columns, rows);
+    }
+    subscribers().restore(running);
+}
+
+// Reports the terminal's new size in columns and rows whenever it's resized,
+// so live output can lay itself out again instead of wrapping badly until
+// its next redraw. Bursts of resizes while a window is dragged are folded
+// together. Dropping the watcher stops the reports. Only unix terminals
+// report resizes; elsewhere creating a watcher fails as unsupported.
+#[derive(Debug)]
+pub struct ResizeWatcher {
+    id: usize,
+}
+
+impl ResizeWatcher {
+    pub fn on_resize<F>(callback: F) -> io::Result<Self>
+    where
+        F: FnMut(usize, usize) + Send + 'static,
+    {
+        listen()?;
+        Ok(ResizeWatcher {
+            id: subscribers().add(Box::new(callback)),
+        })
+    }
+
+    // The sizes arrive on a channel instead, for event loops that already
+    // wait on one
+    pub fn channel() -> io::Result<(Self, Receiver<(usize, usize)>)> {
+        let (sender, receiver) = mpsc::channel();
+        let watcher = Self::on_resize(move |columns, rows| {
+            let _ = sender.send((columns, rows));
+        })?;
+        Ok((watcher, receiver))
+    }
+}
+
+impl Drop for ResizeWatcher {
+    fn drop(&mut self) {
+        subscribers().remove(self.id);
+    }
+}
+
+// Starts the shared watcher thread on first use
+#[cfg(unix)]
+fn listen() -> io::Result<()> {
+    use std::sync::OnceLock;
+
+    static STARTED: OnceLock<Result<(), i32>> = OnceLock::new();
+    STARTED
+        .get_or_init(|| signal::start().map_err(|e| e.raw_os_error().unwrap_or(0)))
+        .map_err(io::Error::from_raw_os_error)
+}
+
+#[cfg(not(unix))]
+fn listen() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "resize events are only supported on unix terminals",
+    ))
+}
+
+// SIGWINCH arrives on a self-pipe: the handler only writes a byte, and a
+// thread reads it and does the work outside signal context
+#[cfg(unix)]
+mod signal {
+    use std::io;
+    use std::sync::OnceLock;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    use crate::terminal::terminal_size;
+
+    pub(super) static PIPE: AtomicI32 = AtomicI32::new(-1);
+    // The disposition before ours; a handler there is called after ours
+    static PREVIOUS: OnceLock<libc::sigaction> = OnceLock::new();
+
+    pub(super) extern "C" fn on_sigwinch(
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        context: *mut libc::c_void,
+    ) {
+        // The interrupted code may be about to read errno, which a failed
+        // `write` here would change
+        let errno = errno_location();
+        let saved = (!errno.is_null()).then(|| unsafe { *errno });
+        let fd = PIPE.load(Ordering::Relaxed);
+        if fd >= 0 {
+            let byte = 1u8;
+            unsafe { libc::write(fd, (&byte as *const u8).cast(), 1) };
+        }
+        if let Some(previous) = PREVIOUS.get() {
+            call_previous(previous, signal, info, context);
+        }
+        if let Some(saved) = saved {
+            unsafe { *errno = saved };
+        }
+    }
+
+    // Runs the handler in `previous`, in the form it was installed with
+    pub(super) fn call_previous(
+        previous: &libc::sigaction,
+        signal: libc::c_int,
+        info: *mut libc::siginfo_t,
+        context: *mut libc::c_void,
+    ) {
+        let handler = previous.sa_sigaction;
+        if handler == libc::SIG_DFL || handler == libc::SIG_IGN {
+            return;
+        }
+        if previous.sa_flags & libc::SA_SIGINFO != 0 {
+            let handler: extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) =
+                unsafe { std::mem::transmute(handler) };
+            handler(signal, info, context);
+        } else {
+            let handler: extern "C" fn(libc::c_int) = unsafe { std::mem::transmute(handler) };
+            handler(signal);
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "redox"))]
+    fn errno_location() -> *mut libc::c_int {
+        unsafe { libc::__errno_location() }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"))]
+    fn errno_location() -> *mut libc::c_int {
+        unsafe { libc::__errno() }
+    }
+
+    #[cfg(any(
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "dragonfly"
+    ))]
+    fn errno_location() -> *mut libc::c_int {
+        unsafe { libc::__error() }
+    }
+
+    // Elsewhere errno is left as the handler finds it
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "redox",
+        target_os = "android",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "dragonfly"
+    )))]
+    fn errno_location() -> *mut libc::c_int {
+        std::ptr::null_mut()
+    }
+
+    pub(super) fn start() -> io::Result<()> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let [read, write] = fds;
+        unsafe {
+            libc::fcntl(read, libc::F_SETFD, libc::FD_CLOEXEC);
+            libc::fcntl(write, libc::F_SETFD, libc::FD_CLOEXEC);
+            // A full pipe already means a resize is pending
+            libc::fcntl(write, libc::F_SETFL, libc::O_NONBLOCK);
+        }
+        PIPE.store(write, Ordering::Relaxed);
+
+        // Kept before ours goes in, so no resize misses the previous handler
+        let mut previous: libc::sigaction = unsafe { std::mem::zeroed() };
+        if unsafe { libc::sigaction(libc::SIGWINCH, std::ptr::null(), &mut previous) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let _ = PREVIOUS.set(previous);
+
+        let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+        action.sa_sigaction = on_sigwinch as *const () as libc::sighandler_t;
+        // Takes the extra arguments a previous handler may want passed on
+        action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+        if unsafe { libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        std::thread::Builder::new()
+            .name("pretty-console-resize".into())
+            .spawn(move || watch(read))?;
+        Ok(())
+    }
+
+    fn watch(read: libc::c_int) {
+        let mut buffer = [0u8; 64];
+        loop {
+            let count = unsafe { libc::read(read, buffer.as_mut_ptr().cast(), buffer.len()) };
+            if count < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+            if let Some((columns, rows)) = terminal_size() {
+                super::dispatch(columns, rows);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn recorder(log: &Arc<Mutex<Vec<String>>>, name: &'static str) -> Callback {
+        let log = Arc::clone(log);
+        Box::new(move |columns, rows| log.lock().unwrap().push(format!("{name} {columns}x{rows}")))
+    }
+
+    #[test]
+    fn test_subscribers() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut subscribers = Subscribers::default();
+        let first = subscribers.add(recorder(&log, "a"));
+        subscribers.add(recorder(&log, "b"));
+
+        // One unsubscribes and another subscribes while callbacks run
+        let mut running = subscribers.take();
+        for (_, callback) in &mut running {
+            callback(100, 30);
+        }
+        subscribers.remove(first);
+        subscribers.add(recorder(&log, "c"));
+        subscribers.restore(running);
+
+        for (_, callback) in &mut subscribers.take() {
+            callback(80, 24);
+        }
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["a 100x30", "b 100x30", "b 80x24", "c 80x24"]
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_handler_keeps_errno() {
+        use std::sync::atomic::Ordering;
+
+        // A descriptor that isn't open, so the handler's write fails
+        let pipe = signal::PIPE.swap(1 << 20, Ordering::Relaxed);
+        unsafe { *libc::__errno_location() = libc::EINTR };
+        signal::on_sigwinch(libc::SIGWINCH, std::ptr::null_mut(), std::ptr::null_mut());
+        let errno = unsafe { *libc::__errno_location() };
+        signal::PIPE.store(pipe, Ordering::Relaxed);
+        assert_eq!(errno, libc::EINTR);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_previous_handlers_are_chained() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static PLAIN: AtomicUsize = AtomicUsize::new(0);
+        static WITH_INFO: AtomicUsize = AtomicUsize::new(0);
+        extern "C" fn plain(_: libc::c_int) {
+            PLAIN.fetch_add(1, Ordering::Relaxed);
+        }
+        extern "C" fn with_info(_: libc::c_int, info: *mut libc::siginfo_t, _: *mut libc::c_void) {
+            WITH_INFO.store(info as usize, Ordering::Relaxed);
+        }
+
+        let mut previous: libc::sigaction = unsafe { std::mem::zeroed() };
+        previous.sa_sigaction = plain as *const () as libc::sighandler_t;
+        signal::call_previous(
+            &previous,
+            libc::SIGWINCH,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(PLAIN.load(Ordering::Relaxed), 1);
+
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        previous.sa_sigaction = with_info as *const () as libc::sighandler_t;
+        previous.sa_flags = libc::SA_SIGINFO;
+        signal::call_previous(&previous, libc::SIGWINCH, &mut info, std::ptr::null_mut());
+        assert_eq!(
+            WITH_INFO.load(Ordering::Relaxed),
+            &mut info as *mut _ as usize
+        );
+
+        // Default and ignored dispositions have nothing to call
+        previous.sa_sigaction = libc::SIG_IGN;
+        signal::call_previous(
+            &previous,
+            libc::SIGWINCH,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(PLAIN.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::terminal::{Background, terminal_background};
+use crate::{Color, Console, Style, StyledText};
+
+// Alternating row styles for making wide tables and listings easier to
+// follow. The default stripe is a subtle gray chosen for the terminal's
+// background; a row's own styles are layered on top of its stripe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rows {
+    even: Style,
+    odd: Style,
+    width: Option<usize>,
+}
+
+impl Rows {
+    pub fn new() -> Self {
+        Self::for_background(terminal_background())
+    }
+
+    pub fn for_background(background: Background) -> Self {
+        let stripe = match background {
+            Background::Dark => Color::Named(236),
+            Background::Light => Color::Named(254),
+        };
+        Rows {
+            even: Style::new(),
+            odd: Style::new().bg(stripe),
+            width: None,
+        }
+    }
+
+    // Style of the first, third, ... row
+    pub fn even(mut self, style: Style) -> Self {
+        self.even = style;
+        self
+    }
+
+    pub fn odd(mut self, style: Style) -> Self {
+        self.odd = style;
+        self
+    }
+
+    // Pads every row to `width` columns so stripes run the full width
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    // The stripe for the row at `index`, counting from zero
+    pub fn style(&self, index: usize) -> &Style {
+        if index.is_multiple_of(2) {
+            &self.even
+        } else {
+            &self.odd
+        }
+    }
+
+    pub fn apply<I>(&self, lines: I) -> impl Iterator<Item = StyledText>
+    where
+        I: IntoIterator,
+        I::Item: Into<StyledText>,
+    {
+        lines
+            .into_iter()
+            .enumerate()
+            .map(move |(index, line)| self.stripe(index, &line.into()))
+    }
+
+    pub fn stripe(&self, index: usize, line: &StyledText) -> StyledText {
+        let stripe = self.style(index);
+        let mut striped: StyledText = line
+            .segments()
+            .iter()
+            .map(|segment| segment.with_style(stripe.merge(segment.style())))
+            .collect();
+        let padding = self.width.unwrap_or(0).saturating_sub(striped.width());
+        if padding > 0 {
+            striped.push(Console::repeat(' ', padding).with_style(stripe.clone()));
+        }
+        striped
+    }
+}
+
+impl Default for Rows {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alternating_styles() {
+        let rows = Rows::for_background(Background::Dark);
+        assert_eq!(rows.style(0), &Style::new());
+        assert_eq!(rows.style(1).background, Some(Color::Named(236)));
+        assert_eq!(
+            Rows::for_background(Background::Light).style(3).background,
+            Some(Color::Named(254))
+        );
+    }
+
+    #[test]
+    fn test_apply_keeps_row_styles_and_pads() {
+        let rows = Rows::for_background(Background::Dark)
+            .odd(Style::new().bg(Color::BLUE))
+            .width(6);
+        let striped: Vec<StyledText> = rows
+            .apply(vec![
+                StyledText::from("one"),
+                StyledText::from(Console::new("two").red()),
+            ])
+            .collect();
+
+        assert_eq!(striped[0].plain(), "one   ");
+        assert_eq!(striped[0].segments()[1].style(), &Style::new());
+
+        let second = &striped[1].segments()[0];
+        assert_eq!(second.style().foreground, Some(Color::RED));
+        assert_eq!(second.style().background, Some(Color::BLUE));
+        assert_eq!(
+            striped[1].segments()[1].style().background,
+            Some(Color::BLUE)
+        );
+    }
+
+    #[test]
+    fn test_row_background_wins() {
+        let rows = Rows::for_background(Background::Dark);
+        let line = StyledText::from(Console::new("x").on_red());
+        assert_eq!(
+            rows.stripe(1, &line).segments()[0].style().background,
+            Some(Color::RED)
+        );
+    }
+}
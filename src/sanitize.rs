@@ -0,0 +1,78 @@
+use crate::{Console, Style};
+
+// Makes untrusted text safe to print: control characters (other than newline
+// and tab) and bidirectional overrides are replaced with a visible `\u{..}`
+// escape, so embedded sequences can't retitle the terminal, move the cursor
+// or fake styled output
+pub fn sanitize(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if is_unsafe(ch) {
+            sanitized.push_str(&format!("\\u{{{:x}}}", ch as u32));
+        } else {
+            sanitized.push(ch);
+        }
+    }
+    sanitized
+}
+
+fn is_unsafe(ch: char) -> bool {
+    match ch {
+        '\n' | '\t' => false,
+        // Explicit embeddings, overrides and isolates can reorder the
+        // displayed text
+        '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => true,
+        c => c.is_control(),
+    }
+}
+
+impl Console {
+    // Untrusted text is shown as written, shortcodes included
+    pub fn new_sanitized<T: AsRef<str>>(text: T) -> Self {
+        Console::new_with_style(sanitize(text.as_ref()), Style::new())
+    }
+
+    // Escapes control characters in the text; styling applied through the
+    // builder methods is unaffected
+    pub fn sanitize(self) -> Self {
+        Console {
+            text: sanitize(&self.text),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_escapes_control_characters() {
+        assert_eq!(
+            sanitize("\x1b]0;pwned\x07name\x1b[2K\r"),
+            "\\u{1b}]0;pwned\\u{7}name\\u{1b}[2K\\u{d}"
+        );
+        assert_eq!(sanitize("a\u{9b}31m"), "a\\u{9b}31m");
+        assert_eq!(sanitize("abc\u{202e}fed"), "abc\\u{202e}fed");
+    }
+
+    #[test]
+    fn test_sanitize_keeps_ordinary_text() {
+        assert_eq!(sanitize("line one\n\tline two ✓"), "line one\n\tline two ✓");
+    }
+
+    #[test]
+    fn test_sanitized_console_keeps_styling() {
+        let console = Console::new("\x1b[31mfake\x1b[0m").green().sanitize();
+        let plain = Console::new_sanitized("\x1b[31mfake\x1b[0m");
+        assert_eq!(plain.text, "\\u{1b}[31mfake\\u{1b}[0m");
+
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(
+            console.to_string(),
+            "\x1b[38;5;2m\\u{1b}[31mfake\\u{1b}[0m\x1b[0m"
+        );
+        #[cfg(feature = "no-color")]
+        assert_eq!(console.to_string(), "\\u{1b}[31mfake\\u{1b}[0m");
+    }
+}
@@ -0,0 +1,406 @@
+use std::io::{self, Write};
+
+use crate::draw_target::stderr_target;
+use crate::events::{self, Event, Key, MouseButton, MouseCapture, MouseEvent, MouseKind, RawMode};
+use crate::live::LiveRegion;
+use crate::symbols::symbols;
+use crate::terminal::query_cursor_row;
+use crate::transcript::finish_prompt;
+use crate::{Color, Console, Style};
+
+#[derive(Debug, Clone)]
+pub struct Select {
+    prompt: String,
+    items: Vec<String>,
+    default: usize,
+    max_length: Option<usize>,
+    highlight: Style,
+    transcript: bool,
+    mouse: bool,
+}
+
+impl Select {
+    pub fn new<T: Into<String>>(prompt: T) -> Self {
+        Select {
+            prompt: prompt.into(),
+            items: Vec::new(),
+            default: 0,
+            max_length: None,
+            highlight: Style::new().fg(Color::CYAN).bold(),
+            transcript: true,
+            mouse: false,
+        }
+    }
+
+    pub fn items<T: ToString>(mut self, items: &[T]) -> Self {
+        self.items.extend(items.iter().map(ToString::to_string));
+        self
+    }
+
+    pub fn item<T: Into<String>>(mut self, item: T) -> Self {
+        self.items.push(item.into());
+        self
+    }
+
+    pub fn default(mut self, index: usize) -> Self {
+        self.default = index;
+        self
+    }
+
+    // Shows at most `max_length` items at a time, paging through the rest
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn highlight(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    // Leaves a one-line summary of the answer in place of the prompt,
+    // `? Prompt · answer`; on by default
+    pub fn transcript(mut self, enabled: bool) -> Self {
+        self.transcript = enabled;
+        self
+    }
+
+    // Lets the user choose an item by clicking it and move with the scroll
+    // wheel; off by default, since it stops the terminal selecting text.
+    // Clicks are ignored if the terminal won't say where the prompt is.
+    pub fn mouse(mut self, enabled: bool) -> Self {
+        self.mouse = enabled;
+        self
+    }
+
+    // Returns the chosen index, or `None` if the user pressed escape
+    pub fn interact(&self) -> io::Result<Option<usize>> {
+        let _raw = RawMode::enable()?;
+        let _mouse = self.mouse.then(MouseCapture::enable).transpose()?;
+        let mut region =
+            LiveRegion::new(stderr_target()).columns(crate::terminal::terminal_width());
+        region.hide_cursor()?;
+        let result = if self.mouse {
+            self.interact_on(&mut region, events::read_event, query_cursor_row)
+        } else {
+            self.interact_on(&mut region, || events::read_key().map(Event::Key), || None)
+        };
+        region.show_cursor()?;
+        result
+    }
+
+    // `locate` gives the screen row of the cursor after the first draw,
+    // which places the items for mouse clicks
+    fn interact_on<W, F, L>(
+        &self,
+        region: &mut LiveRegion<W>,
+        mut read_event: F,
+        locate: L,
+    ) -> io::Result<Option<usize>>
+    where
+        W: Write,
+        F: FnMut() -> io::Result<Event>,
+        L: FnOnce() -> Option<usize>,
+    {
+        if self.items.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "select prompt has no items",
+            ));
+        }
+
+        let len = self.items.len();
+        let page = self.max_length.filter(|&size| size > 0).unwrap_or(len);
+        let mut cursor = self.default.min(len - 1);
+        let mut locate = Some(locate);
+        let mut top = None;
+
+        loop {
+            let lines = self.render(cursor);
+            let height = lines.len();
+            region.draw(lines)?;
+            if let Some(locate) = locate.take() {
+                top = locate().and_then(|row| row.checked_sub(height - 1));
+            }
+            let key = match read_event()? {
+                Event::Key(key) => key,
+                Event::Mouse(mouse) => {
+                    let shown = page_range(cursor, len, self.max_length);
+                    mouse_key(mouse, top, shown, &mut cursor, Key::Enter)
+                }
+                _ => continue,
+            };
+            match key {
+                Key::Up | Key::Char('k') => cursor = (cursor + len - 1) % len,
+                Key::Down | Key::Char('j') | Key::Tab => cursor = (cursor + 1) % len,
+                Key::Left => cursor = cursor.saturating_sub(page),
+                Key::Right => cursor = (cursor + page).min(len - 1),
+                Key::Enter => {
+                    finish_prompt(region, self.transcript, &self.prompt, &self.items[cursor])?;
+                    return Ok(Some(cursor));
+                }
+                Key::Escape | Key::Char('q') => {
+                    region.clear()?;
+                    return Ok(None);
+                }
+                Key::CtrlC => {
+                    region.clear()?;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&self, cursor: usize) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{} {}",
+            Console::new("?").green().bold(),
+            Console::new(self.prompt.as_str()).bold()
+        )];
+
+        let (start, end) = page_range(cursor, self.items.len(), self.max_length);
+        for (i, item) in self.items.iter().enumerate().take(end).skip(start) {
+            if i == cursor {
+                lines.push(format!(
+                    "{} {}",
+                    Console::new_with_style(symbols().pointer, self.highlight.clone()),
+                    Console::new_with_style(item.as_str(), self.highlight.clone())
+                ));
+            } else {
+                lines.push(format!("  {}", item));
+            }
+        }
+
+        if let Some(size) = self.max_length.filter(|_| end - start < self.items.len()) {
+            let pages = self.items.len().div_ceil(size);
+            lines.push(format!(
+                "  {}",
+                Console::new(format!(
+                    "page {}/{} (←/→ to change page)",
+                    cursor / size + 1,
+                    pages
+                ))
+                .dim()
+            ));
+        }
+
+        lines
+    }
+}
+
+// Turns the mouse into keys the prompts already handle: the wheel moves
+// the cursor, and a left click on one of the items shown, `start..end`
+// below a one-line header at screen row `top`, moves the cursor there and
+// then acts as `click`
+pub(crate) fn mouse_key(
+    mouse: MouseEvent,
+    top: Option<usize>,
+    (start, end): (usize, usize),
+    cursor: &mut usize,
+    click: Key,
+) -> Key {
+    match mouse.kind {
+        MouseKind::ScrollUp => Key::Up,
+        MouseKind::ScrollDown => Key::Down,
+        MouseKind::Press(MouseButton::Left) => {
+            let item = top
+                .and_then(|top| mouse.row.checked_sub(top + 1))
+                .map(|offset| start + offset)
+                .filter(|&item| item < end);
+            match item {
+                Some(item) => {
+                    *cursor = item;
+                    click
+                }
+                None => Key::Unknown,
+            }
+        }
+        _ => Key::Unknown,
+    }
+}
+
+// The slice of items shown when a prompt only displays one page at a time
+pub(crate) fn page_range(cursor: usize, len: usize, page_size: Option<usize>) -> (usize, usize) {
+    match page_size {
+        Some(size) if size > 0 && size < len => {
+            let start = cursor / size * size;
+            (start, (start + size).min(len))
+        }
+        _ => (0, len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(select: &Select, keys: &[Key]) -> io::Result<Option<usize>> {
+        let events: Vec<Event> = keys.iter().copied().map(Event::Key).collect();
+        run_events(select, &events)
+    }
+
+    // Drawn with its last line on screen row 10
+    fn run_events(select: &Select, events: &[Event]) -> io::Result<Option<usize>> {
+        let mut events = events.iter().cloned();
+        let mut region = LiveRegion::new(Vec::new());
+        select.interact_on(
+            &mut region,
+            || Ok(events.next().expect("ran out of events")),
+            || Some(10),
+        )
+    }
+
+    fn mouse(kind: MouseKind, row: usize) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            column: 4,
+            row,
+        })
+    }
+
+    #[test]
+    fn test_select_enter_returns_cursor() {
+        let select = Select::new("Pick a region").items(&["us-east", "eu-west", "ap-south"]);
+
+        assert_eq!(run(&select, &[Key::Enter]).unwrap(), Some(0));
+        assert_eq!(
+            run(&select, &[Key::Down, Key::Down, Key::Enter]).unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            run(&select, &[Key::Down, Key::Up, Key::Enter]).unwrap(),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_select_wraps_around() {
+        let select = Select::new("Pick").items(&["a", "b", "c"]);
+
+        assert_eq!(run(&select, &[Key::Up, Key::Enter]).unwrap(), Some(2));
+        assert_eq!(
+            run(&select, &[Key::Down, Key::Down, Key::Down, Key::Enter]).unwrap(),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_select_default_and_cancel() {
+        let select = Select::new("Pick").items(&["a", "b", "c"]).default(1);
+
+        assert_eq!(run(&select, &[Key::Enter]).unwrap(), Some(1));
+        assert_eq!(run(&select, &[Key::Escape]).unwrap(), None);
+
+        let err = run(&select, &[Key::CtrlC]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+
+    #[test]
+    fn test_select_leaves_transcript() {
+        let select = Select::new("Pick a region").items(&["us-east", "eu-west"]);
+        let mut region = LiveRegion::new(Vec::new());
+        let mut keys = [Key::Down, Key::Enter].into_iter();
+        select
+            .interact_on(
+                &mut region,
+                || Ok(Event::Key(keys.next().unwrap())),
+                || None,
+            )
+            .unwrap();
+        let output = crate::testing::strip_ansi(&String::from_utf8(region.into_inner()).unwrap());
+        assert!(
+            output.ends_with("? Pick a region · eu-west\n"),
+            "{output:?}"
+        );
+    }
+
+    #[test]
+    fn test_select_mouse() {
+        // Header on row 7, items on rows 8 to 10
+        let select = Select::new("Pick").items(&["a", "b", "c"]);
+        let click = |row| mouse(MouseKind::Press(MouseButton::Left), row);
+
+        assert_eq!(run_events(&select, &[click(9)]).unwrap(), Some(1));
+        assert_eq!(run_events(&select, &[click(10)]).unwrap(), Some(2));
+        assert_eq!(
+            run_events(
+                &select,
+                &[
+                    click(7),
+                    click(11),
+                    mouse(MouseKind::Press(MouseButton::Right), 9),
+                    Event::Key(Key::Enter)
+                ]
+            )
+            .unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            run_events(
+                &select,
+                &[
+                    mouse(MouseKind::ScrollDown, 0),
+                    mouse(MouseKind::ScrollDown, 0),
+                    mouse(MouseKind::ScrollUp, 0),
+                    Event::Key(Key::Enter)
+                ]
+            )
+            .unwrap(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_select_paging() {
+        let items: Vec<String> = (0..10).map(|i| format!("item {}", i)).collect();
+        let select = Select::new("Pick").items(&items).max_length(4);
+
+        assert_eq!(run(&select, &[Key::Right, Key::Enter]).unwrap(), Some(4));
+        assert_eq!(
+            run(&select, &[Key::Right, Key::Right, Key::Right, Key::Enter]).unwrap(),
+            Some(9)
+        );
+        assert_eq!(
+            run(&select, &[Key::Right, Key::Left, Key::Enter]).unwrap(),
+            Some(0)
+        );
+
+        // Only the current page is rendered, plus the page indicator
+        assert_eq!(select.render(5).len(), 1 + 4 + 1);
+        assert_eq!(select.render(9).len(), 1 + 2 + 1);
+        assert_eq!(crate::testing::strip_ansi(&select.render(5)[1]), "  item 4");
+    }
+
+    #[test]
+    fn test_select_paged_mouse() {
+        let items: Vec<String> = (0..10).map(|i| format!("item {}", i)).collect();
+        let select = Select::new("Pick").items(&items).max_length(4).default(4);
+        let click = |row| mouse(MouseKind::Press(MouseButton::Left), row);
+
+        // The second page, drawn with its page indicator on row 10, so the
+        // header is on row 5 and items 4 to 7 are on rows 6 to 9
+        assert_eq!(run_events(&select, &[click(7)]).unwrap(), Some(5));
+    }
+
+    #[test]
+    fn test_select_without_items() {
+        let err = run(&Select::new("Pick"), &[]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_page_range() {
+        assert_eq!(page_range(3, 10, None), (0, 10));
+        assert_eq!(page_range(3, 10, Some(20)), (0, 10));
+        assert_eq!(page_range(3, 10, Some(4)), (0, 4));
+        assert_eq!(page_range(4, 10, Some(4)), (4, 8));
+        assert_eq!(page_range(9, 10, Some(4)), (8, 10));
+    }
+
+    #[test]
+    fn test_select_render() {
+        let select = Select::new("Pick a region").item("us-east").item("eu-west");
+        insta::assert_yaml_snapshot!(select.render(1));
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::draw_target::{DrawTarget, stderr_target};
+use crate::theme::{Tone, theme};
+use crate::{Console, ProgressBar, StyledText};
+
+const TEMPLATE: &str = "{spinner:info} {msg}";
+const TICK: Duration = Duration::from_millis(80);
+
+// A spinner and a message for work with nothing to count, redrawn in place,
+// that ends on a line saying how it went. Clones share the same spinner.
+//
+//   ⠹ Resolving dependencies
+//   ✓ Resolved 214 packages
+//   ⚠ Resolved with 2 yanked versions
+//   ✗ Couldn't reach the registry
+#[derive(Clone)]
+pub struct Spinner {
+    pub(crate) bar: ProgressBar,
+}
+
+impl Spinner {
+    // Spins on stderr from a background thread when it's a terminal, and
+    // stays hidden otherwise
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Self::with_target(stderr_target(), message)
+    }
+
+    // Spins on `target` from a background thread if live components draw
+    // there, and stays hidden otherwise
+    pub fn with_target<T: Into<String>>(target: DrawTarget, message: T) -> Self {
+        let spinner = Self::from_bar(ProgressBar::with_target(target, 0), message);
+        spinner.bar.enable_steady_tick(TICK);
+        spinner
+    }
+
+    // Moves only on `tick` and message changes
+    pub fn with_writer<W: Write + Send + 'static, T: Into<String>>(out: W, message: T) -> Self {
+        Self::from_bar(ProgressBar::with_writer(out, 0), message)
+    }
+
+    // Draws nothing until it finishes, then writes the final line to stderr
+    pub fn hidden<T: Into<String>>(message: T) -> Self {
+        Self::from_bar(ProgressBar::hidden(0), message)
+    }
+
+    fn from_bar<T: Into<String>>(bar: ProgressBar, message: T) -> Self {
+        let bar = bar
+            .indeterminate()
+            .template(TEMPLATE.parse().expect("spinner template"))
+            .message(message);
+        bar.tick();
+        Spinner { bar }
+    }
+
+    // Reads the time from `clock` instead of the system, e.g. a
+    // `ManualClock` in tests
+    pub fn clock<C: Clock + 'static>(self, clock: C) -> Self {
+        Spinner {
+            bar: self.bar.clock(clock),
+        }
+    }
+
+    pub fn set_message<T: Into<String>>(&self, message: T) {
+        self.bar.set_message(message);
+    }
+
+    pub fn tick(&self) {
+        self.bar.tick();
+    }
+
+    pub fn enable_steady_tick(&self, interval: Duration) {
+        self.bar.enable_steady_tick(interval);
+    }
+
+    // Prints `line` for good above the spinner
+    pub fn println<T: Display>(&self, line: T) {
+        self.bar.println(line);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.bar.is_finished()
+    }
+
+    pub fn finish_ok<T: Display>(&self, message: T) {
+        self.finish(Tone::Success, message);
+    }
+
+    pub fn finish_warn<T: Display>(&self, message: T) {
+        self.finish(Tone::Warning, message);
+    }
+
+    pub fn finish_fail<T: Display>(&self, message: T) {
+        self.finish(Tone::Error, message);
+    }
+
+    pub fn finish_and_clear(&self) {
+        self.bar.finish_and_clear();
+    }
+
+    // Swaps the frame for the tone's icon and colors the message to match
+    fn finish<T: Display>(&self, tone: Tone, message: T) {
+        let theme = theme();
+        let style = theme.style(tone).clone();
+        let line = StyledText::new()
+            .then(Console::new_with_style(
+                theme.icon(tone),
+                theme.labeled_style(tone),
+            ))
+            .then(Console::new_with_style(format!(" {}", message), style));
+        self.bar.finish_with_line(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+
+    #[test]
+    fn test_finishes() {
+        for (tone, expected) in [
+            (Tone::Success, "✓ done"),
+            (Tone::Warning, "⚠ done"),
+            (Tone::Error, "✗ done"),
+        ] {
+            let out = CaptureWriter::new();
+            let spinner = Spinner::with_writer(out.clone(), "Working");
+            assert!(strip_ansi(&out.contents()).contains("Working"));
+            spinner.finish(tone, "done");
+            assert!(spinner.is_finished());
+            assert!(strip_ansi(&out.contents()).ends_with(&format!("{}\n", expected)));
+            // Later finishes and updates leave the line alone
+            let before = out.contents();
+            spinner.finish_fail("again");
+            spinner.set_message("more");
+            assert_eq!(out.contents(), before);
+        }
+    }
+
+    #[test]
+    fn test_finish_colors_message() {
+        let out = CaptureWriter::new();
+        let spinner = Spinner::with_writer(out.clone(), "Fetching");
+        spinner.finish_fail("Couldn't reach the registry");
+        assert!(
+            out.contents().contains(
+                &Console::new(" Couldn't reach the registry")
+                    .red()
+                    .to_string()
+            )
+        );
+    }
+}
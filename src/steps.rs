@@ -0,0 +1,105 @@
+use std::fmt::{self, Display};
+
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::{Color, Console, Style};
+
+// A breadcrumb of stages such as `[2/3] fetch › build › deploy`, with past
+// stages dimmed, the current one highlighted and the rest grey
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Steps {
+    names: Vec<String>,
+    current: usize,
+    separator: String,
+    counter: bool,
+}
+
+impl Steps {
+    pub fn new<I, T>(names: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Steps {
+            names: names.into_iter().map(Into::into).collect(),
+            current: 0,
+            separator: format!(" {} ", symbols().separator),
+            counter: true,
+        }
+    }
+
+    // Zero-based index of the active stage; past the end means all are done
+    pub fn current(mut self, current: usize) -> Self {
+        self.current = current;
+        self
+    }
+
+    pub fn separator<T: Into<String>>(mut self, separator: T) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    // Shows the `[2/3]` position in front, on by default
+    pub fn counter(mut self, counter: bool) -> Self {
+        self.counter = counter;
+        self
+    }
+}
+
+impl Display for Steps {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let muted = theme.style(Tone::Muted);
+
+        if self.counter && !self.names.is_empty() {
+            let position = (self.current + 1).min(self.names.len());
+            let counter = format!("[{}/{}]", position, self.names.len());
+            write!(f, "{} ", Console::new_with_style(counter, muted.clone()))?;
+        }
+
+        for (i, name) in self.names.iter().enumerate() {
+            if i > 0 {
+                write!(
+                    f,
+                    "{}",
+                    Console::new_with_style(self.separator.as_str(), muted.clone())
+                )?;
+            }
+            let style = match i.cmp(&self.current) {
+                std::cmp::Ordering::Less => muted.clone(),
+                std::cmp::Ordering::Equal => theme.style(Tone::Info).clone().bold(),
+                std::cmp::Ordering::Greater => Style::new().fg(Color::BRIGHT_BLACK),
+            };
+            write!(f, "{}", Console::new_with_style(name.as_str(), style))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_steps_text() {
+        let steps = Steps::new(["fetch", "build", "deploy"]).current(1);
+        assert_eq!(
+            strip_ansi(&steps.to_string()),
+            "[2/3] fetch › build › deploy"
+        );
+
+        let steps = Steps::new(["a", "b"])
+            .current(5)
+            .counter(false)
+            .separator(" > ");
+        assert_eq!(strip_ansi(&steps.to_string()), "a > b");
+        assert_eq!(Steps::new(Vec::<String>::new()).to_string(), "");
+    }
+
+    #[test]
+    fn test_steps_render() {
+        let steps = Steps::new(["fetch", "build", "deploy"]).current(1);
+        insta::assert_yaml_snapshot!(steps.to_string());
+    }
+}
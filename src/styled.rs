@@ -0,0 +1,171 @@
+use std::fmt::{self, Display};
+
+use crate::{Attribute, Color, Console, Style, accessible};
+
+// Styles any `Display` value lazily: the value is formatted straight into the
+// output when the `Styled` itself is displayed, without an intermediate String
+#[derive(Debug, Clone, PartialEq)]
+pub struct Styled<T: Display> {
+    value: T,
+    style: Style,
+}
+
+macro_rules! color_methods {
+    ($($name:ident, $on_name:ident => $color:ident;)*) => {
+        $(
+            pub fn $name(self) -> Self {
+                self.fg(Color::$color)
+            }
+
+            pub fn $on_name(self) -> Self {
+                self.bg(Color::$color)
+            }
+        )*
+    };
+}
+
+macro_rules! attribute_methods {
+    ($($name:ident => $attribute:ident;)*) => {
+        $(
+            pub fn $name(self) -> Self {
+                self.attr(Attribute::$attribute)
+            }
+        )*
+    };
+}
+
+impl<T: Display> Styled<T> {
+    pub fn new(value: T) -> Self {
+        Styled {
+            value,
+            style: Style::new(),
+        }
+    }
+
+    pub fn with_style(value: T, style: Style) -> Self {
+        Styled { value, style }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn fg(self, color: Color) -> Self {
+        Styled {
+            style: self.style.fg(color),
+            ..self
+        }
+    }
+
+    pub fn bg(self, color: Color) -> Self {
+        Styled {
+            style: self.style.bg(color),
+            ..self
+        }
+    }
+
+    pub fn fg_rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.fg(Color::RGB(r, g, b))
+    }
+
+    pub fn bg_rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.bg(Color::RGB(r, g, b))
+    }
+
+    pub fn attr(self, attribute: Attribute) -> Self {
+        Styled {
+            style: self.style.attr(attribute),
+            ..self
+        }
+    }
+
+    color_methods! {
+        black, on_black => BLACK;
+        red, on_red => RED;
+        green, on_green => GREEN;
+        yellow, on_yellow => YELLOW;
+        blue, on_blue => BLUE;
+        magenta, on_magenta => MAGENTA;
+        cyan, on_cyan => CYAN;
+        white, on_white => WHITE;
+        bright_black, on_bright_black => BRIGHT_BLACK;
+        bright_red, on_bright_red => BRIGHT_RED;
+        bright_green, on_bright_green => BRIGHT_GREEN;
+        bright_yellow, on_bright_yellow => BRIGHT_YELLOW;
+        bright_blue, on_bright_blue => BRIGHT_BLUE;
+        bright_magenta, on_bright_magenta => BRIGHT_MAGENTA;
+        bright_cyan, on_bright_cyan => BRIGHT_CYAN;
+        bright_white, on_bright_white => BRIGHT_WHITE;
+    }
+
+    attribute_methods! {
+        bold => Bold;
+        dim => Dim;
+        italic => Italic;
+        underline => Underline;
+        blink => Blink;
+        reverse => Reverse;
+        hidden => Hidden;
+        strikethrough => Strikethrough;
+        framed => Framed;
+        encircled => Encircled;
+        overline => Overline;
+        superscript => Superscript;
+        subscript => Subscript;
+    }
+}
+
+impl<T: Display> Display for Styled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if accessible::accessible_mode() {
+            let text = self.value.to_string();
+            return f.write_str(&accessible::render(&self.style, &text));
+        }
+        let ansi_code = self.style.to_ansi_start();
+        if !ansi_code.is_empty() {
+            f.write_str(&ansi_code)?;
+        }
+        // Formatting options such as width apply to the value itself
+        self.value.fmt(f)?;
+        if !ansi_code.is_empty() {
+            f.write_str("\x1b[0m")?;
+        }
+        Ok(())
+    }
+}
+
+impl Console {
+    pub fn display<T: Display>(value: T) -> Styled<T> {
+        Styled::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Point(i32, i32);
+
+    impl Display for Point {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "({}, {})", self.0, self.1)
+        }
+    }
+
+    #[test]
+    fn test_styled_matches_console() {
+        let styled = Styled::new(&Point(1, 2)).cyan().bold();
+        let console = Console::new("(1, 2)").cyan().bold();
+        assert_eq!(styled.to_string(), console.to_string());
+    }
+
+    #[test]
+    fn test_formatting_options_reach_the_value() {
+        let styled = Console::display(42).on_blue();
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(format!("{:>5}", styled), "\x1b[48;5;4m   42\x1b[0m");
+        #[cfg(feature = "no-color")]
+        assert_eq!(format!("{:>5}", styled), "   42");
+        assert_eq!(format!("{:.2}", Styled::new(1.0 / 3.0)), "0.33");
+    }
+}
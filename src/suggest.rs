@@ -0,0 +1,148 @@
+use std::fmt::{self, Display};
+
+use crate::{Color, Console, Style};
+
+// At most this many suggestions are offered
+const MAX_SUGGESTIONS: usize = 3;
+
+// "unknown command `fo`, did you mean `foo`?", suggesting the candidates
+// closest to what was typed by edit distance, each in the accent style.
+// Without a close enough candidate it's just "unknown command `fo`".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    unknown: String,
+    candidates: Vec<String>,
+    kind: String,
+    accent: Style,
+}
+
+impl Suggestion {
+    pub fn new<T, I, S>(unknown: T, candidates: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Suggestion {
+            unknown: unknown.into(),
+            candidates: candidates.into_iter().map(Into::into).collect(),
+            kind: "command".to_string(),
+            accent: Style::new().fg(Color::YELLOW).bold(),
+        }
+    }
+
+    // What was typed, e.g. "subcommand" or "flag", defaults to "command"
+    pub fn kind<T: Into<String>>(mut self, kind: T) -> Self {
+        self.kind = kind.into();
+        self
+    }
+
+    pub fn accent(mut self, style: Style) -> Self {
+        self.accent = style;
+        self
+    }
+
+    // The candidates close enough to suggest, closest first. A candidate is
+    // close enough when about a third of it or less would need changing,
+    // so short names still allow one typo.
+    pub fn suggestions(&self) -> Vec<&str> {
+        let mut close: Vec<(usize, &str)> = self
+            .candidates
+            .iter()
+            .map(|candidate| (distance(&self.unknown, candidate), candidate.as_str()))
+            .filter(|&(distance, candidate)| {
+                distance > 0 && distance <= (candidate.chars().count() / 3).max(1)
+            })
+            .collect();
+        close.sort();
+        close.dedup();
+        close
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown {} `{}`", self.kind, self.unknown)?;
+        let suggestions = self.suggestions();
+        let Some((last, rest)) = suggestions.split_last() else {
+            return Ok(());
+        };
+        let accent = |name: &str| Console::new_with_style(name, self.accent.clone());
+        write!(f, ", did you mean ")?;
+        for (i, name) in rest.iter().enumerate() {
+            let separator = if i + 1 == rest.len() { " or " } else { ", " };
+            write!(f, "`{}`{}", accent(name), separator)?;
+        }
+        write!(f, "`{}`?", accent(last))
+    }
+}
+
+// Levenshtein distance: the fewest characters inserted, removed or replaced
+// to turn `a` into `b`
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("fo", "foo"), 1);
+        assert_eq!(distance("héllo", "hello"), 1);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let suggestion = Suggestion::new("stauts", ["status", "stash", "start", "log"]);
+        assert_eq!(suggestion.suggestions(), ["status"]);
+        assert_eq!(
+            Suggestion::new("bild", ["build", "bind", "init"]).suggestions(),
+            ["bind", "build"]
+        );
+        assert!(Suggestion::new("xyz", ["build"]).suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let suggestion = Suggestion::new("fo", ["foo", "bar"]);
+        assert_eq!(
+            suggestion.to_string(),
+            format!(
+                "unknown command `fo`, did you mean `{}`?",
+                Console::new("foo").yellow().bold()
+            )
+        );
+        assert_eq!(
+            strip_ansi(
+                &Suggestion::new("--verbos", ["--verbose", "--version"])
+                    .kind("flag")
+                    .to_string()
+            ),
+            "unknown flag `--verbos`, did you mean `--verbose` or `--version`?"
+        );
+        assert_eq!(
+            Suggestion::new("zzz", ["foo"]).to_string(),
+            "unknown command `zzz`"
+        );
+    }
+}
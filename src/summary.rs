@@ -0,0 +1,106 @@
+use std::fmt::{self, Display};
+
+use crate::Console;
+use crate::theme::{Tone, theme};
+
+// The counts line test runners and linters print at the end, e.g.
+// `12 passed, 2 failed, 1 skipped`, each count and label in the theme's
+// style for its tone. Groups with nothing in them are dimmed, or left out
+// with `hide_zero`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Summary {
+    items: Vec<(usize, String, Tone)>,
+    hide_zero: bool,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn item<T: Into<String>>(mut self, count: usize, label: T, tone: Tone) -> Self {
+        self.items.push((count, label.into(), tone));
+        self
+    }
+
+    pub fn passed(self, count: usize) -> Self {
+        self.item(count, "passed", Tone::Success)
+    }
+
+    pub fn failed(self, count: usize) -> Self {
+        self.item(count, "failed", Tone::Error)
+    }
+
+    pub fn skipped(self, count: usize) -> Self {
+        self.item(count, "skipped", Tone::Warning)
+    }
+
+    pub fn hide_zero(mut self, hide: bool) -> Self {
+        self.hide_zero = hide;
+        self
+    }
+
+    pub fn print(&self) {
+        crate::ignore_broken_pipe(crate::group::write_stdout(&format!("{}\n", self)));
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let theme = theme();
+        let items = self
+            .items
+            .iter()
+            .filter(|(count, _, _)| *count > 0 || !self.hide_zero);
+        for (i, (count, label, tone)) in items.enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            let style = if *count == 0 {
+                theme.style(Tone::Muted)
+            } else {
+                theme.style(*tone)
+            };
+            write!(
+                f,
+                "{}",
+                Console::new_with_style(format!("{} {}", count, label), style.clone())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new().passed(12).failed(2).skipped(0);
+        assert_eq!(
+            summary.to_string(),
+            format!(
+                "{}, {}, {}",
+                Console::new("12 passed").green(),
+                Console::new("2 failed").red(),
+                Console::new("0 skipped").dim()
+            )
+        );
+        assert_eq!(
+            strip_ansi(&summary.hide_zero(true).to_string()),
+            "12 passed, 2 failed"
+        );
+    }
+
+    #[test]
+    fn test_custom_items() {
+        let summary = Summary::new()
+            .item(0, "errors", Tone::Error)
+            .item(3, "warnings", Tone::Warning)
+            .hide_zero(true);
+        assert_eq!(strip_ansi(&summary.to_string()), "3 warnings");
+        assert_eq!(Summary::new().to_string(), "");
+    }
+}
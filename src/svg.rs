@@ -0,0 +1,307 @@
+use crate::html::{css_color, escape};
+use crate::unicode;
+use crate::{Attribute, Console, Style, StyledText};
+
+// Lays out styled text as a terminal-window-looking SVG image
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgRenderer {
+    font_family: String,
+    font_size: f32,
+    line_height: f32,
+    padding: f32,
+    background: String,
+    foreground: String,
+    chrome: bool,
+    title: Option<String>,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        SvgRenderer {
+            font_family: "ui-monospace, SFMono-Regular, Menlo, Consolas, monospace".into(),
+            font_size: 14.0,
+            line_height: 1.4,
+            padding: 16.0,
+            background: "#1e1e1e".into(),
+            foreground: "#d4d4d4".into(),
+            chrome: true,
+            title: None,
+        }
+    }
+}
+
+// Advance width of a monospace glyph relative to the font size
+const CHAR_WIDTH: f32 = 0.6;
+const CHROME_HEIGHT: f32 = 32.0;
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn font_family<T: Into<String>>(mut self, font_family: T) -> Self {
+        self.font_family = font_family.into();
+        self
+    }
+
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    // Line height as a multiple of the font size
+    pub fn line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    // Default background and text colors, as CSS color values
+    pub fn colors<B: Into<String>, F: Into<String>>(
+        mut self,
+        background: B,
+        foreground: F,
+    ) -> Self {
+        self.background = background.into();
+        self.foreground = foreground.into();
+        self
+    }
+
+    // Draws the title bar with the three window buttons
+    pub fn window_chrome(mut self, chrome: bool) -> Self {
+        self.chrome = chrome;
+        self
+    }
+
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn render(&self, text: &StyledText) -> String {
+        let lines = split_lines(text);
+        let cell_width = self.font_size * CHAR_WIDTH;
+        let row_height = self.font_size * self.line_height;
+        let columns = lines
+            .iter()
+            .map(|line| line.iter().map(|s| unicode::width(&s.text)).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+
+        let top = if self.chrome { CHROME_HEIGHT } else { 0.0 };
+        let width = columns as f32 * cell_width + 2.0 * self.padding;
+        let height = top + lines.len() as f32 * row_height + 2.0 * self.padding;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+             <rect width=\"100%\" height=\"100%\" rx=\"6\" fill=\"{bg}\"/>\n",
+            w = num(width),
+            h = num(height),
+            bg = escape(&self.background),
+        );
+
+        if self.chrome {
+            for (i, color) in ["#ff5f56", "#ffbd2e", "#27c93f"].iter().enumerate() {
+                svg.push_str(&format!(
+                    "<circle cx=\"{}\" cy=\"16\" r=\"6\" fill=\"{}\"/>\n",
+                    num(self.padding + 6.0 + i as f32 * 20.0),
+                    color
+                ));
+            }
+            if let Some(title) = &self.title {
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"20\" text-anchor=\"middle\" font-family=\"{}\" font-size=\"12\" fill=\"{}\" opacity=\"0.6\">{}</text>\n",
+                    num(width / 2.0),
+                    escape(&self.font_family),
+                    escape(&self.foreground),
+                    escape(title)
+                ));
+            }
+        }
+
+        svg.push_str(&format!(
+            "<g font-family=\"{}\" font-size=\"{}\" xml:space=\"preserve\">\n",
+            escape(&self.font_family),
+            num(self.font_size)
+        ));
+
+        for (row, line) in lines.iter().enumerate() {
+            let y = top + self.padding + row as f32 * row_height;
+            // Text sits on a baseline roughly 80% of the way down its row
+            let baseline = y + (row_height + self.font_size * 0.8) / 2.0;
+            let mut column = 0;
+            let mut rects = String::new();
+            let mut spans = String::new();
+
+            for segment in line {
+                let len = unicode::width(&segment.text);
+                let x = self.padding + column as f32 * cell_width;
+                let (fill, background) = self.colors_for(&segment.style);
+
+                if let Some(background) = background {
+                    rects.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                        num(x),
+                        num(y),
+                        num(len as f32 * cell_width),
+                        num(row_height),
+                        background
+                    ));
+                }
+                if !segment.style.attributes.contains(&Attribute::Hidden) {
+                    spans.push_str(&format!(
+                        "<tspan x=\"{}\" fill=\"{}\"{}>{}</tspan>",
+                        num(x),
+                        fill,
+                        text_attributes(&segment.style),
+                        escape(&segment.text)
+                    ));
+                }
+                column += len;
+            }
+
+            svg.push_str(&rects);
+            if !spans.is_empty() {
+                svg.push_str(&format!("<text y=\"{}\">{}</text>\n", num(baseline), spans));
+            }
+        }
+
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    fn colors_for(&self, style: &Style) -> (String, Option<String>) {
+        let fg = style.foreground.map(css_color);
+        let bg = style.background.map(css_color);
+        if style.attributes.contains(&Attribute::Reverse) {
+            let fill = bg.unwrap_or_else(|| escape(&self.background));
+            let background = fg.unwrap_or_else(|| escape(&self.foreground));
+            (fill, Some(background))
+        } else {
+            (fg.unwrap_or_else(|| escape(&self.foreground)), bg)
+        }
+    }
+}
+
+impl Console {
+    pub fn to_svg(&self) -> String {
+        SvgRenderer::new().render(&StyledText::from(self.clone()))
+    }
+}
+
+impl StyledText {
+    pub fn to_svg(&self) -> String {
+        SvgRenderer::new().render(self)
+    }
+}
+
+fn split_lines(text: &StyledText) -> Vec<Vec<Console>> {
+    let mut lines = vec![Vec::new()];
+    for segment in text.segments() {
+        for (i, part) in segment.text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Vec::new());
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap().push(segment.with_text(part));
+            }
+        }
+    }
+    lines
+}
+
+fn text_attributes(style: &Style) -> String {
+    let has = |attr| style.attributes.contains(&attr);
+    let mut attributes = String::new();
+    if has(Attribute::Bold) {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if has(Attribute::Italic) {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if has(Attribute::Dim) {
+        attributes.push_str(" opacity=\"0.6\"");
+    }
+    let decorations: Vec<&str> = [
+        (Attribute::Underline, "underline"),
+        (Attribute::Overline, "overline"),
+        (Attribute::Strikethrough, "line-through"),
+    ]
+    .into_iter()
+    .filter(|&(attr, _)| has(attr))
+    .map(|(_, value)| value)
+    .collect();
+    if !decorations.is_empty() {
+        attributes.push_str(&format!(" text-decoration=\"{}\"", decorations.join(" ")));
+    }
+    attributes
+}
+
+// Formats coordinates without a trailing ".0" for whole numbers
+fn num(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_formatting() {
+        assert_eq!(num(16.0), "16");
+        assert_eq!(num(8.4), "8.4");
+        assert_eq!(num(1.0 / 3.0), "0.33");
+    }
+
+    #[test]
+    fn test_split_lines() {
+        let text = StyledText::new()
+            .then(Console::new("one\ntw").red())
+            .then(Console::new("o\n\nthree"));
+        let lines: Vec<Vec<String>> = split_lines(&text)
+            .iter()
+            .map(|line| line.iter().map(|s| s.text.clone()).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![vec!["one"], vec!["tw", "o"], vec![], vec!["three"]]
+        );
+    }
+
+    #[test]
+    fn test_svg_dimensions() {
+        let renderer = SvgRenderer::new().window_chrome(false).padding(10.0);
+        let svg = renderer.render(&StyledText::from(Console::new("0123456789")));
+        // 10 columns of 8.4px plus padding, one 19.6px row plus padding
+        assert!(svg.contains("width=\"104\" height=\"39.6\""));
+
+        let with_chrome = SvgRenderer::new().render(&StyledText::from(Console::new("x")));
+        assert!(with_chrome.contains("<circle"));
+    }
+
+    #[test]
+    fn test_svg_escapes_text() {
+        let svg = Console::new("a < b && c").to_svg();
+        assert!(svg.contains("a &lt; b &amp;&amp; c"));
+    }
+
+    #[test]
+    fn test_console_to_svg() {
+        let text = StyledText::new()
+            .then(Console::new("$ ").bright_black())
+            .then(Console::new("cargo build").bold())
+            .then(Console::new("\n"))
+            .then(Console::new(" PASS ").black().on_green())
+            .then(Console::new(" all tests passed"));
+        insta::assert_snapshot!(SvgRenderer::new().title("demo").render(&text));
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::RwLock;
+
+use crate::capability::Capabilities;
+
+// The glyphs components draw with. `Symbols::UNICODE` is used when the
+// terminal can render it and `Symbols::ASCII` otherwise, e.g. on the Linux
+// console without a UTF-8 locale or a legacy Windows codepage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbols {
+    pub check: &'static str,
+    pub cross: &'static str,
+    pub warning: &'static str,
+    pub info: &'static str,
+    pub star: &'static str,
+    pub arrow: &'static str,
+    pub bullet: &'static str,
+    pub ellipsis: &'static str,
+    pub pending: &'static str,
+    pub skipped: &'static str,
+    pub separator: &'static str,
+    pub pointer: &'static str,
+    pub checked: &'static str,
+    pub unchecked: &'static str,
+    pub block: char,
+    pub spinner: &'static [&'static str],
+    pub border: Border,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Border {
+    pub top_left: &'static str,
+    pub top_right: &'static str,
+    pub bottom_left: &'static str,
+    pub bottom_right: &'static str,
+    pub horizontal: &'static str,
+    pub vertical: &'static str,
+}
+
+impl Symbols {
+    pub const UNICODE: Symbols = Symbols {
+        check: "✓",
+        cross: "✗",
+        warning: "⚠",
+        info: "ℹ",
+        star: "★",
+        arrow: "→",
+        bullet: "·",
+        ellipsis: "…",
+        pending: "○",
+        skipped: "↷",
+        separator: "›",
+        pointer: "❯",
+        checked: "◉",
+        unchecked: "◯",
+        block: '█',
+        spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+        border: Border {
+            top_left: "╭",
+            top_right: "╮",
+            bottom_left: "╰",
+            bottom_right: "╯",
+            horizontal: "─",
+            vertical: "│",
+        },
+    };
+
+    pub const ASCII: Symbols = Symbols {
+        check: "v",
+        cross: "x",
+        warning: "!",
+        info: "i",
+        star: "*",
+        arrow: "->",
+        bullet: "-",
+        ellipsis: "...",
+        pending: "o",
+        skipped: "~",
+        separator: ">",
+        pointer: ">",
+        checked: "[x]",
+        unchecked: "[ ]",
+        block: '#',
+        spinner: &["|", "/", "-", "\\"],
+        border: Border {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            horizontal: "-",
+            vertical: "|",
+        },
+    };
+}
+
+static OVERRIDE: RwLock<Option<Symbols>> = RwLock::new(None);
+
+// Forces a symbol set, e.g. `Symbols::ASCII` for log files, instead of
+// detecting one
+pub fn set_symbols(symbols: Symbols) {
+    *OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = Some(symbols);
+}
+
+pub fn symbols() -> Symbols {
+    if let Some(symbols) = *OVERRIDE.read().unwrap_or_else(|e| e.into_inner()) {
+        return symbols;
+    }
+    // Keep test output independent of the host locale
+    if cfg!(test) || Capabilities::get().unicode {
+        Symbols::UNICODE
+    } else {
+        Symbols::ASCII
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_symbols_are_ascii() {
+        let ascii = Symbols::ASCII;
+        let border = ascii.border;
+        let all = [
+            ascii.check,
+            ascii.cross,
+            ascii.warning,
+            ascii.info,
+            ascii.star,
+            ascii.arrow,
+            ascii.bullet,
+            ascii.ellipsis,
+            ascii.pending,
+            ascii.skipped,
+            ascii.separator,
+            ascii.pointer,
+            ascii.checked,
+            ascii.unchecked,
+            border.top_left,
+            border.top_right,
+            border.bottom_left,
+            border.bottom_right,
+            border.horizontal,
+            border.vertical,
+        ];
+        assert!(all.iter().chain(ascii.spinner).all(|s| s.is_ascii()));
+        assert!(ascii.block.is_ascii());
+    }
+}
@@ -0,0 +1,736 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+#[cfg(any(feature = "csv", feature = "serde"))]
+use std::io;
+
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::unicode;
+use crate::{Console, Rows, Style, StyledText};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Column {
+    align: Align,
+    style: Style,
+    numbers: Option<Numbers>,
+}
+
+// Formatting for a column of numbers, optionally followed by a unit such as
+// `ms` or `%`. Values line up on the decimal point with their units in a
+// column of their own; cells that aren't numbers are left as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Numbers {
+    thousands: Option<char>,
+    sign_colors: bool,
+}
+
+impl Numbers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Groups the integer digits in threes, e.g. `1,234,567` for ','
+    pub fn thousands(mut self, separator: char) -> Self {
+        self.thousands = Some(separator);
+        self
+    }
+
+    // Colors negative values with the theme's error style and positive
+    // values with its success style
+    pub fn sign_colors(mut self, enabled: bool) -> Self {
+        self.sign_colors = enabled;
+        self
+    }
+}
+
+// Rows of styled cells laid out in aligned columns under an optional bold
+// header and rule. Column widths come from the widest cell; rows shorter
+// than the header are padded with empty cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    headers: Vec<StyledText>,
+    rows: Vec<Vec<StyledText>>,
+    columns: Vec<Column>,
+    header_style: Style,
+    gap: usize,
+    stripes: Option<Rows>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Table {
+            headers: Vec::new(),
+            rows: Vec::new(),
+            columns: Vec::new(),
+            header_style: Style::new().bold(),
+            gap: 2,
+            stripes: None,
+        }
+    }
+
+    pub fn headers<I>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<StyledText>,
+    {
+        self.headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn row<I>(mut self, cells: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<StyledText>,
+    {
+        self.push_row(cells);
+        self
+    }
+
+    pub fn push_row<I>(&mut self, cells: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<StyledText>,
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        self.column_mut(column).align = align;
+        self
+    }
+
+    // Layered under the cells' own styles in that column, header excluded
+    pub fn column_style(mut self, column: usize, style: Style) -> Self {
+        self.column_mut(column).style = style;
+        self
+    }
+
+    // Decimal-aligns the column, which also right-aligns its header
+    pub fn numbers(mut self, column: usize, numbers: Numbers) -> Self {
+        let column = self.column_mut(column);
+        column.align = Align::Right;
+        column.numbers = Some(numbers);
+        self
+    }
+
+    // Like `column_style`, for the column under the given header
+    pub fn column_style_for(self, header: &str, style: Style) -> Self {
+        match self.headers.iter().position(|h| h.plain() == header) {
+            Some(column) => self.column_style(column, style),
+            None => self,
+        }
+    }
+
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    // Spaces between columns
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    // Alternates row backgrounds, padding every row to the table's width
+    pub fn striped(mut self, rows: Rows) -> Self {
+        self.stripes = Some(rows);
+        self
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn print(&self) {
+        crate::ignore_broken_pipe(crate::group::write_stdout(&self.to_string()));
+    }
+
+    fn column_mut(&mut self, column: usize) -> &mut Column {
+        if self.columns.len() <= column {
+            self.columns.resize_with(column + 1, Column::default);
+        }
+        &mut self.columns[column]
+    }
+
+    fn widths(&self, rows: &[Vec<StyledText>]) -> Vec<usize> {
+        let mut widths = vec![0; self.column_count()];
+        for cells in rows.iter().chain([&self.headers]) {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(cell.width());
+            }
+        }
+        widths
+    }
+
+    // The rows with number columns formatted and padded to a common width
+    fn formatted_rows(&self) -> Cow<'_, [Vec<StyledText>]> {
+        if self.columns.iter().all(|c| c.numbers.is_none()) {
+            return Cow::Borrowed(&self.rows);
+        }
+        let mut rows = self.rows.clone();
+        for (index, column) in self.columns.iter().enumerate() {
+            if let Some(numbers) = &column.numbers {
+                format_numbers(&mut rows, index, numbers);
+            }
+        }
+        Cow::Owned(rows)
+    }
+
+    fn render_line(
+        &self,
+        cells: &[StyledText],
+        widths: &[usize],
+        cell_style: impl Fn(usize) -> Style,
+    ) -> StyledText {
+        let empty = StyledText::new();
+        let mut line = StyledText::new();
+        // Without stripes there is nothing to fill after the last cell
+        let count = match self.stripes {
+            Some(_) => widths.len(),
+            None => cells
+                .iter()
+                .rposition(|cell| !cell.is_empty())
+                .map_or(0, |last| last + 1),
+        };
+        for (index, &width) in widths[..count].iter().enumerate() {
+            let cell = cells.get(index).unwrap_or(&empty);
+            let padding = width.saturating_sub(cell.width());
+            let (before, after) = match self.align_of(index) {
+                Align::Left => (0, padding),
+                Align::Right => (padding, 0),
+                Align::Center => (padding / 2, padding - padding / 2),
+            };
+            if index > 0 {
+                line.push(Console::repeat(' ', self.gap));
+            }
+            line.push(Console::repeat(' ', before));
+            let base = cell_style(index);
+            line.extend(
+                cell.segments()
+                    .iter()
+                    .map(|segment| segment.with_style(base.merge(segment.style()))),
+            );
+            if index + 1 < count || self.stripes.is_some() {
+                line.push(Console::repeat(' ', after));
+            }
+        }
+        line.segments()
+            .iter()
+            .filter(|segment| !segment.text().is_empty())
+            .cloned()
+            .collect()
+    }
+
+    // Right-aligns every column whose non-empty cells are all numbers
+    #[cfg(any(feature = "csv", feature = "serde"))]
+    fn align_numeric_columns(&mut self) {
+        for column in 0..self.column_count() {
+            let mut fields = self
+                .rows
+                .iter()
+                .filter_map(|cells| cells.get(column))
+                .map(StyledText::plain)
+                .filter(|field| !field.is_empty())
+                .peekable();
+            if fields.peek().is_some() && fields.all(|field| is_numeric(&field)) {
+                self.column_mut(column).align = Align::Right;
+            }
+        }
+    }
+
+    fn align_of(&self, column: usize) -> Align {
+        self.columns.get(column).map_or(Align::Left, |c| c.align)
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = self.formatted_rows();
+        let widths = self.widths(&rows);
+        if widths.is_empty() {
+            return Ok(());
+        }
+        if !self.headers.is_empty() {
+            writeln!(
+                f,
+                "{}",
+                self.render_line(&self.headers, &widths, |_| self.header_style.clone())
+            )?;
+            let rule: Vec<String> = widths
+                .iter()
+                .map(|&width| symbols().border.horizontal.repeat(width))
+                .collect();
+            writeln!(
+                f,
+                "{}",
+                Console::new_with_style(
+                    rule.join(&" ".repeat(self.gap)),
+                    theme().style(Tone::Muted).clone()
+                )
+            )?;
+        }
+        for (index, cells) in rows.iter().enumerate() {
+            let line = self.render_line(cells, &widths, |column| {
+                self.columns
+                    .get(column)
+                    .map_or_else(Style::new, |c| c.style.clone())
+            });
+            match &self.stripes {
+                Some(stripes) => writeln!(f, "{}", stripes.stripe(index, &line))?,
+                None => writeln!(f, "{}", line)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// A number split around its decimal point: `-1,234.5 ms` is the sign `-`,
+// integer `1,234`, fraction `.5` and unit ` ms`
+struct NumberParts<'a> {
+    sign: &'a str,
+    integer: String,
+    fraction: &'a str,
+    unit: &'a str,
+}
+
+impl<'a> NumberParts<'a> {
+    fn parse(text: &'a str) -> Option<Self> {
+        let rest = text.trim();
+        let unsigned = rest.trim_start_matches(['-', '+']);
+        let sign = &rest[..(rest.len() - unsigned.len()).min(1)];
+        if unsigned.len() + sign.len() != rest.len() {
+            return None;
+        }
+        let integer_len = unsigned
+            .find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '_'))
+            .unwrap_or(unsigned.len());
+        let (integer, rest) = unsigned.split_at(integer_len);
+        let fraction_len = match rest.strip_prefix('.') {
+            Some(digits) => {
+                1 + digits
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(digits.len())
+            }
+            None => 0,
+        };
+        let (fraction, unit) = rest.split_at(fraction_len);
+        let has_digits = integer.starts_with(|c: char| c.is_ascii_digit())
+            || fraction.len() > 1 && integer.is_empty();
+        has_digits.then(|| NumberParts {
+            sign,
+            integer: integer.to_string(),
+            fraction,
+            unit,
+        })
+    }
+
+    fn group_thousands(&mut self, separator: char) {
+        let digits: Vec<char> = self.integer.chars().filter(char::is_ascii_digit).collect();
+        let mut grouped = String::new();
+        for (index, digit) in digits.iter().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(*digit);
+        }
+        self.integer = grouped;
+    }
+
+    fn is_zero(&self) -> bool {
+        self.integer
+            .chars()
+            .chain(self.fraction.chars())
+            .all(|c| !c.is_ascii_digit() || c == '0')
+    }
+
+    fn sign_style(&self) -> Style {
+        match self.sign {
+            _ if self.is_zero() => Style::new(),
+            "-" => theme().style(Tone::Error).clone(),
+            _ => theme().style(Tone::Success).clone(),
+        }
+    }
+}
+
+fn format_numbers(rows: &mut [Vec<StyledText>], column: usize, numbers: &Numbers) {
+    let texts: Vec<String> = rows
+        .iter()
+        .map(|cells| cells.get(column).map(StyledText::plain).unwrap_or_default())
+        .collect();
+    let mut parsed: Vec<Option<NumberParts>> =
+        texts.iter().map(|t| NumberParts::parse(t)).collect();
+    if let Some(separator) = numbers.thousands {
+        parsed
+            .iter_mut()
+            .flatten()
+            .for_each(|p| p.group_thousands(separator));
+    }
+
+    let max =
+        |width: fn(&NumberParts) -> usize| parsed.iter().flatten().map(width).max().unwrap_or(0);
+    let integer_width = max(|p| p.sign.len() + unicode::width(&p.integer));
+    let fraction_width = max(|p| p.fraction.len());
+    let unit_width = max(|p| unicode::width(p.unit));
+
+    for (cells, parts) in rows.iter_mut().zip(&parsed) {
+        let (Some(cell), Some(parts)) = (cells.get_mut(column), parts) else {
+            continue;
+        };
+        let number = format!("{}{}{}", parts.sign, parts.integer, parts.fraction);
+        let base = cell
+            .segments()
+            .first()
+            .map_or_else(Style::new, |segment| segment.style().clone());
+        let style = if numbers.sign_colors {
+            base.merge(&parts.sign_style())
+        } else {
+            base.clone()
+        };
+        let leading = integer_width - parts.sign.len() - unicode::width(&parts.integer);
+        let trailing = fraction_width - parts.fraction.len();
+        *cell = StyledText::new()
+            .then(Console::repeat(' ', leading))
+            .then(Console::new_with_style(number, style))
+            .then(Console::repeat(' ', trailing))
+            .then(Console::new_with_style(parts.unit, base))
+            .then(Console::repeat(
+                ' ',
+                unit_width - unicode::width(parts.unit),
+            ));
+    }
+}
+
+#[cfg(feature = "csv")]
+impl Table {
+    // Reads comma-separated input whose first record is the header.
+    // Numeric columns are right-aligned.
+    pub fn from_csv<R: io::Read>(reader: R) -> io::Result<Table> {
+        Self::from_delimited(reader, b',')
+    }
+
+    pub fn from_tsv<R: io::Read>(reader: R) -> io::Result<Table> {
+        Self::from_delimited(reader, b'\t')
+    }
+
+    pub fn from_delimited<R: io::Read>(reader: R, delimiter: u8) -> io::Result<Table> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(invalid_data)?
+            .iter()
+            .map(String::from)
+            .collect();
+        let mut records = Vec::new();
+        for record in reader.records() {
+            records.push(
+                record
+                    .map_err(invalid_data)?
+                    .iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        let mut table = Table::new().headers(headers.iter().map(|h| data_cell(h)));
+        for record in &records {
+            table.push_row(record.iter().map(|field| data_cell(field)));
+        }
+        table.align_numeric_columns();
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "csv")]
+fn invalid_data(err: csv::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(feature = "serde")]
+impl Table {
+    // One row per item with the struct's field names as headers, in the
+    // order they're declared. Strings are shown as-is, nested values as
+    // compact JSON and missing or null fields as empty cells.
+    pub fn from_serde<T: serde::Serialize>(items: &[T]) -> io::Result<Table> {
+        let mut headers: Vec<String> = Vec::new();
+        let mut records = Vec::new();
+        for item in items {
+            let serde_json::Value::Object(fields) =
+                serde_json::to_value(item).map_err(io::Error::from)?
+            else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "expected items that serialize as structs or maps",
+                ));
+            };
+            for name in fields.keys() {
+                if !headers.contains(name) {
+                    headers.push(name.clone());
+                }
+            }
+            records.push(fields);
+        }
+
+        let mut table = Table::new().headers(headers.iter().map(|h| data_cell(h)));
+        for fields in &records {
+            table.push_row(headers.iter().map(|name| {
+                data_cell(&match fields.get(name) {
+                    None | Some(serde_json::Value::Null) => String::new(),
+                    Some(serde_json::Value::String(text)) => text.clone(),
+                    Some(value) => value.to_string(),
+                })
+            }));
+        }
+        table.align_numeric_columns();
+        Ok(table)
+    }
+}
+
+// Untrusted text from a data file, kept on one line with control
+// characters made visible
+#[cfg(any(feature = "csv", feature = "serde"))]
+fn data_cell(field: &str) -> StyledText {
+    StyledText::from(Console::new_with_style(
+        crate::show_controls(&field.replace("\r\n", " ").replace('\n', " ")),
+        Style::new(),
+    ))
+}
+
+// Accepts numbers as they usually appear in data files: signs, thousands
+// separators, decimals, exponents and a trailing percent sign
+#[cfg_attr(not(any(feature = "csv", feature = "serde")), allow(dead_code))]
+fn is_numeric(field: &str) -> bool {
+    let digits = field.strip_prefix(['-', '+']).unwrap_or(field);
+    let digits = digits.strip_suffix('%').unwrap_or(digits);
+    digits.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && digits.replace([',', '_'], "").parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::Background;
+    use crate::testing::strip_ansi;
+
+    fn table() -> Table {
+        Table::new()
+            .headers(["Name", "Size", "Kind"])
+            .row(["Cargo.toml", "1.2K", "file"])
+            .row(["src", "-", "directory"])
+            .align(1, Align::Right)
+    }
+
+    #[test]
+    fn test_table_layout() {
+        assert_eq!(
+            strip_ansi(&table().to_string()),
+            "Name        Size  Kind\n\
+             ──────────  ────  ─────────\n\
+             Cargo.toml  1.2K  file\n\
+             src            -  directory\n"
+        );
+    }
+
+    #[test]
+    fn test_table_ragged_rows_and_center() {
+        let table = Table::new()
+            .row(["a", "bbbbb"])
+            .row(["ccc"])
+            .align(0, Align::Center)
+            .gap(1);
+        assert_eq!(strip_ansi(&table.to_string()), " a  bbbbb\nccc\n");
+        assert_eq!(Table::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_table_striped_rows_fill_width() {
+        let output = table()
+            .striped(Rows::for_background(Background::Dark))
+            .to_string();
+        let lines: Vec<String> = output.lines().map(strip_ansi).collect();
+        assert_eq!(lines[2], "Cargo.toml  1.2K  file     ");
+        assert_eq!(lines[3], "src            -  directory");
+    }
+
+    #[test]
+    fn test_is_numeric() {
+        for field in ["42", "-3.5", "+1e6", "1,234,567", ".5", "12%"] {
+            assert!(is_numeric(field), "{field}");
+        }
+        for field in ["", "-", "inf", "NaN", "1.2K", "v1", "12:30"] {
+            assert!(!is_numeric(field), "{field}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_from_csv_right_aligns_numbers() {
+        let input = "name, qty, price\napple, 3, 1.25\nwatermelon, 12, \nfig, 100, 0.5\n";
+        let table = Table::from_csv(input.as_bytes()).unwrap();
+        assert_eq!(
+            strip_ansi(&table.to_string()),
+            "name        qty  price\n\
+             ──────────  ───  ─────\n\
+             apple         3   1.25\n\
+             watermelon   12\n\
+             fig         100    0.5\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_from_tsv_and_errors() {
+        let table = Table::from_tsv("a\tb\n\"x\ny\"\t1\n".as_bytes()).unwrap();
+        assert_eq!(strip_ansi(&table.to_string()), "a    b\n───  ─\nx y  1\n");
+
+        let err = Table::from_csv(&b"a\n\xff\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_from_serde_uses_field_names() {
+        #[derive(serde::Serialize)]
+        struct Bench {
+            name: &'static str,
+            iterations: u32,
+            mean: Option<f64>,
+            tags: Vec<&'static str>,
+        }
+        let table = Table::from_serde(&[
+            Bench {
+                name: "parse",
+                iterations: 1000,
+                mean: Some(1.5),
+                tags: vec!["fast"],
+            },
+            Bench {
+                name: "render",
+                iterations: 20,
+                mean: None,
+                tags: vec![],
+            },
+        ])
+        .unwrap();
+        assert_eq!(
+            strip_ansi(&table.to_string()),
+            "name    iterations  mean  tags\n\
+             ──────  ──────────  ────  ────────\n\
+             parse         1000   1.5  [\"fast\"]\n\
+             render          20        []\n"
+        );
+
+        let err = Table::from_serde(&[1, 2]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_column_style_for_header() {
+        let cyan = Style::new().fg(crate::Color::CYAN);
+        assert_eq!(
+            table().column_style_for("Kind", cyan.clone()),
+            table().column_style(2, cyan)
+        );
+        assert_eq!(table().column_style_for("Missing", Style::new()), table());
+    }
+
+    #[test]
+    fn test_number_parts() {
+        let parts = NumberParts::parse(" -1,234.50 ms").unwrap();
+        assert_eq!(
+            (
+                parts.sign,
+                parts.integer.as_str(),
+                parts.fraction,
+                parts.unit
+            ),
+            ("-", "1,234", ".50", " ms")
+        );
+        let parts = NumberParts::parse(".5%").unwrap();
+        assert_eq!(
+            (parts.integer.as_str(), parts.fraction, parts.unit),
+            ("", ".5", "%")
+        );
+        for text in ["", "-", "n/a", "--1", "v2", "."] {
+            assert!(NumberParts::parse(text).is_none(), "{text}");
+        }
+
+        let mut parts = NumberParts::parse("1234567.8").unwrap();
+        parts.group_thousands(',');
+        assert_eq!(parts.integer, "1,234,567");
+        let mut parts = NumberParts::parse("1_000").unwrap();
+        parts.group_thousands('.');
+        assert_eq!(parts.integer, "1.000");
+        assert!(NumberParts::parse("-0.00").unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_numbers_align_on_decimal_point() {
+        let table = Table::new()
+            .headers(["Benchmark", "Time", "Change"])
+            .row(["parse", "1234.5 ms", "-2.25%"])
+            .row(["render", "12 µs", "+10%"])
+            .row(["layout", "n/a", "0.1%"])
+            .numbers(1, Numbers::new().thousands(','))
+            .numbers(2, Numbers::new());
+        assert_eq!(
+            strip_ansi(&table.to_string()),
+            "Benchmark        Time   Change\n\
+             ─────────  ──────────  ───────\n\
+             parse      1,234.5 ms   -2.25%\n\
+             render        12   µs  +10   %\n\
+             layout            n/a    0.1 %\n"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_numbers_sign_colors() {
+        let table = Table::new()
+            .row(["-1.5"])
+            .row(["2"])
+            .row(["0.0"])
+            .numbers(0, Numbers::new().sign_colors(true));
+        let lines: Vec<String> = table.to_string().lines().map(String::from).collect();
+        assert_eq!(
+            lines,
+            [
+                Console::new("-1.5").red().to_string(),
+                format!(" {}  ", Console::new("2").green()),
+                " 0.0".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_render() {
+        insta::assert_yaml_snapshot!(
+            table()
+                .column_style(0, Style::new().fg(crate::Color::CYAN))
+                .to_string()
+        );
+    }
+}
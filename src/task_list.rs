@@ -0,0 +1,243 @@
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::draw_target::{DrawTarget, stderr_target};
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+use crate::throttle::Throttle;
+use crate::{Console, LiveRegion};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Skipped,
+}
+
+struct Task {
+    label: String,
+    state: TaskState,
+    message: Option<String>,
+}
+
+// A list of steps with status icons that is redrawn in place whenever a step
+// changes state. Call `tick` periodically to animate running steps.
+pub struct TaskList<W: Write> {
+    region: LiveRegion<W>,
+    tasks: Vec<Task>,
+    frame: usize,
+    throttle: Throttle,
+}
+
+impl TaskList<DrawTarget> {
+    pub fn new<I, T>(labels: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::with_writer(stderr_target(), labels)
+    }
+}
+
+impl<W: Write> TaskList<W> {
+    pub fn with_writer<I, T>(out: W, labels: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        TaskList {
+            region: LiveRegion::new(out),
+            tasks: labels
+                .into_iter()
+                .map(|label| Task {
+                    label: label.into(),
+                    state: TaskState::Pending,
+                    message: None,
+                })
+                .collect(),
+            frame: 0,
+            throttle: Throttle::default(),
+        }
+    }
+
+    // Adds a pending step and returns its index
+    pub fn push<T: Into<String>>(&mut self, label: T) -> io::Result<usize> {
+        self.tasks.push(Task {
+            label: label.into(),
+            state: TaskState::Pending,
+            message: None,
+        });
+        self.draw()?;
+        Ok(self.tasks.len() - 1)
+    }
+
+    pub fn state(&self, index: usize) -> TaskState {
+        self.tasks[index].state
+    }
+
+    pub fn set_state(&mut self, index: usize, state: TaskState) -> io::Result<()> {
+        self.tasks[index].state = state;
+        self.draw()
+    }
+
+    pub fn start(&mut self, index: usize) -> io::Result<()> {
+        self.set_state(index, TaskState::Running)
+    }
+
+    pub fn done(&mut self, index: usize) -> io::Result<()> {
+        self.set_state(index, TaskState::Done)
+    }
+
+    pub fn fail(&mut self, index: usize) -> io::Result<()> {
+        self.set_state(index, TaskState::Failed)
+    }
+
+    pub fn skip(&mut self, index: usize) -> io::Result<()> {
+        self.set_state(index, TaskState::Skipped)
+    }
+
+    // Detail shown after the label, e.g. a failure reason
+    pub fn set_message<T: Into<String>>(&mut self, index: usize, message: T) -> io::Result<()> {
+        self.tasks[index].message = Some(message.into());
+        self.draw()
+    }
+
+    // Advances the spinner of running steps, at most once per frame so it
+    // can be called from a busy loop
+    pub fn tick(&mut self) -> io::Result<()> {
+        if !self.throttle.ready(Instant::now()) {
+            return Ok(());
+        }
+        self.frame = self.frame.wrapping_add(1);
+        self.render_now()
+    }
+
+    pub fn draw(&mut self) -> io::Result<()> {
+        self.throttle.drawn(Instant::now());
+        self.render_now()
+    }
+
+    fn render_now(&mut self) -> io::Result<()> {
+        let lines = self.render();
+        self.region.draw(lines)
+    }
+
+    // Runs `f` with the list taken off screen, e.g. while a child process
+    // writes to the terminal, then draws it again
+    pub fn suspend<F, R>(&mut self, f: F) -> io::Result<R>
+    where
+        F: FnOnce() -> R,
+    {
+        self.region.suspend(f)
+    }
+
+    // Leaves the final state on screen
+    pub fn finish(mut self) -> io::Result<W> {
+        self.draw()?;
+        self.region.finish()?;
+        Ok(self.region.into_inner())
+    }
+
+    fn render(&self) -> Vec<String> {
+        let theme = theme();
+        let symbols = symbols();
+        let styled = |tone, text: &str| Console::new_with_style(text, theme.style(tone).clone());
+
+        self.tasks
+            .iter()
+            .map(|task| {
+                let (icon, label) = match task.state {
+                    TaskState::Pending => (
+                        styled(Tone::Muted, symbols.pending),
+                        styled(Tone::Muted, &task.label),
+                    ),
+                    TaskState::Running => (
+                        styled(
+                            Tone::Info,
+                            symbols.spinner[self.frame % symbols.spinner.len()],
+                        ),
+                        Console::new(task.label.as_str()).bold(),
+                    ),
+                    TaskState::Done => (
+                        styled(Tone::Success, theme.icon(Tone::Success)),
+                        Console::new(task.label.as_str()),
+                    ),
+                    TaskState::Failed => (
+                        styled(Tone::Error, theme.icon(Tone::Error)),
+                        styled(Tone::Error, &task.label),
+                    ),
+                    TaskState::Skipped => (
+                        styled(Tone::Muted, symbols.skipped),
+                        styled(Tone::Muted, &format!("{} (skipped)", task.label)),
+                    ),
+                };
+                match &task.message {
+                    Some(message) => format!("{} {} {}", icon, label, styled(Tone::Muted, message)),
+                    None => format!("{} {}", icon, label),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    fn plain_lines(tasks: &TaskList<Vec<u8>>) -> Vec<String> {
+        tasks.render().iter().map(|line| strip_ansi(line)).collect()
+    }
+
+    #[test]
+    fn test_task_states() {
+        let mut tasks = TaskList::with_writer(Vec::new(), ["Fetch", "Build", "Test", "Deploy"]);
+        tasks.done(0).unwrap();
+        tasks.start(1).unwrap();
+        tasks.fail(2).unwrap();
+        tasks.set_message(2, "3 failures").unwrap();
+        tasks.skip(3).unwrap();
+        assert_eq!(tasks.state(1), TaskState::Running);
+
+        assert_eq!(
+            plain_lines(&tasks),
+            vec![
+                "✓ Fetch",
+                "⠋ Build",
+                "✗ Test 3 failures",
+                "↷ Deploy (skipped)",
+            ]
+        );
+
+        // Ticks in the same frame as the last draw are skipped
+        tasks.tick().unwrap();
+        assert_eq!(plain_lines(&tasks)[1], "⠋ Build");
+        tasks.throttle = Throttle::default();
+        tasks.tick().unwrap();
+        assert_eq!(plain_lines(&tasks)[1], "⠙ Build");
+    }
+
+    #[test]
+    fn test_task_list_redraws_in_place() {
+        let mut tasks = TaskList::with_writer(Vec::new(), ["a", "b"]);
+        tasks.start(0).unwrap();
+        tasks.done(0).unwrap();
+        let index = tasks.push("c").unwrap();
+        assert_eq!(index, 2);
+        let output = String::from_utf8(tasks.finish().unwrap()).unwrap();
+
+        // Each redraw erases the lines drawn before: 2 + 2 + 3 lines
+        assert_eq!(output.matches("\x1b[1A\x1b[2K").count(), 1 + 1 + 2);
+        assert!(strip_ansi(&output).ends_with("✓ a\n○ b\n○ c\n"));
+    }
+
+    #[test]
+    fn test_task_list_render() {
+        let mut tasks = TaskList::with_writer(Vec::new(), ["Resolve", "Download", "Link"]);
+        tasks.done(0).unwrap();
+        tasks.start(1).unwrap();
+        insta::assert_yaml_snapshot!(tasks.render());
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::{self, Write};
+
+use crate::ansi::AnsiStripper;
+
+// Writes styled output to `terminal` and an ANSI-stripped copy to `log`,
+// e.g. for `--log-file` support without formatting every message twice
+pub struct TeeWriter<T: Write, L: Write> {
+    terminal: T,
+    log: L,
+    stripper: AnsiStripper,
+    buffer: Vec<u8>,
+}
+
+impl<T: Write, L: Write> TeeWriter<T, L> {
+    pub fn new(terminal: T, log: L) -> Self {
+        TeeWriter {
+            terminal,
+            log,
+            stripper: AnsiStripper::default(),
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn terminal(&self) -> &T {
+        &self.terminal
+    }
+
+    pub fn log(&self) -> &L {
+        &self.log
+    }
+
+    pub fn into_inner(self) -> (T, L) {
+        (self.terminal, self.log)
+    }
+}
+
+impl<T: Write, L: Write> Write for TeeWriter<T, L> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.terminal.write(buf)?;
+        self.buffer.clear();
+        self.stripper.strip(&buf[..n], &mut self.buffer);
+        self.log.write_all(&self.buffer)?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.terminal.flush()?;
+        self.log.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Console;
+
+    #[test]
+    fn test_tee_strips_log_copy() {
+        let mut tee = TeeWriter::new(Vec::new(), Vec::new());
+        Console::new("error")
+            .red()
+            .bold()
+            .write_to(&mut tee)
+            .unwrap();
+        writeln!(tee, ": disk full").unwrap();
+
+        let (terminal, log) = tee.into_inner();
+        assert_eq!(log, b"error: disk full\n");
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(terminal, b"\x1b[1;38;5;1merror\x1b[0m: disk full\n");
+        #[cfg(feature = "no-color")]
+        assert_eq!(terminal, b"error: disk full\n");
+    }
+
+    #[test]
+    fn test_tee_handles_partial_terminal_writes() {
+        // A writer that accepts at most two bytes per call
+        struct Slow(Vec<u8>);
+        impl Write for Slow {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let n = buf.len().min(2);
+                self.0.extend_from_slice(&buf[..n]);
+                Ok(n)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut tee = TeeWriter::new(Slow(Vec::new()), Vec::new());
+        tee.write_all(b"\x1b[32mok\x1b[0m").unwrap();
+        assert_eq!(tee.terminal().0, b"\x1b[32mok\x1b[0m");
+        assert_eq!(tee.log(), b"ok");
+    }
+}
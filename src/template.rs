@@ -0,0 +1,322 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io;
+use std::str::FromStr;
+
+use crate::theme::{Tone, theme};
+use crate::{Color, Console, Style, StyledText};
+
+// A parsed format such as `{icon} {name:cyan.bold} took {ms:yellow}ms`.
+// Each placeholder can carry a dot-separated style: color names
+// (`bright_red`, `gray`), `#rrggbb`, `on_<color>` backgrounds, attributes
+// (`bold`, `italic`, ...) and theme tones (`success`, `error`, ...). A
+// number first is a width, e.g. `{name:12.cyan}` pads the value to 12
+// columns, and a second style after `/` is for the rest of a field drawn
+// in two parts, e.g. the unfilled part of `{bar:40.green/dim}`. `{{` and
+// `}}` are literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field(Field),
+}
+
+// A placeholder and how it's to be shown
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Field {
+    pub(crate) name: String,
+    pub(crate) style: Style,
+    pub(crate) width: Option<usize>,
+    // After the `/`, if there is one
+    pub(crate) rest_style: Option<Style>,
+}
+
+impl Field {
+    // `text` in the field's style, padded to its width
+    pub(crate) fn styled(&self, text: String) -> Console {
+        let padding = self.width.map_or(0, |width| {
+            width.saturating_sub(crate::unicode::width(&text))
+        });
+        Console::new_with_style(text + &" ".repeat(padding), self.style.clone())
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> io::Result<Template> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(invalid("unmatched `}` in template".into())),
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => {
+                                return Err(invalid(format!("unclosed placeholder `{{{}`", field)));
+                            }
+                            Some(ch) => field.push(ch),
+                        }
+                    }
+                    let (name, spec) = field.split_once(':').unwrap_or((&field, ""));
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return Err(invalid(format!("placeholder `{{{}}}` has no name", field)));
+                    }
+                    let (spec, rest) = match spec.split_once('/') {
+                        Some((spec, rest)) => (spec, Some(rest)),
+                        None => (spec, None),
+                    };
+                    let (width, spec) = match spec.split_once('.').unwrap_or((spec, "")) {
+                        (number, rest)
+                            if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) =>
+                        {
+                            (number.parse().ok(), rest)
+                        }
+                        _ => (None, spec),
+                    };
+                    let unknown = |token: String| {
+                        invalid(format!("unknown style `{}` in `{{{}}}`", token, field))
+                    };
+                    let style = parse_style(spec).map_err(unknown)?;
+                    let rest_style = rest.map(parse_style).transpose().map_err(unknown)?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(Field {
+                        name: name.to_string(),
+                        style,
+                        width,
+                        rest_style,
+                    }));
+                }
+                _ => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { parts })
+    }
+
+    // Placeholder names in order of appearance
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Field(field) => Some(field.name.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+
+    // Placeholders without a value render as nothing
+    pub fn render<K, V>(&self, values: &HashMap<K, V>) -> StyledText
+    where
+        K: Borrow<str> + Hash + Eq,
+        V: Display,
+    {
+        self.render_with(|name| values.get(name).map(|value| value.to_string()))
+    }
+
+    pub fn render_with<F>(&self, mut value: F) -> StyledText
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        self.render_fields(|field| {
+            value(&field.name).map(|text| StyledText::from(field.styled(text)))
+        })
+    }
+
+    // For placeholders drawn by the caller, e.g. a progress bar that's
+    // sized by the field's width
+    pub(crate) fn render_fields<F>(&self, mut value: F) -> StyledText
+    where
+        F: FnMut(&Field) -> Option<StyledText>,
+    {
+        let mut text = StyledText::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => {
+                    text.push(Console::new_with_style(literal.as_str(), Style::new()))
+                }
+                Part::Field(field) => {
+                    if let Some(value) = value(field) {
+                        text.extend(value.segments().iter().cloned());
+                    }
+                }
+            }
+        }
+        text
+    }
+}
+
+impl FromStr for Template {
+    type Err = io::Error;
+
+    fn from_str(source: &str) -> io::Result<Template> {
+        Template::parse(source)
+    }
+}
+
+// Returns the offending token on failure
+pub(crate) fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    for token in spec.split('.').map(str::trim).filter(|t| !t.is_empty()) {
+        let lower = token.to_ascii_lowercase();
+        style = if let Some(color) = lower.strip_prefix("on_").and_then(parse_color) {
+            style.bg(color)
+        } else if let Some(color) = parse_color(&lower) {
+            style.fg(color)
+        } else if let Some(tone) = parse_tone(&lower) {
+            style.merge(theme().style(tone))
+        } else {
+            match lower.as_str() {
+                "bold" => style.bold(),
+                "dim" => style.dim(),
+                "italic" => style.italic(),
+                "underline" => style.underline(),
+                "blink" => style.blink(),
+                "reverse" => style.reverse(),
+                "hidden" => style.hidden(),
+                "strikethrough" => style.strikethrough(),
+                "overline" => style.overline(),
+                _ => return Err(token.to_string()),
+            }
+        };
+    }
+    Ok(style)
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    if let Some(hex) = name.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Some(Color::RGB(r, g, b)),
+            _ => None,
+        };
+    }
+    let names = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    let (bright, base) = match name.strip_prefix("bright_") {
+        Some(base) => (8, base),
+        None if matches!(name, "gray" | "grey") => return Some(Color::BRIGHT_BLACK),
+        None => (0, name),
+    };
+    let index = names.iter().position(|&n| n == base)?;
+    Some(Color::Named(index as u8 + bright))
+}
+
+fn parse_tone(name: &str) -> Option<Tone> {
+    Tone::ALL
+        .into_iter()
+        .find(|tone| tone.label().eq_ignore_ascii_case(name) && !tone.label().is_empty())
+        .or((name == "muted").then_some(Tone::Muted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attribute;
+
+    fn parts(text: &StyledText) -> Vec<&str> {
+        text.segments().iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let template = Template::parse("{icon} {name:cyan.bold} took {ms:yellow}ms").unwrap();
+        assert_eq!(
+            template.placeholders().collect::<Vec<_>>(),
+            ["icon", "name", "ms"]
+        );
+
+        let values = HashMap::from([("icon", "✓"), ("name", "build"), ("ms", "42")]);
+        let text = template.render(&values);
+        assert_eq!(parts(&text), ["✓", " ", "build", " took ", "42", "ms"]);
+        let name = &text.segments()[2].style;
+        assert_eq!(name.foreground, Some(Color::CYAN));
+        assert!(name.attributes.contains(&Attribute::Bold));
+        assert_eq!(text.segments()[4].style.foreground, Some(Color::YELLOW));
+    }
+
+    #[test]
+    fn test_missing_values_and_escapes() {
+        let template: Template = "{{{a}}} {b}!".parse().unwrap();
+        let text = template.render_with(|name| (name == "a").then(|| "x".to_string()));
+        assert_eq!(text.plain(), "{x} !");
+    }
+
+    #[test]
+    fn test_parse_style() {
+        let style = parse_style("bright_red.on_#102030.italic").unwrap();
+        assert_eq!(style.foreground, Some(Color::BRIGHT_RED));
+        assert_eq!(style.background, Some(Color::RGB(0x10, 0x20, 0x30)));
+        assert!(style.attributes.contains(&Attribute::Italic));
+
+        assert_eq!(
+            parse_style("gray").unwrap().foreground,
+            Some(Color::BRIGHT_BLACK)
+        );
+        assert_eq!(parse_style("").unwrap().foreground, None);
+        assert!(parse_style("muted").is_ok());
+        assert!(parse_style("success.bold").is_ok());
+        assert_eq!(
+            parse_style("bold.sparkly").err().as_deref(),
+            Some("sparkly")
+        );
+    }
+
+    #[test]
+    fn test_widths_and_second_styles() {
+        let template = Template::parse("{msg:.cyan}|{name:6}|{bar:40.green/dim}").unwrap();
+        let text = template.render_with(|name| Some(name.to_string()));
+        assert_eq!(text.plain(), format!("msg|name  |{:<40}", "bar"));
+        assert_eq!(text.segments()[0].style.foreground, Some(Color::CYAN));
+
+        let Part::Field(bar) = &template.parts[4] else {
+            panic!("expected a field");
+        };
+        assert_eq!(bar.width, Some(40));
+        assert_eq!(bar.style.foreground, Some(Color::GREEN));
+        assert!(
+            bar.rest_style
+                .as_ref()
+                .is_some_and(|s| s.attributes.contains(&Attribute::Dim))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for source in [
+            "{name",
+            "{}",
+            "a } b",
+            "{a:nope}",
+            "{a{b}}",
+            "{bar:4.red/nope}",
+        ] {
+            let error = Template::parse(source).expect_err(source);
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
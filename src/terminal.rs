@@ -0,0 +1,205 @@
+use std::sync::OnceLock;
+
+#[cfg(unix)]
+use crate::events::{RawMode, read_stdin, stdin_ready};
+
+// Width and height of the terminal in columns and rows, from whichever of
+// stdout, stderr or stdin is attached to one, falling back to the `COLUMNS`
+// and `LINES` environment variables
+pub fn terminal_size() -> Option<(usize, usize)> {
+    query_size().or_else(|| {
+        let var = |name| std::env::var(name).ok()?.trim().parse::<usize>().ok();
+        match (var("COLUMNS"), var("LINES")) {
+            (Some(columns), lines) if columns > 0 => Some((columns, lines.unwrap_or(24))),
+            _ => None,
+        }
+    })
+}
+
+// Terminal width for laying out output, 80 columns when it can't be found
+pub(crate) fn terminal_width() -> usize {
+    terminal_size().map_or(80, |(columns, _)| columns)
+}
+
+#[cfg(unix)]
+fn query_size() -> Option<(usize, usize)> {
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO] {
+        let mut size = std::mem::MaybeUninit::<libc::winsize>::uninit();
+        if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, size.as_mut_ptr()) } == 0 {
+            let size = unsafe { size.assume_init() };
+            if size.ws_col > 0 {
+                return Some((size.ws_col as usize, size.ws_row as usize));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(not(unix))]
+fn query_size() -> Option<(usize, usize)> {
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Background {
+    Dark,
+    Light,
+}
+
+// Whether the terminal has a dark or light background. Asks the terminal
+// for its background color (OSC 11) when attached to one, then falls back
+// to `COLORFGBG`, then to dark. Detected once and cached.
+pub fn terminal_background() -> Background {
+    static DETECTED: OnceLock<Background> = OnceLock::new();
+    *DETECTED.get_or_init(|| {
+        query_background()
+            .or_else(|| {
+                std::env::var("COLORFGBG")
+                    .ok()
+                    .and_then(|v| parse_colorfgbg(&v))
+            })
+            .unwrap_or(Background::Dark)
+    })
+}
+
+#[cfg(unix)]
+fn query_background() -> Option<Background> {
+    use std::io::Write;
+
+    let is_tty = |fd| unsafe { libc::isatty(fd) } == 1;
+    if !is_tty(libc::STDIN_FILENO) || !is_tty(libc::STDOUT_FILENO) {
+        return None;
+    }
+    let _raw = RawMode::enable().ok()?;
+    let mut stdout = std::io::stdout();
+    stdout.write_all(b"\x1b]11;?\x07").ok()?;
+    stdout.flush().ok()?;
+
+    // Terminals that don't know OSC 11 never answer
+    let mut response = Vec::new();
+    let mut buf = [0u8; 64];
+    while stdin_ready(100).ok()? {
+        let n = read_stdin(&mut buf).ok()?;
+        response.extend_from_slice(&buf[..n]);
+        if response.ends_with(b"\x07") || response.ends_with(b"\x1b\\") {
+            break;
+        }
+    }
+    parse_osc11(&response)
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<Background> {
+    None
+}
+
+// The screen row the cursor is on, counted from 0, by asking the terminal
+// (DSR 6) on stderr. Stdin must already be in raw mode so the reply isn't
+// echoed.
+#[cfg(unix)]
+pub(crate) fn query_cursor_row() -> Option<usize> {
+    crate::cleanup::write_sequence("\x1b[6n").ok()?;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 32];
+    while stdin_ready(100).ok()? {
+        let n = read_stdin(&mut buf).ok()?;
+        response.extend_from_slice(&buf[..n]);
+        if response.ends_with(b"R") {
+            break;
+        }
+    }
+    parse_cursor_row(&response)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn query_cursor_row() -> Option<usize> {
+    None
+}
+
+// Parses a reply such as `ESC [12;40R`
+fn parse_cursor_row(response: &[u8]) -> Option<usize> {
+    let response = std::str::from_utf8(response).ok()?;
+    let (row, _) = response
+        .strip_prefix("\x1b[")?
+        .strip_suffix('R')?
+        .split_once(';')?;
+    row.parse::<usize>().ok()?.checked_sub(1)
+}
+
+// Parses a reply such as `ESC ]11;rgb:1e1e/1e1e/1e1e BEL`
+fn parse_osc11(response: &[u8]) -> Option<Background> {
+    let response = std::str::from_utf8(response).ok()?;
+    let color = response.split("rgb:").nth(1)?;
+    let color = color
+        .trim_end_matches(['\x07', '\\'])
+        .trim_end_matches('\x1b');
+    let channels: Vec<f64> = color
+        .split('/')
+        .map(|hex| {
+            let max = 16f64.powi(hex.len() as i32) - 1.0;
+            u32::from_str_radix(hex, 16).ok().map(|v| v as f64 / max)
+        })
+        .collect::<Option<_>>()?;
+    let [r, g, b] = channels[..] else {
+        return None;
+    };
+    Some(background_for_luminance(
+        0.2126 * r + 0.7152 * g + 0.0722 * b,
+    ))
+}
+
+// `COLORFGBG` is `fg;bg` (sometimes `fg;default;bg`) with palette indices
+fn parse_colorfgbg(value: &str) -> Option<Background> {
+    let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(match bg {
+        7 | 9..=15 => Background::Light,
+        _ => Background::Dark,
+    })
+}
+
+fn background_for_luminance(luminance: f64) -> Background {
+    if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cursor_row() {
+        assert_eq!(parse_cursor_row(b"\x1b[12;40R"), Some(11));
+        assert_eq!(parse_cursor_row(b"\x1b[1;1R"), Some(0));
+        assert_eq!(parse_cursor_row(b"\x1b[0;1R"), None);
+        assert_eq!(parse_cursor_row(b""), None);
+    }
+
+    #[test]
+    fn test_parse_osc11() {
+        assert_eq!(
+            parse_osc11(b"\x1b]11;rgb:1e1e/1e1e/1e1e\x07"),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            parse_osc11(b"\x1b]11;rgb:ffff/fafa/f0f0\x1b\\"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            parse_osc11(b"\x1b]11;rgb:ff/ff/ff\x07"),
+            Some(Background::Light)
+        );
+        assert_eq!(parse_osc11(b""), None);
+        assert_eq!(parse_osc11(b"\x1b]11;rgb:zz/00/00\x07"), None);
+    }
+
+    #[test]
+    fn test_parse_colorfgbg() {
+        assert_eq!(parse_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(parse_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("0;default;7"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("default"), None);
+    }
+}
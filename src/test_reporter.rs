@@ -0,0 +1,288 @@
+use std::io::{self, Write};
+
+use crate::theme::{Tone, theme};
+use crate::{Console, Summary};
+
+// Columns of dots before starting a new line
+const DOTS_PER_LINE: usize = 80;
+
+struct Failure {
+    name: String,
+    message: String,
+    diff: Option<(String, String)>,
+}
+
+// Output for a custom test or lint runner: progress as each test finishes,
+// either a dot per test (`.` passed, `F` failed, `s` skipped) or a line per
+// test with its icon, then, once everything has run, the failures grouped
+// together with diffs of expected and actual output, and a summary line.
+pub struct TestReporter<W: Write> {
+    out: W,
+    lines: bool,
+    // Dots on the current line
+    column: usize,
+    passed: usize,
+    skipped: usize,
+    failures: Vec<Failure>,
+}
+
+impl<W: Write> TestReporter<W> {
+    pub fn new(out: W) -> Self {
+        TestReporter {
+            out,
+            lines: false,
+            column: 0,
+            passed: 0,
+            skipped: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    // A line per test with its name instead of a dot
+    pub fn lines(mut self, enabled: bool) -> Self {
+        self.lines = enabled;
+        self
+    }
+
+    pub fn pass<T: Into<String>>(&mut self, name: T) -> io::Result<()> {
+        self.passed += 1;
+        self.progress(Tone::Success, ".", &name.into())
+    }
+
+    pub fn skip<T: Into<String>>(&mut self, name: T) -> io::Result<()> {
+        self.skipped += 1;
+        self.progress(Tone::Warning, "s", &name.into())
+    }
+
+    // Records a failure; `message` is shown with the other failures at the
+    // end
+    pub fn fail<T: Into<String>, M: Into<String>>(
+        &mut self,
+        name: T,
+        message: M,
+    ) -> io::Result<()> {
+        self.failure(name.into(), message.into(), None)
+    }
+
+    // Records a failure whose details include a line diff of `expected`
+    // against `actual`
+    pub fn fail_with_diff<T, M, E, A>(
+        &mut self,
+        name: T,
+        message: M,
+        expected: E,
+        actual: A,
+    ) -> io::Result<()>
+    where
+        T: Into<String>,
+        M: Into<String>,
+        E: Into<String>,
+        A: Into<String>,
+    {
+        let diff = Some((expected.into(), actual.into()));
+        self.failure(name.into(), message.into(), diff)
+    }
+
+    fn failure(
+        &mut self,
+        name: String,
+        message: String,
+        diff: Option<(String, String)>,
+    ) -> io::Result<()> {
+        self.progress(Tone::Error, "F", &name)?;
+        self.failures.push(Failure {
+            name,
+            message,
+            diff,
+        });
+        Ok(())
+    }
+
+    fn progress(&mut self, tone: Tone, dot: &str, name: &str) -> io::Result<()> {
+        let theme = theme();
+        let style = theme.style(tone).clone();
+        if self.lines {
+            let line = match tone {
+                Tone::Warning => format!(
+                    "{} {}",
+                    Console::new_with_style(theme.icon(Tone::Muted), style),
+                    Console::new_with_style(
+                        format!("{} (skipped)", name),
+                        theme.style(Tone::Muted).clone()
+                    )
+                ),
+                _ => format!(
+                    "{} {}",
+                    Console::new_with_style(theme.icon(tone), style),
+                    name
+                ),
+            };
+            writeln!(self.out, "{}", line)?;
+        } else {
+            if self.column == DOTS_PER_LINE {
+                writeln!(self.out)?;
+                self.column = 0;
+            }
+            write!(self.out, "{}", Console::new_with_style(dot, style))?;
+            self.column += 1;
+        }
+        self.out.flush()
+    }
+
+    // Prints the failures and the summary line. Returns whether every test
+    // passed or was skipped.
+    pub fn finish(mut self) -> io::Result<bool> {
+        if self.column > 0 {
+            writeln!(self.out)?;
+        }
+        let theme = theme();
+        let error = theme.style(Tone::Error).clone();
+        if !self.failures.is_empty() {
+            writeln!(self.out, "\n{}", Console::new("failures:").bold())?;
+        }
+        for failure in &self.failures {
+            writeln!(
+                self.out,
+                "\n{} {}",
+                Console::new_with_style(theme.icon(Tone::Error), error.clone()),
+                Console::new_with_style(failure.name.as_str(), error.clone().bold())
+            )?;
+            for line in failure.message.lines() {
+                writeln!(self.out, "  {}", line)?;
+            }
+            if let Some((expected, actual)) = &failure.diff {
+                for (change, line) in diff_lines(expected, actual) {
+                    let text = format!("  {} {}", change.sign(), line);
+                    let style = match change {
+                        Change::Removed => error.clone(),
+                        Change::Added => theme.style(Tone::Success).clone(),
+                        Change::Same => theme.style(Tone::Muted).clone(),
+                    };
+                    writeln!(self.out, "{}", Console::new_with_style(text, style))?;
+                }
+            }
+        }
+        let summary = Summary::new()
+            .passed(self.passed)
+            .failed(self.failures.len())
+            .skipped(self.skipped)
+            .hide_zero(true);
+        writeln!(self.out, "\n{}", summary)?;
+        self.out.flush()?;
+        Ok(self.failures.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+impl Change {
+    fn sign(self) -> char {
+        match self {
+            Change::Same => ' ',
+            Change::Removed => '-',
+            Change::Added => '+',
+        }
+    }
+}
+
+// The lines of `expected` and `actual` in order, marked as in both, only
+// expected (removed) or only actual (added), from their longest common
+// subsequence
+fn diff_lines<'a>(expected: &'a str, actual: &'a str) -> Vec<(Change, &'a str)> {
+    let a: Vec<&str> = expected.lines().collect();
+    let b: Vec<&str> = actual.lines().collect();
+    // common[i][j] is the length of the longest common subsequence of
+    // a[i..] and b[j..]
+    let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if a[i] == b[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push((Change::Same, a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push((Change::Removed, a[i]));
+            i += 1;
+        } else {
+            lines.push((Change::Added, b[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{CaptureWriter, strip_ansi};
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nx\nc\nd"),
+            [
+                (Change::Same, "a"),
+                (Change::Removed, "b"),
+                (Change::Added, "x"),
+                (Change::Same, "c"),
+                (Change::Added, "d"),
+            ]
+        );
+        assert_eq!(diff_lines("", "a"), [(Change::Added, "a")]);
+    }
+
+    #[test]
+    fn test_dots_and_failures() {
+        let out = CaptureWriter::new();
+        let mut reporter = TestReporter::new(out.clone());
+        reporter.pass("lexer::empty").unwrap();
+        reporter.skip("lexer::slow").unwrap();
+        reporter
+            .fail_with_diff("parser::nested", "output differs", "(a\n b)", "(a\n c)")
+            .unwrap();
+        assert_eq!(strip_ansi(&out.contents()), ".sF");
+        assert!(!reporter.finish().unwrap());
+        assert_eq!(
+            strip_ansi(&out.contents()),
+            ".sF\n\
+             \n\
+             failures:\n\
+             \n\
+             ✗ parser::nested\n  \
+             output differs\n    \
+             (a\n  \
+             -  b)\n  \
+             +  c)\n\
+             \n\
+             1 passed, 1 failed, 1 skipped\n"
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let out = CaptureWriter::new();
+        let mut reporter = TestReporter::new(out.clone()).lines(true);
+        reporter.pass("lexer::empty").unwrap();
+        reporter.skip("lexer::slow").unwrap();
+        assert!(reporter.finish().unwrap());
+        assert_eq!(
+            strip_ansi(&out.contents()),
+            "✓ lexer::empty\n· lexer::slow (skipped)\n\n1 passed, 1 skipped\n"
+        );
+    }
+}
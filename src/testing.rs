@@ -0,0 +1,182 @@
+//! Helpers for testing styled output: a capture writer, a virtual terminal
+//! for live output, ANSI stripping and normalization, and `assert_styled_eq!`
+//! which shows escape codes as `␛[31m` when the comparison fails.
+
+use std::fmt::{Display, Write as _};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use crate::parse_ansi;
+
+pub use crate::virtual_terminal::VirtualTerminal;
+
+// A `Write` target that keeps everything written to it. Clones share the
+// same buffer, so one can be handed to the code under test and the other
+// kept for assertions.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureWriter {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl CaptureWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.lock().unwrap()).into_owned()
+    }
+
+    pub fn plain(&self) -> String {
+        strip_ansi(&self.contents())
+    }
+
+    pub fn clear(&self) {
+        self.buffer.lock().unwrap().clear();
+    }
+}
+
+impl io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn strip_ansi(text: &str) -> String {
+    parse_ansi(text).plain()
+}
+
+// Re-renders the text so equivalent styling produces identical escape codes,
+// e.g. redundant or repeated sequences are collapsed
+pub fn normalize_ansi(text: &str) -> String {
+    parse_ansi(text).to_string()
+}
+
+// Replaces ESC with `␛` and other control characters (except newlines) with
+// their Unicode control pictures
+pub fn visible_escapes(text: &str) -> String {
+    text.chars()
+        .map(|ch| match ch {
+            '\x1b' => '␛',
+            '\n' => '\n',
+            '\x7f' => '␡',
+            c if (c as u32) < 0x20 => char::from_u32(0x2400 + c as u32).unwrap(),
+            c => c,
+        })
+        .collect()
+}
+
+#[macro_export]
+macro_rules! assert_styled_eq {
+    ($actual:expr, $expected:expr $(,)?) => {
+        $crate::testing::assert_styled_eq_impl(&$actual, &$expected)
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_styled_eq_impl(actual: &dyn Display, expected: &dyn Display) {
+    let actual = actual.to_string();
+    let expected = expected.to_string();
+    if actual != expected {
+        panic!("{}", styled_diff(&actual, &expected));
+    }
+}
+
+fn styled_diff(actual: &str, expected: &str) -> String {
+    let mut message = String::from("styled output does not match\n");
+    let actual_lines: Vec<&str> = actual.split('\n').collect();
+    let expected_lines: Vec<&str> = expected.split('\n').collect();
+
+    for i in 0..actual_lines.len().max(expected_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(e), Some(a)) if e == a => {
+                let _ = writeln!(message, "  {}", visible_escapes(e));
+            }
+            (e, a) => {
+                if let Some(e) = e {
+                    let _ = writeln!(message, "- {}", visible_escapes(e));
+                }
+                if let Some(a) = a {
+                    let _ = writeln!(message, "+ {}", visible_escapes(a));
+                }
+            }
+        }
+    }
+
+    if strip_ansi(actual) == strip_ansi(expected) {
+        message.push_str("(the text is identical, only the styling differs)\n");
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Console;
+    use std::io::Write;
+
+    #[test]
+    fn test_capture_writer() {
+        let capture = CaptureWriter::new();
+        let mut writer = capture.clone();
+        Console::new("captured")
+            .red()
+            .write_to(&mut writer)
+            .unwrap();
+        writer.write_all(b"!").unwrap();
+
+        assert_eq!(capture.plain(), "captured!");
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(capture.contents(), "\x1b[38;5;1mcaptured\x1b[0m!");
+
+        capture.clear();
+        assert_eq!(capture.contents(), "");
+    }
+
+    #[test]
+    fn test_strip_and_normalize() {
+        assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: x"), "error: x");
+        assert_eq!(
+            normalize_ansi("\x1b[31m\x1b[1merr\x1b[0m\x1b[0m"),
+            normalize_ansi("\x1b[1;31merr\x1b[0m")
+        );
+    }
+
+    #[test]
+    fn test_visible_escapes() {
+        assert_eq!(visible_escapes("\x1b[31mred\x1b[0m"), "␛[31mred␛[0m");
+        assert_eq!(visible_escapes("a\tb\r\n"), "a␉b␍\n");
+    }
+
+    #[test]
+    fn test_assert_styled_eq_passes() {
+        let console = Console::new("same");
+        assert_styled_eq!(console, "same");
+        assert_styled_eq!(String::from("x"), "x");
+    }
+
+    #[test]
+    fn test_styled_diff_message() {
+        let diff = styled_diff("\x1b[32mok\x1b[0m\nsame", "\x1b[31mok\x1b[0m\nsame");
+        assert_eq!(
+            diff,
+            "styled output does not match\n\
+             - ␛[31mok␛[0m\n\
+             + ␛[32mok␛[0m\n  \
+             same\n\
+             (the text is identical, only the styling differs)\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "- ␛[31mred␛[0m")]
+    fn test_assert_styled_eq_fails() {
+        assert_styled_eq!("red", "\x1b[31mred\x1b[0m");
+    }
+}
@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::unicode;
+use crate::{Console, Style, write_padded};
+
+// A run of differently styled segments rendered one after another
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StyledText {
+    segments: Vec<Console>,
+}
+
+impl StyledText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, segment: Console) {
+        self.segments.push(segment);
+    }
+
+    pub fn then(mut self, segment: Console) -> Self {
+        self.push(segment);
+        self
+    }
+
+    // Appends text in `style`, extending the last segment when it's styled
+    // the same
+    pub(crate) fn push_styled(&mut self, text: &str, style: &Style) {
+        match self.segments.last_mut() {
+            Some(last) if last.style == *style => last.text.push_str(text),
+            _ => self.push(Console::new_with_style(text, style.clone())),
+        }
+    }
+
+    pub fn segments(&self) -> &[Console] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.iter().all(|s| s.text.is_empty())
+    }
+
+    // The text without any styling
+    pub fn plain(&self) -> String {
+        self.segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    // Terminal columns taken by the text
+    pub fn width(&self) -> usize {
+        self.segments.iter().map(|s| unicode::width(&s.text)).sum()
+    }
+
+    // Grapheme clusters paired with the style of their segment
+    pub fn graphemes(&self) -> impl Iterator<Item = (&str, &Style)> {
+        self.segments
+            .iter()
+            .flat_map(|s| unicode::graphemes(&s.text).map(move |g| (g, &s.style)))
+    }
+
+    // The part that fits in `columns`, never splitting a grapheme cluster
+    pub fn take_visible(&self, columns: usize) -> StyledText {
+        self.split_at_visible(columns).0
+    }
+
+    // Layers a style on each character, e.g. for gradients or alternating
+    // colors. The closure gets the character's index and the character;
+    // combining marks and emoji sequences are kept with the character they
+    // belong to, and runs that end up with the same style are merged.
+    pub fn map_chars<F>(&self, mut style_for: F) -> StyledText
+    where
+        F: FnMut(usize, char) -> Style,
+    {
+        let mut mapped = StyledText::new();
+        for (index, (cluster, base)) in self.graphemes().enumerate() {
+            let first = cluster.chars().next().unwrap_or_default();
+            mapped.push_styled(cluster, &base.merge(&style_for(index, first)));
+        }
+        mapped
+    }
+
+    pub fn split_at_visible(&self, columns: usize) -> (StyledText, StyledText) {
+        let mut head = StyledText::new();
+        let mut tail = StyledText::new();
+        let mut remaining = columns;
+        let mut segments = self.segments.iter();
+
+        for segment in segments.by_ref() {
+            let fits = unicode::take_width(&segment.text, remaining);
+            if fits.len() == segment.text.len() {
+                remaining -= unicode::width(fits);
+                head.push(segment.clone());
+                continue;
+            }
+            let (left, right) = segment.text.split_at(fits.len());
+            if !left.is_empty() {
+                head.push(Console::new_with_style(left, segment.style.clone()));
+            }
+            tail.push(Console::new_with_style(right, segment.style.clone()));
+            break;
+        }
+        tail.extend(segments.cloned());
+        (head, tail)
+    }
+}
+
+impl From<Console> for StyledText {
+    fn from(segment: Console) -> Self {
+        StyledText {
+            segments: vec![segment],
+        }
+    }
+}
+
+impl From<&str> for StyledText {
+    fn from(text: &str) -> Self {
+        StyledText::from(Console::new(text))
+    }
+}
+
+impl From<String> for StyledText {
+    fn from(text: String) -> Self {
+        StyledText::from(Console::new(text))
+    }
+}
+
+impl FromIterator<Console> for StyledText {
+    fn from_iter<I: IntoIterator<Item = Console>>(iter: I) -> Self {
+        StyledText {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Console> for StyledText {
+    fn extend<I: IntoIterator<Item = Console>>(&mut self, iter: I) {
+        self.segments.extend(iter);
+    }
+}
+
+// Width, alignment and precision apply to the text as a whole, as for
+// `Console`
+impl fmt::Display for StyledText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match f.precision() {
+            Some(columns) => Cow::Owned(self.take_visible(columns)),
+            None => Cow::Borrowed(self),
+        };
+        write_padded(f, text.width(), |f| {
+            for segment in &text.segments {
+                write!(f, "{}", segment)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styled_text_plain() {
+        let text = StyledText::new()
+            .then(Console::new("error").red().bold())
+            .then(Console::new(": file not found"));
+
+        assert_eq!(text.segments().len(), 2);
+        assert_eq!(text.plain(), "error: file not found");
+        assert!(!text.is_empty());
+        assert!(StyledText::from(Console::new("")).is_empty());
+    }
+
+    #[test]
+    fn test_split_at_visible() {
+        let text = StyledText::new()
+            .then(Console::new("ab").red())
+            .then(Console::new("ce\u{301}d"))
+            .then(Console::new("!").bold());
+
+        let (head, tail) = text.split_at_visible(4);
+        assert_eq!(head.plain(), "abce\u{301}");
+        assert_eq!(tail.plain(), "d!");
+        assert_eq!(head.segments().len(), 2);
+        assert_eq!(tail.segments().len(), 2);
+        assert_eq!(text.take_visible(2).segments().len(), 1);
+        assert_eq!(text.take_visible(10).plain(), text.plain());
+        assert_eq!(text.width(), 6);
+
+        let graphemes: Vec<&str> = text.graphemes().map(|(g, _)| g).collect();
+        assert_eq!(graphemes, ["a", "b", "c", "e\u{301}", "d", "!"]);
+    }
+
+    #[test]
+    fn test_map_chars() {
+        use crate::Color;
+
+        let text = StyledText::new()
+            .then(Console::new("ab").bold())
+            .then(Console::new("cde\u{301}"));
+        let striped = text.map_chars(|i, _| match i % 2 {
+            0 => Style::new().fg(Color::RED),
+            _ => Style::new().fg(Color::BLUE),
+        });
+        let parts: Vec<&str> = striped.segments().iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(parts, ["a", "b", "c", "d", "e\u{301}"]);
+        assert_eq!(striped.segments()[1].style.foreground, Some(Color::BLUE));
+        assert!(
+            striped.segments()[1]
+                .style
+                .attributes
+                .contains(&crate::Attribute::Bold)
+        );
+
+        let vowels = StyledText::from("aei!").map_chars(|_, ch| match ch {
+            'a' | 'e' | 'i' => Style::new().bold(),
+            _ => Style::new(),
+        });
+        assert_eq!(vowels.segments().len(), 2);
+        assert_eq!(vowels.plain(), "aei!");
+    }
+
+    #[test]
+    fn test_styled_text_display() {
+        let text: StyledText = [Console::new("a").red(), Console::new("b")]
+            .into_iter()
+            .collect();
+
+        #[cfg(not(feature = "no-color"))]
+        assert_eq!(text.to_string(), "\x1b[38;5;1ma\x1b[0mb");
+        #[cfg(feature = "no-color")]
+        assert_eq!(text.to_string(), "ab");
+    }
+
+    #[test]
+    fn test_styled_text_format_flags() {
+        let text = StyledText::new()
+            .then(Console::new("ab"))
+            .then(Console::new("cd"));
+        assert_eq!(format!("[{:>6}]", text), "[  abcd]");
+        assert_eq!(format!("[{:.3}]", text), "[abc]");
+        assert_eq!(format!("[{:*<5.3}]", text), "[abc**]");
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::RwLock;
+
+use crate::symbols::symbols;
+use crate::{Color, Style};
+
+// The kinds of message components style by meaning rather than by color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tone {
+    Note,
+    Tip,
+    Info,
+    Success,
+    Warning,
+    Error,
+    Hint,
+    Muted,
+}
+
+impl Tone {
+    pub(crate) const ALL: [Tone; 8] = [
+        Tone::Note,
+        Tone::Tip,
+        Tone::Info,
+        Tone::Success,
+        Tone::Warning,
+        Tone::Error,
+        Tone::Hint,
+        Tone::Muted,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tone::Note => "Note",
+            Tone::Tip => "Tip",
+            Tone::Info => "Info",
+            Tone::Success => "Success",
+            Tone::Warning => "Warning",
+            Tone::Error => "Error",
+            Tone::Hint => "Hint",
+            Tone::Muted => "",
+        }
+    }
+}
+
+// Semantic styles and icons shared by the components. Install a customized
+// theme with `set_theme`; components read it when they render.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    styles: [Style; 8],
+    icons: [String; 8],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let style = |tone| match tone {
+            Tone::Note => Style::new().fg(Color::BLUE),
+            Tone::Tip => Style::new().fg(Color::GREEN),
+            Tone::Info => Style::new().fg(Color::CYAN),
+            Tone::Success => Style::new().fg(Color::GREEN),
+            Tone::Warning => Style::new().fg(Color::YELLOW),
+            Tone::Error => Style::new().fg(Color::RED),
+            Tone::Hint => Style::new().fg(Color::CYAN).dim(),
+            Tone::Muted => Style::new().dim(),
+        };
+        let symbols = symbols();
+        let icon = |tone| match tone {
+            Tone::Note | Tone::Info => symbols.info,
+            Tone::Tip => symbols.star,
+            Tone::Success => symbols.check,
+            Tone::Warning => symbols.warning,
+            Tone::Error => symbols.cross,
+            Tone::Hint => symbols.arrow,
+            Tone::Muted => symbols.bullet,
+        };
+        Theme {
+            styles: Tone::ALL.map(style),
+            icons: Tone::ALL.map(|tone| icon(tone).to_string()),
+        }
+    }
+}
+
+static CURRENT: RwLock<Option<Theme>> = RwLock::new(None);
+
+pub fn set_theme(theme: Theme) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(theme);
+}
+
+// The installed theme, or the default one
+pub fn theme() -> Theme {
+    CURRENT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_style(mut self, tone: Tone, style: Style) -> Self {
+        self.styles[tone.index()] = style;
+        self
+    }
+
+    pub fn with_icon<T: Into<String>>(mut self, tone: Tone, icon: T) -> Self {
+        self.icons[tone.index()] = icon.into();
+        self
+    }
+
+    pub fn style(&self, tone: Tone) -> &Style {
+        &self.styles[tone.index()]
+    }
+
+    pub fn icon(&self, tone: Tone) -> &str {
+        &self.icons[tone.index()]
+    }
+
+    // The tone's style labeled with its name, e.g. `ERROR`, for the part of
+    // a message that says what kind it is. Accessible mode shows the label
+    // as a `[ERROR]` prefix in place of the color.
+    pub fn labeled_style(&self, tone: Tone) -> Style {
+        let style = self.style(tone).clone();
+        match tone.label() {
+            "" => style,
+            label => style.label(label.to_uppercase()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_customize_theme() {
+        let theme = Theme::new()
+            .with_style(Tone::Warning, Style::new().fg(Color::MAGENTA).bold())
+            .with_icon(Tone::Warning, "!");
+        assert_eq!(theme.icon(Tone::Warning), "!");
+        assert_eq!(theme.style(Tone::Warning).foreground, Some(Color::MAGENTA));
+        assert_eq!(theme.style(Tone::Error).foreground, Some(Color::RED));
+        assert_eq!(theme.icon(Tone::Success), "✓");
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+static MAX_FPS: AtomicU32 = AtomicU32::new(20);
+
+// Caps how often live components redraw, in frames per second; 0 lifts the
+// cap. The default of 20 keeps tight update loops from flooding the
+// terminal, which matters most over SSH.
+pub fn set_max_fps(fps: u32) {
+    MAX_FPS.store(fps, Ordering::Relaxed);
+}
+
+pub fn max_fps() -> u32 {
+    MAX_FPS.load(Ordering::Relaxed)
+}
+
+fn frame_interval() -> Option<Duration> {
+    match max_fps() {
+        0 => None,
+        fps => Some(Duration::from_secs(1) / fps),
+    }
+}
+
+// Decides whether a live component may redraw now. Updates that come too
+// soon after the last frame are skipped; components always render their
+// latest state, so the next frame catches up on all of them.
+#[derive(Debug, Default)]
+pub(crate) struct Throttle {
+    last: Option<Instant>,
+}
+
+impl Throttle {
+    pub(crate) fn ready(&mut self, now: Instant) -> bool {
+        self.ready_within(now, frame_interval())
+    }
+
+    fn ready_within(&mut self, now: Instant, interval: Option<Duration>) -> bool {
+        if let (Some(last), Some(interval)) = (self.last, interval)
+            && now.saturating_duration_since(last) < interval
+        {
+            return false;
+        }
+        self.drawn(now);
+        true
+    }
+
+    // Records a frame drawn regardless of the cap, e.g. a final state
+    pub(crate) fn drawn(&mut self, now: Instant) {
+        self.last = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updates_within_a_frame_are_folded() {
+        let interval = Some(Duration::from_millis(50));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut throttle = Throttle::default();
+
+        assert!(throttle.ready_within(at(0), interval));
+        assert!(!throttle.ready_within(at(10), interval));
+        assert!(!throttle.ready_within(at(49), interval));
+        assert!(throttle.ready_within(at(50), interval));
+
+        throttle.drawn(at(60));
+        assert!(!throttle.ready_within(at(100), interval));
+        assert!(throttle.ready_within(at(101), None));
+    }
+}
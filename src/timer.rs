@@ -0,0 +1,185 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::Console;
+use crate::clock::{Clock, SystemClock};
+use crate::format::human_duration;
+use crate::group;
+use crate::symbols::symbols;
+use crate::theme::{Tone, theme};
+
+// Times an operation: prints a start line when created and the elapsed time
+// when finished or dropped, colored green, yellow or red against the
+// `fast` and `slow` thresholds. Lines go to stderr.
+pub struct Timer {
+    label: String,
+    clock: Box<dyn Clock>,
+    start: Instant,
+    fast: Duration,
+    slow: Duration,
+    finished: bool,
+}
+
+impl Timer {
+    pub fn start<T: Into<String>>(label: T) -> Self {
+        let label = label.into();
+        crate::ignore_broken_pipe(group::write_stderr(&format!("{}\n", start_line(&label))));
+        Timer {
+            label,
+            clock: Box::new(SystemClock),
+            start: Instant::now(),
+            fast: Duration::from_secs(1),
+            slow: Duration::from_secs(10),
+            finished: false,
+        }
+    }
+
+    // Anything under `fast` is green, anything from `slow` on is red
+    pub fn thresholds(mut self, fast: Duration, slow: Duration) -> Self {
+        self.fast = fast;
+        self.slow = slow.max(fast);
+        self
+    }
+
+    // Reads the time from `clock` instead of the system, e.g. a
+    // `ManualClock` in tests. Timing starts over from the clock's current
+    // time.
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.start = clock.now();
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_duration_since(self.start)
+    }
+
+    pub fn finish(mut self) -> Duration {
+        self.report()
+    }
+
+    fn report(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        self.finished = true;
+        let line = finish_line(&self.label, elapsed, self.fast, self.slow);
+        crate::ignore_broken_pipe(group::write_stderr(&format!("{}\n", line)));
+        elapsed
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Timer")
+            .field("label", &self.label)
+            .field("start", &self.start)
+            .field("fast", &self.fast)
+            .field("slow", &self.slow)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.report();
+        }
+    }
+}
+
+// Runs a block between a start line and a line with its elapsed time,
+// evaluating to the block's value
+#[macro_export]
+macro_rules! timed {
+    ($label:expr, $body:expr $(,)?) => {{
+        let timer = $crate::Timer::start($label);
+        let value = $body;
+        timer.finish();
+        value
+    }};
+}
+
+fn start_line(label: &str) -> String {
+    format!(
+        "{} {}",
+        Console::new_with_style(symbols().arrow, theme().style(Tone::Info).clone()),
+        label
+    )
+}
+
+fn finish_line(label: &str, elapsed: Duration, fast: Duration, slow: Duration) -> String {
+    let theme = theme();
+    format!(
+        "{} {} {}",
+        Console::new_with_style(symbols().check, theme.style(Tone::Success).clone()),
+        label,
+        Console::new_with_style(
+            format!("in {}", human_duration(elapsed).plain()),
+            theme.style(speed(elapsed, fast, slow)).clone()
+        )
+    )
+}
+
+fn speed(elapsed: Duration, fast: Duration, slow: Duration) -> Tone {
+    if elapsed < fast {
+        Tone::Success
+    } else if elapsed < slow {
+        Tone::Warning
+    } else {
+        Tone::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualClock;
+    use crate::testing::strip_ansi;
+
+    const FAST: Duration = Duration::from_secs(1);
+    const SLOW: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_speed_thresholds() {
+        assert_eq!(speed(Duration::from_millis(999), FAST, SLOW), Tone::Success);
+        assert_eq!(speed(FAST, FAST, SLOW), Tone::Warning);
+        assert_eq!(speed(SLOW, FAST, SLOW), Tone::Error);
+    }
+
+    #[test]
+    fn test_clock() {
+        let clock = ManualClock::new();
+        let timer = Timer::start("compiling").clock(clock.clone());
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(timer.elapsed(), Duration::from_millis(1500));
+        assert_eq!(timer.finish(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(strip_ansi(&start_line("compiling")), "→ compiling");
+        assert_eq!(
+            strip_ansi(&finish_line(
+                "compiling",
+                Duration::from_secs(133),
+                FAST,
+                SLOW
+            )),
+            "✓ compiling in 2m 13s"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_elapsed_time_is_colored() {
+        assert!(
+            finish_line("x", Duration::from_secs(30), FAST, SLOW)
+                .ends_with(&Console::new("in 30s").red().to_string())
+        );
+    }
+
+    #[test]
+    fn test_timed_returns_block_value() {
+        assert_eq!(crate::timed!("adding", { 1 + 2 }), 3);
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::Console;
+use crate::theme::{Tone, theme};
+
+// What to stamp each line with: the local time of day, or the time since
+// the writer or reporter was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timestamp {
+    WallClock,
+    Elapsed,
+}
+
+impl Timestamp {
+    // A line prefix for `LinePrefixer`, measuring elapsed time from now
+    pub(crate) fn prefixer(self) -> impl FnMut(usize) -> String + Send + Sync + 'static {
+        let start = Instant::now();
+        move |_| {
+            let text = match self {
+                Timestamp::WallClock => clock_now(),
+                Timestamp::Elapsed => format_elapsed(start.elapsed()),
+            };
+            format!(
+                "{} ",
+                Console::new_with_style(text, theme().style(Tone::Muted).clone())
+            )
+        }
+    }
+}
+
+// The local time of day, e.g. `13:05:09`
+pub(crate) fn clock_now() -> String {
+    format_clock(local_seconds_of_day(SystemTime::now()))
+}
+
+fn format_clock(seconds: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// Fixed width up to 10000 seconds so stamped lines stay aligned
+fn format_elapsed(elapsed: Duration) -> String {
+    format!("{:>9.3}s", elapsed.as_secs_f64())
+}
+
+#[cfg(unix)]
+fn local_seconds_of_day(time: SystemTime) -> u64 {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut tm = std::mem::MaybeUninit::<libc::tm>::uninit();
+    let time = seconds as libc::time_t;
+    if unsafe { libc::localtime_r(&time, tm.as_mut_ptr()) }.is_null() {
+        return seconds % 86_400;
+    }
+    let tm = unsafe { tm.assume_init() };
+    (tm.tm_hour * 3600 + tm.tm_min * 60 + tm.tm_sec) as u64
+}
+
+// UTC where the local offset isn't available
+#[cfg(not(unix))]
+fn local_seconds_of_day(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+        % 86_400
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0), "00:00:00");
+        assert_eq!(format_clock(13 * 3600 + 5 * 60 + 9), "13:05:09");
+        assert_eq!(format_clock(86_400 + 61), "00:01:01");
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(1234)), "    1.234s");
+        assert_eq!(format_elapsed(Duration::from_secs(754)), "  754.000s");
+    }
+
+    #[test]
+    fn test_prefix_is_dim_and_spaced() {
+        let mut prefix = Timestamp::Elapsed.prefixer();
+        let text = strip_ansi(&prefix(0));
+        assert!(text.starts_with("    0.0"), "{text}");
+        assert!(text.ends_with("s "), "{text}");
+        assert_eq!(strip_ansi(&Timestamp::WallClock.prefixer()(3)).len(), 9);
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::{self, Write};
+
+use crate::live::LiveRegion;
+use crate::theme::{Tone, theme};
+use crate::{Console, StyledText};
+
+// The line an answered prompt collapses to, e.g. `? Project name · my-app`,
+// dimmed apart from the answer so scrollback stays easy to skim
+pub fn prompt_transcript(prompt: &str, answer: &str) -> StyledText {
+    let theme = theme();
+    let muted = theme.style(Tone::Muted).clone();
+    let answer = if answer.is_empty() {
+        Console::new_with_style("none", muted.clone().italic())
+    } else {
+        Console::new_with_style(answer, theme.style(Tone::Info).clone())
+    };
+    StyledText::new()
+        .then(Console::new_with_style(format!("? {} · ", prompt), muted))
+        .then(answer)
+}
+
+// Replaces a finished prompt with its transcript line, or clears it when
+// transcripts are turned off
+pub(crate) fn finish_prompt<W: Write>(
+    region: &mut LiveRegion<W>,
+    transcript: bool,
+    prompt: &str,
+    answer: &str,
+) -> io::Result<()> {
+    if !transcript {
+        return region.clear();
+    }
+    region.draw([prompt_transcript(prompt, answer)])?;
+    region.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::strip_ansi;
+
+    #[test]
+    fn test_prompt_transcript() {
+        assert_eq!(
+            prompt_transcript("Project name", "my-app").plain(),
+            "? Project name · my-app"
+        );
+        assert_eq!(
+            prompt_transcript("Features", "").plain(),
+            "? Features · none"
+        );
+    }
+
+    #[test]
+    fn test_finish_prompt() {
+        let mut region = LiveRegion::new(Vec::new());
+        region.draw(["? Pick", "❯ a", "  b"]).unwrap();
+        finish_prompt(&mut region, true, "Pick", "a").unwrap();
+        let output = String::from_utf8(region.into_inner()).unwrap();
+        assert!(strip_ansi(&output).ends_with("? Pick · a\n"), "{output:?}");
+
+        let mut region = LiveRegion::new(Vec::new());
+        region.draw(["? Pick"]).unwrap();
+        finish_prompt(&mut region, false, "Pick", "a").unwrap();
+        assert_eq!(region.height(), 0);
+        assert!(
+            !String::from_utf8(region.into_inner())
+                .unwrap()
+                .contains('·')
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-color"))]
+    fn test_only_the_answer_stands_out() {
+        assert_eq!(
+            prompt_transcript("Name", "x").to_string(),
+            format!(
+                "{}{}",
+                Console::new("? Name · ").dim(),
+                Console::new("x").cyan()
+            )
+        );
+    }
+}
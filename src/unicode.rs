@@ -0,0 +1,119 @@
+// Grapheme clusters and display widths for layout. With the `unicode`
+// feature these follow UAX #29 and East Asian Width; without it a cluster
+// is a character plus any combining marks, variation selectors, skin tones
+// and ZWJ-joined characters after it, one column wide each.
+
+#[cfg(feature = "unicode")]
+pub(crate) fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    unicode_segmentation::UnicodeSegmentation::graphemes(text, true)
+}
+
+#[cfg(not(feature = "unicode"))]
+pub(crate) fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+        let mut end = first.len_utf8();
+        let mut joined = false;
+        for (i, ch) in chars {
+            if !(joined || is_extending(ch)) {
+                break;
+            }
+            joined = ch == '\u{200D}';
+            end = i + ch.len_utf8();
+        }
+        let (cluster, tail) = rest.split_at(end);
+        rest = tail;
+        Some(cluster)
+    })
+}
+
+#[cfg(not(feature = "unicode"))]
+fn is_extending(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{200D}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FE20}'..='\u{FE2F}'
+            | '\u{1F3FB}'..='\u{1F3FF}'
+            | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+// Terminal columns taken by the text
+#[cfg(feature = "unicode")]
+pub(crate) fn width(text: &str) -> usize {
+    unicode_width::UnicodeWidthStr::width(text)
+}
+
+#[cfg(not(feature = "unicode"))]
+pub(crate) fn width(text: &str) -> usize {
+    graphemes(text).count()
+}
+
+// The longest prefix that fits in `columns`, never splitting a cluster
+pub(crate) fn take_width(text: &str, columns: usize) -> &str {
+    let mut used = 0;
+    let mut end = 0;
+    for cluster in graphemes(text) {
+        used += width(cluster);
+        if used > columns {
+            break;
+        }
+        end += cluster.len();
+    }
+    &text[..end]
+}
+
+// Shortens text to at most `columns`, ending in `ellipsis` when cut
+pub(crate) fn truncate(text: &str, columns: usize, ellipsis: &str) -> String {
+    if width(text) <= columns {
+        return text.to_string();
+    }
+    let ellipsis = take_width(ellipsis, columns);
+    let mut cut = take_width(text, columns - width(ellipsis)).to_string();
+    cut.push_str(ellipsis);
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphemes_keep_clusters_together() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let text = format!("e\u{301}👍🏽{}x", family);
+        let clusters: Vec<&str> = graphemes(&text).collect();
+        assert_eq!(clusters, ["e\u{301}", "👍🏽", family, "x"]);
+    }
+
+    #[test]
+    fn test_take_width() {
+        assert_eq!(take_width("abc", 2), "ab");
+        assert_eq!(take_width("e\u{301}e\u{301}", 1), "e\u{301}");
+        assert_eq!(take_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("abcdef", 6, "…"), "abcdef");
+        assert_eq!(truncate("abcdef", 4, "…"), "abc…");
+        assert_eq!(truncate("abcdef", 4, "..."), "a...");
+        assert_eq!(truncate("abcdef", 2, "..."), "..");
+        assert_eq!(truncate("abcdef", 0, "…"), "");
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn test_wide_characters() {
+        assert_eq!(width("日本"), 4);
+        assert_eq!(take_width("日本", 3), "日");
+        assert_eq!(truncate("日本語", 5, "…"), "日本…");
+    }
+}
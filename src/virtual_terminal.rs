@@ -0,0 +1,438 @@
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::ansi::{apply_sgr, same_style};
+use crate::{Console, Style, StyledText, unicode};
+
+// A terminal screen in memory for testing live output. Bytes written to it
+// are interpreted the way a terminal would: text fills cells and wraps at
+// the edge, `\r`, `\n`, backspace and tabs move the cursor, and CSI
+// sequences move it, erase parts of the screen and set the style of what
+// follows. The screen scrolls up once the cursor passes the bottom row.
+// Other escape sequences are ignored.
+//
+// Clones share the same screen, so one can be handed to a `ProgressBar` or
+// `LiveRegion` and the other kept to look at what ended up on screen,
+// rather than at the redraws that got it there.
+#[derive(Clone)]
+pub struct VirtualTerminal {
+    screen: Arc<Mutex<Screen>>,
+}
+
+#[derive(Clone)]
+struct Cell {
+    // Empty for the second column of a wide character
+    text: String,
+    style: Style,
+}
+
+impl Cell {
+    fn blank() -> Self {
+        Cell {
+            text: " ".to_string(),
+            style: Style::new(),
+        }
+    }
+}
+
+struct Screen {
+    columns: usize,
+    grid: Vec<Vec<Cell>>,
+    row: usize,
+    column: usize,
+    saved: (usize, usize),
+    style: Style,
+    cursor_visible: bool,
+    // The start of an escape sequence or character split across writes
+    pending: Vec<u8>,
+}
+
+impl VirtualTerminal {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        let columns = columns.max(1);
+        VirtualTerminal {
+            screen: Arc::new(Mutex::new(Screen {
+                columns,
+                grid: vec![vec![Cell::blank(); columns]; rows.max(1)],
+                row: 0,
+                column: 0,
+                saved: (0, 0),
+                style: Style::new(),
+                cursor_visible: true,
+                pending: Vec::new(),
+            })),
+        }
+    }
+
+    // The text on screen, one line per row with trailing spaces and blank
+    // rows at the bottom left out
+    pub fn text(&self) -> String {
+        let screen = self.lock();
+        let mut lines: Vec<String> = (0..screen.grid.len())
+            .map(|row| screen.line(row).trim_end().to_string())
+            .collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+
+    // The text of one row, padded with spaces to the full width
+    pub fn line(&self, row: usize) -> String {
+        let screen = self.lock();
+        if row < screen.grid.len() {
+            screen.line(row)
+        } else {
+            String::new()
+        }
+    }
+
+    // One row with its styles, trailing blank cells left out
+    pub fn styled_line(&self, row: usize) -> StyledText {
+        let screen = self.lock();
+        let mut text = StyledText::new();
+        let Some(cells) = screen.grid.get(row) else {
+            return text;
+        };
+        let end = cells
+            .iter()
+            .rposition(|cell| cell.text != " " || cell.style != Style::new())
+            .map_or(0, |last| last + 1);
+        let mut run = String::new();
+        let mut style = Style::new();
+        for cell in &cells[..end] {
+            if !same_style(&cell.style, &style) {
+                if !run.is_empty() {
+                    text.push(Console::new_with_style(std::mem::take(&mut run), style));
+                }
+                style = cell.style.clone();
+            }
+            run.push_str(&cell.text);
+        }
+        if !run.is_empty() {
+            text.push(Console::new_with_style(run, style));
+        }
+        text
+    }
+
+    // The style of the cell at `row` and `column`, from 0
+    pub fn style_at(&self, row: usize, column: usize) -> Option<Style> {
+        let screen = self.lock();
+        let cell = screen.grid.get(row)?.get(column)?;
+        Some(cell.style.clone())
+    }
+
+    // The cursor's row and column, from 0
+    pub fn cursor(&self) -> (usize, usize) {
+        let screen = self.lock();
+        (screen.row, screen.column)
+    }
+
+    pub fn cursor_visible(&self) -> bool {
+        self.lock().cursor_visible
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Screen> {
+        self.screen.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl io::Write for VirtualTerminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().feed(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Screen {
+    fn line(&self, row: usize) -> String {
+        self.grid[row]
+            .iter()
+            .map(|cell| cell.text.as_str())
+            .collect()
+    }
+
+    fn rows(&self) -> usize {
+        self.grid.len()
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+        let pending = std::mem::take(&mut self.pending);
+        let (text, rest) = match std::str::from_utf8(&pending) {
+            Ok(text) => (text.to_string(), &[][..]),
+            Err(e) => {
+                let (valid, rest) = pending.split_at(e.valid_up_to());
+                let mut text = String::from_utf8_lossy(valid).into_owned();
+                match e.error_len() {
+                    // Invalid bytes show as a replacement character
+                    Some(len) => {
+                        text.push('\u{fffd}');
+                        self.interpret(&text);
+                        self.feed(&rest[len..]);
+                        return;
+                    }
+                    // A character split across writes
+                    None => (text, rest),
+                }
+            }
+        };
+        let used = self.interpret(&text);
+        self.pending = text.as_bytes()[used..].to_vec();
+        self.pending.extend_from_slice(rest);
+    }
+
+    // Interprets as much of `text` as is complete, returning how many bytes
+    // that was
+    fn interpret(&mut self, text: &str) -> usize {
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, ch)) = chars.next() {
+            match ch {
+                '\x1b' => match chars.next() {
+                    None => return start,
+                    // CSI: parameters, then a final byte in 0x40..=0x7e
+                    Some((_, '[')) => {
+                        let params_start = start + 2;
+                        let mut end = None;
+                        for (i, c) in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&c) {
+                                end = Some((i, c));
+                                break;
+                            }
+                        }
+                        let Some((end, command)) = end else {
+                            return start;
+                        };
+                        self.csi(&text[params_start..end], command);
+                    }
+                    // OSC: terminated by BEL or ESC \
+                    Some((_, ']')) => loop {
+                        match chars.next() {
+                            None => return start,
+                            Some((_, '\x07')) => break,
+                            Some((_, '\x1b')) => {
+                                if chars.next().is_none() {
+                                    return start;
+                                }
+                                break;
+                            }
+                            Some(_) => {}
+                        }
+                    },
+                    Some((_, '7')) => self.saved = (self.row, self.column),
+                    Some((_, '8')) => (self.row, self.column) = self.saved,
+                    Some(_) => {}
+                },
+                '\r' => self.column = 0,
+                '\n' => {
+                    self.column = 0;
+                    self.line_feed();
+                }
+                '\x08' => self.column = self.column.saturating_sub(1),
+                '\t' => self.column = ((self.column / 8 + 1) * 8).min(self.columns - 1),
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+        text.len()
+    }
+
+    fn put(&mut self, ch: char) {
+        let mut buf = [0; 4];
+        let text = ch.encode_utf8(&mut buf);
+        let width = unicode::width(text);
+        if width == 0 {
+            // Combining marks join the character before them
+            let column = self.column.saturating_sub(1);
+            let cell = &mut self.grid[self.row][column];
+            if !cell.text.is_empty() {
+                cell.text.push(ch);
+            }
+            return;
+        }
+        if self.column + width > self.columns {
+            self.column = 0;
+            self.line_feed();
+        }
+        let (row, column) = (self.row, self.column);
+        self.grid[row][column] = Cell {
+            text: text.to_string(),
+            style: self.style.clone(),
+        };
+        for offset in 1..width.min(self.columns - column) {
+            self.grid[row][column + offset] = Cell {
+                text: String::new(),
+                style: self.style.clone(),
+            };
+        }
+        // Like a terminal, the cursor waits at the last column until
+        // the next character wraps it
+        self.column = (column + width).min(self.columns);
+    }
+
+    fn line_feed(&mut self) {
+        if self.row + 1 < self.rows() {
+            self.row += 1;
+        } else {
+            self.grid.remove(0);
+            self.grid.push(vec![Cell::blank(); self.columns]);
+        }
+    }
+
+    fn csi(&mut self, params: &str, command: char) {
+        if command == 'm' {
+            self.style = apply_sgr(&self.style, params);
+            return;
+        }
+        if let Some(private) = params.strip_prefix('?') {
+            if private == "25" {
+                match command {
+                    'h' => self.cursor_visible = true,
+                    'l' => self.cursor_visible = false,
+                    _ => {}
+                }
+            }
+            return;
+        }
+        let numbers: Vec<usize> = params.split(';').map(|n| n.parse().unwrap_or(0)).collect();
+        let arg = |i: usize, default: usize| match numbers.get(i) {
+            Some(&0) | None => default,
+            Some(&n) => n,
+        };
+        let last_row = self.rows() - 1;
+        let last_column = self.columns - 1;
+        match command {
+            'A' => self.row = self.row.saturating_sub(arg(0, 1)),
+            'B' => self.row = (self.row + arg(0, 1)).min(last_row),
+            'C' => self.column = (self.column + arg(0, 1)).min(last_column),
+            'D' => self.column = self.column.min(last_column).saturating_sub(arg(0, 1)),
+            'E' => {
+                self.row = (self.row + arg(0, 1)).min(last_row);
+                self.column = 0;
+            }
+            'F' => {
+                self.row = self.row.saturating_sub(arg(0, 1));
+                self.column = 0;
+            }
+            'G' => self.column = (arg(0, 1) - 1).min(last_column),
+            'H' | 'f' => {
+                self.row = (arg(0, 1) - 1).min(last_row);
+                self.column = (arg(1, 1) - 1).min(last_column);
+            }
+            'J' => self.erase_display(arg(0, 0)),
+            'K' => self.erase_line(arg(0, 0)),
+            's' => self.saved = (self.row, self.column),
+            'u' => (self.row, self.column) = self.saved,
+            _ => {}
+        }
+    }
+
+    // 0 erases from the cursor to the end, 1 from the start to the cursor,
+    // 2 the whole row
+    fn erase_line(&mut self, mode: usize) {
+        let column = self.column.min(self.columns - 1);
+        let range = match mode {
+            0 => column..self.columns,
+            1 => 0..column + 1,
+            _ => 0..self.columns,
+        };
+        for cell in &mut self.grid[self.row][range] {
+            *cell = Cell::blank();
+        }
+    }
+
+    // Like `erase_line`, for the screen
+    fn erase_display(&mut self, mode: usize) {
+        let rows = match mode {
+            0 => self.row + 1..self.rows(),
+            1 => 0..self.row,
+            _ => 0..self.rows(),
+        };
+        for row in rows {
+            self.grid[row] = vec![Cell::blank(); self.columns];
+        }
+        if mode < 2 {
+            self.erase_line(mode);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, LiveRegion, ProgressBar};
+    use std::io::Write;
+
+    #[test]
+    fn test_text_wraps_and_scrolls() {
+        let mut terminal = VirtualTerminal::new(4, 2);
+        write!(terminal, "abcdef\nxy").unwrap();
+        assert_eq!(terminal.text(), "ef\nxy");
+        assert_eq!(terminal.cursor(), (1, 2));
+        assert_eq!(terminal.line(1), "xy  ");
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_wide_characters() {
+        let mut terminal = VirtualTerminal::new(4, 2);
+        write!(terminal, "日本語").unwrap();
+        assert_eq!(terminal.text(), "日本\n語");
+    }
+
+    #[test]
+    fn test_cursor_movement_and_erasing() {
+        let mut terminal = VirtualTerminal::new(10, 3);
+        write!(terminal, "one\ntwo\r\x1b[2K\x1b[1A\x1b[2Kfour").unwrap();
+        assert_eq!(terminal.text(), "four");
+        write!(terminal, "\x1b[3;2Hx\x1b[1;3H\x1b[K").unwrap();
+        assert_eq!(terminal.text(), "fo\n\n x");
+        write!(terminal, "\x1b[?25l").unwrap();
+        assert!(!terminal.cursor_visible());
+    }
+
+    #[test]
+    fn test_styles() {
+        let mut terminal = VirtualTerminal::new(10, 1);
+        write!(terminal, "a\x1b[31mbc\x1b[0md").unwrap();
+        assert_eq!(terminal.style_at(0, 0), Some(Style::new()));
+        assert_eq!(terminal.style_at(0, 1), Some(Style::new().fg(Color::RED)));
+        assert_eq!(
+            terminal.styled_line(0).to_string(),
+            format!("a{}d", Console::new("bc").red())
+        );
+    }
+
+    #[test]
+    fn test_split_writes() {
+        let mut terminal = VirtualTerminal::new(10, 1);
+        let bytes = "\x1b[32mé\x1b[0m".as_bytes();
+        for byte in bytes {
+            terminal.write_all(&[*byte]).unwrap();
+        }
+        assert_eq!(terminal.text(), "é");
+        assert_eq!(terminal.style_at(0, 0), Some(Style::new().fg(Color::GREEN)));
+    }
+
+    #[test]
+    fn test_live_output_shows_final_frame() {
+        let terminal = VirtualTerminal::new(20, 4);
+        let mut region = LiveRegion::new(terminal.clone());
+        region.draw(["first", "second"]).unwrap();
+        region.println("log").unwrap();
+        region.draw(["third"]).unwrap();
+        assert_eq!(terminal.text(), "log\nthird");
+
+        let terminal = VirtualTerminal::new(20, 2);
+        let bar = ProgressBar::with_writer(terminal.clone(), 4)
+            .width(4)
+            .template("{bar} {pos}/{len}".parse().unwrap());
+        bar.set_position(1);
+        bar.finish();
+        assert_eq!(terminal.text(), "████ 4/4");
+    }
+}
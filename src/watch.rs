@@ -0,0 +1,170 @@
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::draw_target::stdout_target;
+use crate::events::{Key, RawMode};
+use crate::theme::{Tone, theme};
+use crate::timestamp::clock_now;
+use crate::{AltScreen, Block, Console, ResizeWatcher, StyledText};
+
+// Runs a closure over and over and shows its latest output full-screen,
+// like `watch(1)` but keeping colors and only redrawing what changed.
+// A header shows the title and when the output was last refreshed.
+#[derive(Debug, Clone)]
+pub struct Watch {
+    interval: Duration,
+    title: Option<String>,
+    paths: Vec<PathBuf>,
+}
+
+impl Watch {
+    pub fn every(interval: Duration) -> Self {
+        Watch {
+            interval,
+            title: None,
+            paths: Vec::new(),
+        }
+    }
+
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    // Also refreshes as soon as the file at `path` changes, without waiting
+    // for the interval. Files are checked by modification time a few times
+    // a second.
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    // Shows `render`'s output on the alternate screen until the user
+    // presses `q` or escape. Any other key refreshes right away. Ctrl-C
+    // returns an `Interrupted` error. Keys are only read on unix
+    // terminals; elsewhere it runs until the process is stopped.
+    pub fn run<F, T>(&self, mut render: F) -> io::Result<()>
+    where
+        F: FnMut() -> T,
+        T: Display,
+    {
+        let raw = RawMode::enable().ok();
+        let resizes = ResizeWatcher::channel().ok();
+        let mut screen = AltScreen::enter(stdout_target())?;
+        loop {
+            let output = render().to_string();
+            let (columns, rows) = screen.size();
+            screen.draw(&self.frame(&output, &clock_now(), columns, rows))?;
+
+            let deadline = Instant::now() + self.interval;
+            let modified = self.modified();
+            loop {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() || self.modified() != modified {
+                    break;
+                }
+                match poll_key(raw.is_some(), left.min(Duration::from_millis(200)))? {
+                    Some(Key::Char('q') | Key::Escape) => return Ok(()),
+                    Some(Key::CtrlC) => return Err(io::ErrorKind::Interrupted.into()),
+                    Some(_) => break,
+                    None => {}
+                }
+                if let Some((columns, rows)) =
+                    resizes.as_ref().and_then(|(_, r)| r.try_iter().last())
+                {
+                    screen.resize(columns, rows);
+                    break;
+                }
+            }
+        }
+    }
+
+    // The title on the left and the refresh time on the right, a blank
+    // line, then the output
+    fn frame(&self, output: &str, clock: &str, columns: usize, rows: usize) -> Block {
+        let title = match &self.title {
+            Some(title) => format!("Every {:?}: {}", self.interval, title),
+            None => format!("Every {:?}", self.interval),
+        };
+        let used = crate::unicode::width(&title) + crate::unicode::width(clock);
+        let gap = columns.saturating_sub(used).max(1);
+        let header = StyledText::new()
+            .then(Console::new(title).bold())
+            .then(Console::repeat(' ', gap))
+            .then(Console::new_with_style(
+                clock,
+                theme().style(Tone::Muted).clone(),
+            ));
+        Block::new()
+            .line(header)
+            .line(StyledText::new())
+            .above(Block::from(crate::parse_ansi(output)))
+            .pad_to_rect(columns, rows)
+    }
+
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.paths
+            .iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+#[cfg(unix)]
+fn poll_key(raw: bool, timeout: Duration) -> io::Result<Option<Key>> {
+    if raw && crate::events::stdin_ready(timeout.as_millis() as i32)? {
+        return crate::events::read_key().map(Some);
+    }
+    if !raw {
+        std::thread::sleep(timeout);
+    }
+    Ok(None)
+}
+
+#[cfg(not(unix))]
+fn poll_key(_raw: bool, timeout: Duration) -> io::Result<Option<Key>> {
+    std::thread::sleep(timeout);
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(block: &Block) -> Vec<String> {
+        block.lines().iter().map(|line| line.plain()).collect()
+    }
+
+    #[test]
+    fn test_frame() {
+        let watch = Watch::every(Duration::from_secs(2)).title("df -h");
+        let frame = watch.frame("\x1b[31m/dev/sda1\x1b[0m 91%\n", "13:05:09", 32, 5);
+        assert_eq!(
+            plain(&frame),
+            [
+                "Every 2s: df -h         13:05:09",
+                "                                ",
+                "/dev/sda1 91%                   ",
+                "                                ",
+                "                                ",
+            ]
+        );
+        assert_eq!(
+            frame.lines()[2].segments()[0],
+            Console::new("/dev/sda1").red()
+        );
+    }
+
+    #[test]
+    fn test_modified() {
+        let path =
+            std::env::temp_dir().join(format!("pretty-console-watch-{}", std::process::id()));
+        let watch = Watch::every(Duration::from_secs(1)).path(&path);
+        assert_eq!(watch.modified(), [None]);
+        std::fs::write(&path, "v1").unwrap();
+        assert!(watch.modified()[0].is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+use crate::normalize::show_controls;
+use crate::unicode;
+use crate::{Block, Style, StyledText};
+
+// Word-wraps plain text to `width` columns. Existing line breaks are kept,
+// words longer than a line are split, and control characters are shown so
+// they can't throw off the measured widths.
+pub(crate) fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let text = show_controls(&text.replace("\r\n", "\n"));
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word = word;
+            let mut word_width = unicode::width(word);
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            while word_width > width {
+                // A cluster wider than the line still has to go somewhere
+                let split = match unicode::take_width(word, width).len() {
+                    0 => unicode::graphemes(word).next().map_or(word.len(), str::len),
+                    n => n,
+                };
+                line.push_str(&word[..split]);
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+                word = &word[split..];
+                word_width = unicode::width(word);
+            }
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+            line.push_str(word);
+            line_width += word_width;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// Reflows text that was already wrapped, and maybe colored, e.g. a
+// compiler's output, to `width` columns. Lines that were broken because the
+// next word didn't fit are joined back up, keeping the hanging indent of
+// their continuation lines; short lines, blank lines and changes of indent
+// are kept as they are. Styles carry over to the new lines.
+pub fn rewrap(input: &str, width: usize) -> String {
+    let width = width.max(1);
+    let text = crate::parse_ansi(input).expand_tabs(8);
+    let lines: Vec<(usize, Vec<StyledText>)> =
+        Block::from(text).lines().iter().map(split_words).collect();
+    let widths: Vec<usize> = lines
+        .iter()
+        .map(|(indent, words)| line_width(*indent, words))
+        .collect();
+    // The width the input was wrapped at, as near as can be told
+    let source_width = widths.iter().copied().max().unwrap_or(0);
+
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let (indent, first) = &lines[i];
+        let mut words = first.clone();
+        let mut hanging = None;
+        while let Some((next_indent, next)) = lines.get(i + 1) {
+            let Some(next_word) = next.first() else {
+                break;
+            };
+            let continues = match hanging {
+                None => !words.is_empty() && next_indent >= indent,
+                Some(hanging) => *next_indent == hanging,
+            };
+            // The next word would have fitted on this line if it hadn't
+            // been wrapped
+            let wrapped = widths[i] + 1 + next_word.width() > source_width;
+            if !(continues && wrapped) {
+                break;
+            }
+            hanging = Some(*next_indent);
+            words.extend(next.iter().cloned());
+            i += 1;
+        }
+        out.extend(fill(words, *indent, hanging.unwrap_or(*indent), width));
+        i += 1;
+    }
+    out.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A line's indent and its words, each keeping the styles of its characters
+fn split_words(line: &StyledText) -> (usize, Vec<StyledText>) {
+    let mut indent = 0;
+    let mut words = Vec::new();
+    let mut word = StyledText::new();
+    for (cluster, style) in line.graphemes() {
+        if !cluster.chars().all(char::is_whitespace) {
+            word.push_styled(cluster, style);
+        } else if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        } else if words.is_empty() {
+            indent += unicode::width(cluster);
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    (indent, words)
+}
+
+fn line_width(indent: usize, words: &[StyledText]) -> usize {
+    let text: usize = words.iter().map(StyledText::width).sum();
+    indent + text + words.len().saturating_sub(1)
+}
+
+// Lays words out greedily, the first line at `indent` and the rest at
+// `hanging`, splitting words longer than a line
+fn fill(words: Vec<StyledText>, indent: usize, hanging: usize, width: usize) -> Vec<StyledText> {
+    let start = |indent: usize| {
+        let mut line = StyledText::new();
+        line.push_styled(&" ".repeat(indent), &Style::new());
+        (line, indent, true)
+    };
+    let mut lines = Vec::new();
+    let (mut line, mut used, mut empty) = start(indent);
+    for mut word in words {
+        if !empty && used + 1 + word.width() > width {
+            lines.push(line);
+            (line, used, empty) = start(hanging);
+        }
+        if !empty {
+            // A space between two words of the same style takes it too, so
+            // e.g. an underline isn't broken up
+            let before = line.segments().last().map(|s| s.style.clone());
+            let after = word.segments().first().map(|s| s.style.clone());
+            let style = match before {
+                Some(style) if Some(&style) == after.as_ref() => style,
+                _ => Style::new(),
+            };
+            line.push_styled(" ", &style);
+            used += 1;
+        }
+        while used + word.width() > width {
+            let room = width.saturating_sub(used).max(1);
+            let (head, tail) = word.split_at_visible(room);
+            if head.is_empty() {
+                break;
+            }
+            append(&mut line, &head);
+            lines.push(line);
+            (line, used, _) = start(hanging);
+            word = tail;
+        }
+        used += word.width();
+        append(&mut line, &word);
+        empty = false;
+    }
+    lines.push(line);
+    lines
+}
+
+// Adds `text` to the end of `line`, merging it into the last run if it
+// starts in the same style
+fn append(line: &mut StyledText, text: &StyledText) {
+    for (cluster, style) in text.graphemes() {
+        line.push_styled(cluster, style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Console;
+
+    #[test]
+    fn test_wrap_words() {
+        assert_eq!(
+            wrap_words("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap_words("a\n\nb", 10), vec!["a", "", "b"]);
+        assert_eq!(wrap_words("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_words("", 4), vec![""]);
+        assert_eq!(wrap_words("a\r\nb\x1bc", 10), vec!["a", "b^[c"]);
+        assert_eq!(
+            wrap_words("e\u{301}e\u{301}e\u{301}", 2),
+            vec!["e\u{301}e\u{301}", "e\u{301}"]
+        );
+    }
+
+    #[test]
+    fn test_rewrap_joins_wrapped_lines() {
+        let input =
+            "warning: unused variable `x` in\n  this function body\n\nnote: short line\n  indented";
+        assert_eq!(
+            rewrap(input, 40),
+            "warning: unused variable `x` in this\n  function body\n\nnote: short line\n  indented"
+        );
+        assert_eq!(rewrap("one two three\nfour", 6), "one\ntwo\nthree\nfour");
+        assert_eq!(rewrap("abcdefgh", 3), "abc\ndef\ngh");
+    }
+
+    #[test]
+    fn test_rewrap_keeps_styles() {
+        let input = "\x1b[31merror: aa bb\x1b[0m";
+        let expected = format!(
+            "{}\n{}",
+            Console::new("error: aa").red(),
+            Console::new("bb").red()
+        );
+        assert_eq!(rewrap(input, 10), expected);
+    }
+}